@@ -1,6 +1,6 @@
 use std::cell::RefCell;
 use std::rc::Rc;
-use crate::{CircleProperty, EllipseProperty, Graphic, RectProperty, TextProperty};
+use crate::{CircleProperty, EllipseProperty, Graphic, Path, PathSide, RectProperty, TextProperty};
 use crate::properties::LineProperty;
 
 
@@ -43,6 +43,11 @@ impl Context {
     pub fn draw_text(&self, text_property: TextProperty) {
         self.graphic.borrow().draw_text(text_property).unwrap();
     }
+
+    /// 把文字放在路径上，offset 为第一个字符距离路径开头的长度，side 决定文字在路径的哪一侧
+    pub fn draw_text_on_path(&self, text_property: TextProperty, path: &Path, offset: f32, side: PathSide) {
+        self.graphic.borrow().draw_text_on_path(text_property, path, offset, side).unwrap();
+    }
 }
 
 impl Drop for Context {
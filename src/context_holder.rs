@@ -1,7 +1,7 @@
 use std::cell::RefCell;
 use std::rc::Rc;
-use windows::Win32::Graphics::DirectWrite::{DWRITE_LINE_METRICS, DWRITE_TEXT_METRICS};
-use crate::{Context, TextLayoutInfo, TextProperty};
+use windows::core::Result;
+use crate::{Context, FontCollection, TextDefaults, TextLayoutInfo, TextProperty};
 use crate::Graphic;
 
 pub struct ContextHolder {
@@ -25,20 +25,30 @@ impl ContextHolder {
         self.graphic.borrow_mut().resize().unwrap();
     }
 
+    /// 注册后可以通过 TextProperty::font_family 使用其中的字体，同名字体族优先使用先注册的
+    pub fn register_font_collection(&self, font_collection: FontCollection) {
+        self.graphic.borrow_mut().text_resources().register_font_collection(font_collection).unwrap();
+    }
+
+    pub fn text_defaults(&self) -> TextDefaults {
+        self.graphic.borrow_mut().text_resources().defaults().clone()
+    }
+
+    /// 设置 TextProperty 没有指定字体和字号时使用的默认值及回退字体
+    pub fn set_text_defaults(&self, text_defaults: TextDefaults) {
+        self.graphic.borrow_mut().text_resources().set_defaults(text_defaults).unwrap();
+    }
+
     pub fn create_text_layout(&self, text_property: TextProperty) -> TextLayoutInfo {
+        self.try_create_text_layout(text_property).unwrap()
+    }
+
+    pub(crate) fn try_create_text_layout(&self, text_property: TextProperty) -> Result<TextLayoutInfo> {
         unsafe {
-            let layout = self.graphic.borrow().create_text_layout(text_property).unwrap();
-            let mut metrics = DWRITE_TEXT_METRICS::default();
-            layout.GetMetrics(&mut metrics).unwrap();
-            let mut line_count = metrics.lineCount;
-            let mut raw_line_metrics = vec![DWRITE_LINE_METRICS::default(); line_count as usize];
-            layout.GetLineMetrics(Some(raw_line_metrics.as_mut_slice()), &mut line_count).unwrap();
-
-            TextLayoutInfo {
-                layout,
-                metrics,
-                raw_line_metrics
-            }
+            let text = text_property.text.clone();
+            let paragraph_spacing = text_property.resolved_paragraph_spacing();
+            let layout = self.graphic.borrow().create_text_layout(text_property)?;
+            TextLayoutInfo::from_layout(layout, text, paragraph_spacing)
         }
     }
-}
\ No newline at end of file
+}
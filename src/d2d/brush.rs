@@ -1,4 +1,4 @@
-use windows::core::IntoParam;
+use windows::core::{IntoParam, IUnknown};
 use windows::core::Param;
 use windows::Win32::Graphics::Direct2D::{ID2D1BitmapBrush, ID2D1Brush, ID2D1LinearGradientBrush, ID2D1RadialGradientBrush, ID2D1SolidColorBrush};
 
@@ -42,4 +42,15 @@ impl IntoParam<ID2D1Brush> for &Brush {
             Brush::RadialGradient(brush) => brush.into_param(),
         }
     }
-}
\ No newline at end of file
+}
+
+impl IntoParam<IUnknown> for &Brush {
+    fn into_param(self) -> Param<IUnknown> {
+        match self {
+            Brush::Bitmap(brush) => brush.into_param(),
+            Brush::SolidColor(brush) => brush.into_param(),
+            Brush::LinearGradient(brush) => brush.into_param(),
+            Brush::RadialGradient(brush) => brush.into_param(),
+        }
+    }
+}
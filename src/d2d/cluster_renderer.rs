@@ -0,0 +1,147 @@
+use std::cell::RefCell;
+use std::ffi::c_void;
+use std::mem::ManuallyDrop;
+use std::rc::Rc;
+use windows::core::{implement, IUnknown, IntoParam, Result};
+use windows::Win32::Foundation::BOOL;
+use windows::Win32::Graphics::Direct2D::{ID2D1Brush, ID2D1DeviceContext};
+use windows::Win32::Graphics::DirectWrite::{DWRITE_GLYPH_OFFSET, DWRITE_GLYPH_RUN, DWRITE_GLYPH_RUN_DESCRIPTION, DWRITE_MATRIX, DWRITE_MEASURING_MODE, DWRITE_STRIKETHROUGH, DWRITE_UNDERLINE, IDWriteFontFace, IDWriteInlineObject, IDWritePixelSnapping_Impl, IDWriteTextLayout, IDWriteTextRenderer, IDWriteTextRenderer_Impl};
+use crate::Direct2DPoint;
+
+/// 一个字簇的字形，可以单独作为字形串绘制
+pub(crate) struct ClusterGlyphs {
+    /// 字簇第一个字符的 UTF-16 位置
+    pub(crate) position: u32,
+    /// 字形串的基线原点，从右到左的字形串在字簇右侧
+    origin: Direct2DPoint,
+    font_face: IDWriteFontFace,
+    font_em_size: f32,
+    is_sideways: BOOL,
+    bidi_level: u32,
+    measuring_mode: DWRITE_MEASURING_MODE,
+    indices: Vec<u16>,
+    advances: Vec<f32>,
+    offsets: Vec<DWRITE_GLYPH_OFFSET>,
+}
+
+impl ClusterGlyphs {
+    /// 在布局中原来的位置绘制，位置和方向由 context 当前的变换决定
+    pub(crate) unsafe fn draw(&self, context: &ID2D1DeviceContext, brush: impl IntoParam<ID2D1Brush>) {
+        let glyph_run = DWRITE_GLYPH_RUN {
+            fontFace: ManuallyDrop::new(Some(self.font_face.clone())),
+            fontEmSize: self.font_em_size,
+            glyphCount: self.indices.len() as u32,
+            glyphIndices: self.indices.as_ptr(),
+            glyphAdvances: self.advances.as_ptr(),
+            glyphOffsets: self.offsets.as_ptr(),
+            isSideways: self.is_sideways,
+            bidiLevel: self.bidi_level,
+        };
+        context.DrawGlyphRun(self.origin, &glyph_run, brush, self.measuring_mode);
+        ManuallyDrop::into_inner(glyph_run.fontFace);
+    }
+}
+
+/// 把布局中的字形串按字簇拆开的文字渲染器，下划线、删除线和内联对象不会被收集
+#[implement(IDWriteTextRenderer)]
+struct ClusterRenderer {
+    clusters: Rc<RefCell<Vec<ClusterGlyphs>>>,
+}
+
+impl IDWritePixelSnapping_Impl for ClusterRenderer {
+    fn IsPixelSnappingDisabled(&self, _: *const c_void) -> Result<BOOL> {
+        Ok(true.into())
+    }
+
+    fn GetCurrentTransform(&self, _: *const c_void, transform: *mut DWRITE_MATRIX) -> Result<()> {
+        unsafe {
+            *transform = DWRITE_MATRIX {
+                m11: 1.0,
+                m12: 0.0,
+                m21: 0.0,
+                m22: 1.0,
+                dx: 0.0,
+                dy: 0.0,
+            };
+        }
+        Ok(())
+    }
+
+    fn GetPixelsPerDip(&self, _: *const c_void) -> Result<f32> {
+        Ok(1.0)
+    }
+}
+
+impl IDWriteTextRenderer_Impl for ClusterRenderer {
+    fn DrawGlyphRun(&self, _: *const c_void, baseline_origin_x: f32, baseline_origin_y: f32, measuring_mode: DWRITE_MEASURING_MODE, glyph_run: *const DWRITE_GLYPH_RUN, glyph_run_description: *const DWRITE_GLYPH_RUN_DESCRIPTION, _: Option<&IUnknown>) -> Result<()> {
+        let (glyph_run, description) = unsafe { (&*glyph_run, &*glyph_run_description) };
+        let Some(font_face) = glyph_run.fontFace.as_ref() else {
+            return Ok(());
+        };
+        let count = glyph_run.glyphCount as usize;
+        if count == 0 || description.clusterMap.is_null() {
+            return Ok(());
+        }
+        let indices = unsafe { std::slice::from_raw_parts(glyph_run.glyphIndices, count) };
+        let advances = unsafe { std::slice::from_raw_parts(glyph_run.glyphAdvances, count) };
+        let offsets = match glyph_run.glyphOffsets.is_null() {
+            true => vec![DWRITE_GLYPH_OFFSET::default(); count],
+            false => unsafe { std::slice::from_raw_parts(glyph_run.glyphOffsets, count) }.to_vec(),
+        };
+        let cluster_map = unsafe { std::slice::from_raw_parts(description.clusterMap, description.stringLength as usize) };
+        //字形按逻辑顺序排列，从右到左的字形串从基线原点向左前进
+        let direction = if glyph_run.bidiLevel % 2 == 1 { -1.0 } else { 1.0 };
+        let mut x = baseline_origin_x;
+        let mut clusters = self.clusters.borrow_mut();
+        for (char_index, glyph_start) in cluster_map.iter().enumerate() {
+            //同一字簇的字符映射到同一个起始字形
+            if char_index > 0 && cluster_map[char_index - 1] == *glyph_start {
+                continue;
+            }
+            let glyph_start = *glyph_start as usize;
+            let glyph_end = cluster_map[char_index..].iter()
+                .map(|glyph| *glyph as usize)
+                .find(|glyph| *glyph > glyph_start)
+                .unwrap_or(count)
+                .min(count);
+            if glyph_start >= glyph_end {
+                continue;
+            }
+            clusters.push(ClusterGlyphs {
+                position: description.textPosition + char_index as u32,
+                origin: Direct2DPoint { x, y: baseline_origin_y },
+                font_face: font_face.clone(),
+                font_em_size: glyph_run.fontEmSize,
+                is_sideways: glyph_run.isSideways,
+                bidi_level: glyph_run.bidiLevel,
+                measuring_mode,
+                indices: indices[glyph_start..glyph_end].to_vec(),
+                advances: advances[glyph_start..glyph_end].to_vec(),
+                offsets: offsets[glyph_start..glyph_end].to_vec(),
+            });
+            x += direction * advances[glyph_start..glyph_end].iter().sum::<f32>();
+        }
+        Ok(())
+    }
+
+    fn DrawUnderline(&self, _: *const c_void, _: f32, _: f32, _: *const DWRITE_UNDERLINE, _: Option<&IUnknown>) -> Result<()> {
+        Ok(())
+    }
+
+    fn DrawStrikethrough(&self, _: *const c_void, _: f32, _: f32, _: *const DWRITE_STRIKETHROUGH, _: Option<&IUnknown>) -> Result<()> {
+        Ok(())
+    }
+
+    fn DrawInlineObject(&self, _: *const c_void, _: f32, _: f32, _: Option<&IDWriteInlineObject>, _: BOOL, _: BOOL, _: Option<&IUnknown>) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// 布局中每个字簇的字形，坐标为布局原点在 (0, 0) 时的位置
+pub(crate) unsafe fn layout_cluster_glyphs(text_layout: &IDWriteTextLayout) -> Result<Vec<ClusterGlyphs>> {
+    let clusters = Rc::new(RefCell::new(vec![]));
+    let renderer: IDWriteTextRenderer = ClusterRenderer { clusters: clusters.clone() }.into();
+    text_layout.Draw(None, &renderer, 0.0, 0.0)?;
+    drop(renderer);
+    Ok(clusters.take())
+}
@@ -1,7 +1,8 @@
-use windows::Win32::Graphics::Direct2D::{ID2D1Factory1, ID2D1PathGeometry};
+use windows::Win32::Graphics::Direct2D::{D2D1_QUADRATIC_BEZIER_SEGMENT, ID2D1Factory1, ID2D1PathGeometry};
 use crate::d2d::GeometrySink;
 use windows::core::{Result};
-use windows::Win32::Graphics::Direct2D::Common::D2D1_FILL_MODE_WINDING;
+use windows::Win32::Graphics::Direct2D::Common::{D2D1_BEZIER_SEGMENT, D2D1_FIGURE_BEGIN_FILLED, D2D1_FIGURE_END_CLOSED, D2D1_FIGURE_END_OPEN, D2D1_FILL_MODE_WINDING};
+use crate::{Path, PathSegment, Point};
 
 pub struct Factory {
     factory: ID2D1Factory1,
@@ -24,6 +25,67 @@ impl Factory {
             Ok(shape)
         }
     }
+
+    /// 由 Path 创建路径几何，每个子路径是一个图形，未闭合的子路径在下一个 MoveTo 或结尾处结束
+    /// 与 PathMeasure 相同，第一个 MoveTo 之前的线段从原点开始，Close 之后的线段从该图形的起点开始
+    pub fn create_path(&self, path: &Path) -> Result<ID2D1PathGeometry> {
+        unsafe {
+            let shape = self.factory.CreatePathGeometry()?;
+            let sink = shape.Open()?;
+            sink.SetFillMode(D2D1_FILL_MODE_WINDING);
+            let mut figure_open = false;
+            let mut current = Point::default();
+            let mut figure_start = current;
+            for segment in path.segments() {
+                if !figure_open && !matches!(segment, PathSegment::MoveTo(_) | PathSegment::Close) {
+                    sink.BeginFigure(*current, D2D1_FIGURE_BEGIN_FILLED);
+                    figure_open = true;
+                }
+                match *segment {
+                    PathSegment::MoveTo(point) => {
+                        if figure_open {
+                            sink.EndFigure(D2D1_FIGURE_END_OPEN);
+                        }
+                        sink.BeginFigure(*point, D2D1_FIGURE_BEGIN_FILLED);
+                        figure_open = true;
+                        figure_start = point;
+                        current = point;
+                    }
+                    PathSegment::LineTo(point) => {
+                        sink.AddLine(*point);
+                        current = point;
+                    }
+                    PathSegment::QuadTo(control, point) => {
+                        sink.AddQuadraticBezier(&D2D1_QUADRATIC_BEZIER_SEGMENT {
+                            point1: *control,
+                            point2: *point,
+                        });
+                        current = point;
+                    }
+                    PathSegment::CubicTo(control1, control2, point) => {
+                        sink.AddBezier(&D2D1_BEZIER_SEGMENT {
+                            point1: *control1,
+                            point2: *control2,
+                            point3: *point,
+                        });
+                        current = point;
+                    }
+                    PathSegment::Close => {
+                        if figure_open {
+                            sink.EndFigure(D2D1_FIGURE_END_CLOSED);
+                        }
+                        figure_open = false;
+                        current = figure_start;
+                    }
+                }
+            }
+            if figure_open {
+                sink.EndFigure(D2D1_FIGURE_END_OPEN);
+            }
+            sink.Close()?;
+            Ok(shape)
+        }
+    }
 }
 
 impl std::ops::Deref for Factory {
@@ -1,22 +1,21 @@
-use std::iter::once;
-use windows::core::{ComInterface, Error, PCWSTR, Result};
-use windows::core::w;
+use windows::core::{Error, Result};
+use windows::Foundation::Numerics::Matrix3x2;
 use windows::Win32::Foundation::HWND;
-use windows::Win32::Graphics::Direct2D::{D2D1_DRAW_TEXT_OPTIONS_NO_SNAP, D2D1_ELLIPSE, D2D1_LAYER_PARAMETERS, ID2D1PathGeometry};
+use windows::Win32::Graphics::Direct2D::{D2D1_BITMAP_INTERPOLATION_MODE_LINEAR, D2D1_CAP_STYLE_FLAT, D2D1_DASH_STYLE_SOLID, D2D1_STROKE_STYLE_PROPERTIES, D2D1_DRAW_TEXT_OPTIONS_CLIP, D2D1_ROUNDED_RECT, D2D1_DRAW_TEXT_OPTIONS_NO_SNAP, D2D1_ELLIPSE, D2D1_LAYER_PARAMETERS, ID2D1PathGeometry};
 use windows::Win32::Graphics::DirectComposition::{IDCompositionDevice, IDCompositionTarget, IDCompositionVisual};
-use windows::Win32::Graphics::DirectWrite::{DWRITE_LINE_METRICS, DWRITE_LINE_SPACING_METHOD_UNIFORM, DWRITE_PARAGRAPH_ALIGNMENT_CENTER, DWRITE_PARAGRAPH_ALIGNMENT_FAR, DWRITE_PARAGRAPH_ALIGNMENT_NEAR, DWRITE_TEXT_ALIGNMENT_CENTER, DWRITE_TEXT_ALIGNMENT_LEADING, DWRITE_TEXT_ALIGNMENT_TRAILING, DWRITE_TEXT_METRICS, DWRITE_TEXT_RANGE, IDWriteFactory2, IDWriteTextFormat1, IDWriteTextLayout};
+use windows::Win32::Graphics::DirectWrite::{DWRITE_TEXT_METRICS, IDWriteTextLayout};
 use windows::Win32::Graphics::Dxgi::Common::DXGI_FORMAT_B8G8R8A8_UNORM;
 use windows::Win32::Graphics::Dxgi::IDXGISwapChain1;
-use crate::{CircleProperty, Color, EllipseProperty, GradientColorProperty, LinearGradientProperty, LineProperty, RadialGradientProperty, RectProperty, TextAlign, TextProperty, TextVerticalAlign};
-use crate::d2d::{create_write_factory, Factory};
-use crate::{create_point, Direct2DPoint, Direct2DRect};
+use crate::{CircleProperty, Color, CssRadialGradient, EllipseProperty, GradientColorProperty, GradientUnits, ImageGradientProperty, InlineBoxContent, LinearGradientProperty, LineProperty, RadialExtent, RadialGradientProperty, RadialShape, RectProperty, TextProperty};
+use crate::d2d::{create_write_factory, decoration_spans, first_line_clusters, get_line_metrics, hit_test_text_range, layout_cluster_glyphs, text_outline, Factory, InlineDrawing, InlineObject, TextResources};
+use crate::{create_point, place_clusters, to_text_range, Direct2DPoint, Direct2DRect, PaintOrder, Path, PathSide, TextStroke, TextWrap};
 use super::RenderTarget;
 use super::{create_composition_device, create_composition_target, create_device, create_dxgi_factory, create_factory, create_render_context, create_swap_chain, create_swap_chain_bitmap, create_visual, get_dpi, get_window_size};
 
 pub struct Graphic {
     handle: HWND,
     factory: Factory,
-    write_factory: IDWriteFactory2,
+    text_resources: TextResources,
     render_target: RenderTarget,
     swap_chain: IDXGISwapChain1,
     comp_device: IDCompositionDevice,
@@ -49,7 +48,7 @@ impl Graphic {
         Ok(Self {
             handle,
             factory: Factory::new(factory),
-            write_factory,
+            text_resources: TextResources::new(write_factory)?,
             render_target: RenderTarget::new(context),
             swap_chain,
             comp_device,
@@ -86,123 +85,208 @@ impl Graphic {
 // draw shape
 
 impl Graphic {
-    unsafe fn get_baseline(text_layout: &IDWriteTextLayout) -> Result<f32> {
-        let mut text_metrics = DWRITE_TEXT_METRICS::default();
-        text_layout.GetMetrics(&mut text_metrics)?;
-        let mut line_count = text_metrics.lineCount;
-        let mut raw_line_metrics = vec![DWRITE_LINE_METRICS::default(); line_count as usize];
-        let result = text_layout.GetLineMetrics(Some(raw_line_metrics.as_mut_slice()), &mut line_count);
-
-        if result.is_err() {
-            let mut raw_line_metrics = vec![DWRITE_LINE_METRICS::default(); line_count as usize];
-            let result = text_layout.GetLineMetrics(Some(raw_line_metrics.as_mut_slice()), &mut line_count);
-            if result.is_err() {
-                return Ok(0.0);
-            }
-        }
+    pub(crate) unsafe fn create_text_layout(&self, text_property: TextProperty) -> Result<IDWriteTextLayout> {
+        self.text_resources.create_text_layout(text_property)
+    }
 
-        Ok(raw_line_metrics.get(0).map(|lm| lm.baseline).unwrap_or(0.0))
+    pub(crate) fn text_resources(&mut self) -> &mut TextResources {
+        &mut self.text_resources
     }
 
-    pub(crate) unsafe fn create_text_layout(&self, text_property: TextProperty) -> Result<IDWriteTextLayout> {
-        let text = text_property.text.as_ref().encode_utf16().chain(once(0)).collect::<Vec<u16>>();
-        let fallback = self.write_factory.GetSystemFontFallback()?;
-        let font_family = match &text_property.font_family {
-            Some(font_family) => PCWSTR::from_raw(font_family.as_ref().encode_utf16().chain(once(0)).collect::<Vec<u16>>().as_ptr()),
-            _ => w!("Microsoft YaHei"),
-        };
-        let text_format = self.write_factory.CreateTextFormat(
-            font_family,
-            None,
-            text_property.font_weight.into(),
-            text_property.font_style.into(),
-            text_property.font_stretch.into(),
-            text_property.font_size,
-            w!(""),
-        )?;
-        let text_format = text_format.cast::<IDWriteTextFormat1>()?;
-        text_format.SetFontFallback(Some(&fallback))?;
-        text_format.SetParagraphAlignment(DWRITE_PARAGRAPH_ALIGNMENT_CENTER)?;
-        let text_layout = self.write_factory.CreateTextLayout(text.as_slice(), &text_format, f32::MAX, f32::MAX)?;
-
-        if let Some(line_height) = text_property.line_height {
-            let baseline = Self::get_baseline(&text_layout)?;
-            text_layout.SetLineSpacing(DWRITE_LINE_SPACING_METHOD_UNIFORM, text_property.font_size * line_height, baseline)?;
-        }
+    pub(crate) fn draw_text(&self, text_property: TextProperty) -> Result<()> {
+        unsafe {
+            let context = &self.render_target;
 
-        if text_property.underline {
-            text_layout.SetUnderline(true, DWRITE_TEXT_RANGE { startPosition: 0, length: text_property.text.as_ref().len() as u32 }).unwrap();
-        }
+            let text_color = text_property.color.clone();
+            let text_position = text_property.position.clone();
+            let text = text_property.text.clone();
+            let styles = text_property.resolved_styles();
+            let inline_boxes = text_property.inline_boxes.clone();
+            let decorations = text_property.decorations.clone();
+            let stroke = text_property.stroke.clone();
+            //限制行数时超出的行需要裁剪掉
+            let options = match text_property.max_lines {
+                Some(_) => D2D1_DRAW_TEXT_OPTIONS_NO_SNAP | D2D1_DRAW_TEXT_OPTIONS_CLIP,
+                None => D2D1_DRAW_TEXT_OPTIONS_NO_SNAP,
+            };
 
-        let align = match text_property.align {
-            TextAlign::Left => {
-                DWRITE_TEXT_ALIGNMENT_LEADING
-            }
-            TextAlign::Center => {
-                DWRITE_TEXT_ALIGNMENT_CENTER
-            }
-            TextAlign::Right => {
-                DWRITE_TEXT_ALIGNMENT_TRAILING
+            let text_layout = self.create_text_layout(text_property)?;
+            for inline_box in inline_boxes.iter() {
+                let Some(drawing) = self.create_inline_drawing(&inline_box.inline_box.content)? else {
+                    continue;
+                };
+                let inline_object = InlineObject::inline_box(&inline_box.inline_box.metrics, Some(drawing));
+                text_layout.SetInlineObject(&inline_object, to_text_range(&text, inline_box.range.clone()))?;
             }
-        };
-        text_layout.SetTextAlignment(align)?;
 
-        let vertical_align = match text_property.vertical_align {
-            TextVerticalAlign::Top => {
-                DWRITE_PARAGRAPH_ALIGNMENT_NEAR
-            }
-            TextVerticalAlign::Center => {
-                DWRITE_PARAGRAPH_ALIGNMENT_CENTER
-            }
-            TextVerticalAlign::Bottom => {
-                DWRITE_PARAGRAPH_ALIGNMENT_FAR
+            //文字的渐变以文字的实际范围作为图形范围，纯色文字不受影响
+            let mut text_metrics = DWRITE_TEXT_METRICS::default();
+            text_layout.GetMetrics(&mut text_metrics)?;
+            let point1 = create_point(text_position.x + text_metrics.left, text_position.y + text_metrics.top);
+            let point2 = create_point(point1.x + text_metrics.width, point1.y + text_metrics.height);
+
+            for styled_range in styles.iter() {
+                let range = to_text_range(&text, styled_range.range.clone());
+                //背景色需要在文字之前绘制
+                if let Some(background) = &styled_range.style.background {
+                    for rect in hit_test_text_range(&text_layout, range, text_position)? {
+                        let gradient_color_property = Self::build_gradient_color_property(background, create_point(rect.left, rect.top), create_point(rect.right, rect.bottom));
+                        let brush = context.create_brush(background.clone(), gradient_color_property);
+                        context.FillRectangle(&rect, &brush);
+                    }
+                }
+                //文字颜色通过绘制效果设置，DrawTextLayout 会使用它代替默认画刷
+                if let Some(color) = &styled_range.style.color {
+                    let gradient_color_property = Self::build_gradient_color_property(color, point1, point2);
+                    let brush = context.create_brush(color.clone(), gradient_color_property);
+                    text_layout.SetDrawingEffect(&brush, range)?;
+                }
             }
-        };
-
-        text_layout.SetParagraphAlignment(vertical_align)?;
-
-        //必须最后再设置宽高，因为其他属性的设置会导致宽高的变化
-        let mut text_metrics = DWRITE_TEXT_METRICS::default();
-
-        text_layout.GetMetrics(&mut text_metrics)?;
 
-        let max_width = text_property.width.unwrap_or(text_metrics.width);
-        text_layout.SetMaxWidth(max_width)?;
+            let gradient_color_property = Self::build_gradient_color_property(&text_color, point1, point2);
 
-        //设置宽度后需要重新获取
-        text_layout.GetMetrics(&mut text_metrics)?;
-
-        text_layout.SetMaxWidth(max_width.max(text_metrics.width))?;
-
-        let max_height = text_property.height.unwrap_or(text_metrics.height);
-        text_layout.SetMaxHeight(max_height.max(text_metrics.height))?;
+            let brush = context.create_brush(text_color.clone(), gradient_color_property);
+            match &stroke {
+                Some(stroke) if stroke.paint_order == PaintOrder::FillOverStroke => {
+                    self.draw_text_stroke(&text_layout, text_position, stroke, point1, point2)?;
+                    context.DrawTextLayout(text_position, &text_layout, &brush, options);
+                }
+                Some(stroke) => {
+                    context.DrawTextLayout(text_position, &text_layout, &brush, options);
+                    self.draw_text_stroke(&text_layout, text_position, stroke, point1, point2)?;
+                }
+                None => context.DrawTextLayout(text_position, &text_layout, &brush, options),
+            }
 
-        Ok(text_layout)
+            for decoration_range in decorations.iter() {
+                let decoration = &decoration_range.decoration;
+                let mut path = Path::new();
+                for span in decoration_spans(&text_layout, to_text_range(&text, decoration_range.range.clone()), text_position)? {
+                    path.append(&decoration.path(&span));
+                }
+                if path.is_empty() {
+                    continue;
+                }
+                let geometry = self.factory.create_path(&path)?;
+                let color = decoration.color.clone().unwrap_or_else(|| text_color.clone());
+                let gradient_color_property = Self::build_gradient_color_property(&color, point1, point2);
+                let brush = context.create_brush(color, gradient_color_property);
+                context.FillGeometry(&geometry, &brush, None);
+            }
+        }
+        Ok(())
     }
 
-    pub(crate) fn draw_text(&self, text_property: TextProperty) -> Result<()> {
+    /// 沿路径逐个字簇绘制文字，文字只排成一行，position、width、wrap 等排版属性会被忽略
+    /// styles 中的颜色和背景、装饰线和描边不会绘制
+    /// 每个字簇的字形单独作为字形串绘制，旋转到路径的切线方向
+    pub(crate) fn draw_text_on_path(&self, text_property: TextProperty, path: &Path, offset: f32, side: PathSide) -> Result<()> {
         unsafe {
             let context = &self.render_target;
-
             let text_color = text_property.color.clone();
-            let text_position = text_property.position.clone();
-
+            let text_property = TextProperty {
+                position: Direct2DPoint::default(),
+                width: None,
+                height: None,
+                wrap: TextWrap::None,
+                max_lines: None,
+                ..text_property
+            };
             let text_layout = self.create_text_layout(text_property)?;
+            let mut text_metrics = DWRITE_TEXT_METRICS::default();
+            text_layout.GetMetrics(&mut text_metrics)?;
+            let baseline = text_metrics.top + get_line_metrics(&text_layout)?.first().map(|line_metrics| line_metrics.baseline).unwrap_or_default();
 
-            let brush = context.create_brush(text_color, GradientColorProperty::None);
-            context.DrawTextLayout(text_position, &text_layout, &brush, D2D1_DRAW_TEXT_OPTIONS_NO_SNAP);
+            let clusters = first_line_clusters(&text_layout)?;
+            let advances = clusters.iter().map(|(_, width, _)| *width).collect::<Vec<f32>>();
+            let placements = place_clusters(&advances, path, offset, side);
+
+            let Some(bounds) = path.bounds() else {
+                return Ok(());
+            };
+            let gradient_color_property = Self::build_gradient_color_property(&text_color, create_point(bounds.left, bounds.top), create_point(bounds.right, bounds.bottom));
+            let brush = context.create_brush(text_color, gradient_color_property);
+
+            let glyphs = layout_cluster_glyphs(&text_layout)?;
+            let mut transform = Matrix3x2::default();
+            context.GetTransform(&mut transform);
+            for placement in placements.iter() {
+                let (left, _, position) = clusters[placement.index];
+                let (sin, cos) = placement.angle.to_radians().sin_cos();
+                let rotation = Matrix3x2 {
+                    M11: cos,
+                    M12: sin,
+                    M21: -sin,
+                    M22: cos,
+                    M31: 0.0,
+                    M32: 0.0,
+                };
+                let cluster_transform = Matrix3x2::translation(-left, -baseline) * rotation * Matrix3x2::translation(placement.origin.x, placement.origin.y) * transform;
+                context.SetTransform(&cluster_transform);
+
+                for cluster_glyphs in glyphs.iter().filter(|cluster_glyphs| cluster_glyphs.position == position) {
+                    cluster_glyphs.draw(context, &brush);
+                }
+            }
+            context.SetTransform(&transform);
         }
         Ok(())
     }
 
+    /// 沿文字轮廓描边，point1 和 point2 为文字区域的左上角和右下角
+    unsafe fn draw_text_stroke(&self, text_layout: &IDWriteTextLayout, text_position: Direct2DPoint, stroke: &TextStroke, point1: Direct2DPoint, point2: Direct2DPoint) -> Result<()> {
+        let geometry = self.factory.create_path(&text_outline(text_layout, text_position)?)?;
+        let stroke_style = self.factory.CreateStrokeStyle(&D2D1_STROKE_STYLE_PROPERTIES {
+            startCap: D2D1_CAP_STYLE_FLAT,
+            endCap: D2D1_CAP_STYLE_FLAT,
+            dashCap: D2D1_CAP_STYLE_FLAT,
+            lineJoin: stroke.join.into(),
+            miterLimit: 4.0,
+            dashStyle: D2D1_DASH_STYLE_SOLID,
+            dashOffset: 0.0,
+        }, None)?;
+        let gradient_color_property = Self::build_gradient_color_property(&stroke.color, point1, point2);
+        let brush = self.render_target.create_brush(stroke.color.clone(), gradient_color_property);
+        self.render_target.DrawGeometry(&geometry, &brush, stroke.width, &stroke_style);
+        Ok(())
+    }
+
+    /// 内联框的绘制内容，在 DrawTextLayout 绘制到内联框时调用
+    fn create_inline_drawing(&self, content: &InlineBoxContent) -> Result<Option<InlineDrawing>> {
+        let render_target = self.render_target.clone();
+        match content.clone() {
+            InlineBoxContent::Placeholder => Ok(None),
+            InlineBoxContent::Image(image) => {
+                let bitmap = render_target.create_bitmap(image.width, image.height, &image.colors())?;
+                Ok(Some(Box::new(move |rect| unsafe {
+                    render_target.DrawBitmap(&bitmap, Some(&rect), 1.0, D2D1_BITMAP_INTERPOLATION_MODE_LINEAR, None);
+                })))
+            }
+            InlineBoxContent::Fill { color, radius } => Ok(Some(Box::new(move |rect| unsafe {
+                let gradient_color_property = Self::build_gradient_color_property(&color, create_point(rect.left, rect.top), create_point(rect.right, rect.bottom));
+                let brush = render_target.create_brush(color.clone(), gradient_color_property);
+                render_target.FillRoundedRectangle(&D2D1_ROUNDED_RECT { rect, radiusX: radius, radiusY: radius }, &brush);
+            }))),
+        }
+    }
+
     pub(crate) fn draw_line(&self, line_property: LineProperty) -> Result<()> {
         unsafe {
             let context = &self.render_target;
             let start = *line_property.start.clone();
             let end = *line_property.end.clone();
             let width = line_property.width;
-            let gradient_color_property = LinearGradientProperty::new(*line_property.start.clone(), *line_property.end.clone());
-            let brush = context.create_brush(line_property.color.clone(), gradient_color_property.into());
+            let gradient_color_property = match &line_property.color {
+                //按角度定义的线性渐变沿线段方向绘制
+                Color::LinearGradient(_, _) => LinearGradientProperty::new(*line_property.start.clone(), *line_property.end.clone()).into(),
+                //其他渐变以线段的外接矩形作为图形范围
+                color => {
+                    let point1 = create_point(start.x.min(end.x), start.y.min(end.y));
+                    let point2 = create_point(start.x.max(end.x), start.y.max(end.y));
+                    Self::build_gradient_color_property(color, point1, point2)
+                }
+            };
+            let brush = context.create_brush(line_property.color.clone(), gradient_color_property);
             context.DrawLine(start, end, &brush, width, None);
         }
         Ok(())
@@ -394,7 +478,41 @@ impl Graphic {
                 println!("point1:{:?},point2:{:?}", center, offset);
                 RadialGradientProperty::new_circle(center, offset, radius).into()
             }
-            Color::SolidColor(_) => {
+            Color::LinearGradientGeometry(_, geometry) => {
+                let start = resolve_gradient_point(geometry.units, *geometry.start, point1, point2);
+                let end = resolve_gradient_point(geometry.units, *geometry.end, point1, point2);
+                LinearGradientProperty::new(start, end).into()
+            }
+            Color::RadialGradientGeometry(_, geometry) => {
+                let center = resolve_gradient_point(geometry.units, *geometry.center, point1, point2);
+                let (offset, radius_x, radius_y) = match geometry.units {
+                    GradientUnits::ObjectBoundingBox => {
+                        let width = point2.x - point1.x;
+                        let height = point2.y - point1.y;
+                        (
+                            create_point(geometry.offset.x * width, geometry.offset.y * height),
+                            geometry.radius_x * width,
+                            geometry.radius_y * height,
+                        )
+                    }
+                    GradientUnits::UserSpace => (*geometry.offset, geometry.radius_x, geometry.radius_y),
+                };
+                RadialGradientProperty::new(center, offset, radius_x, radius_y).into()
+            }
+            Color::CssLinearGradient(_, direction) => {
+                let angle = direction.angle(point2.x - point1.x, point2.y - point1.y);
+                let (start, end) = css_linear_gradient_start_end(point1, point2, angle);
+                LinearGradientProperty::new(start, end).into()
+            }
+            Color::CssRadialGradient(_, radial) => {
+                let center = resolve_gradient_point(GradientUnits::ObjectBoundingBox, *radial.position, point1, point2);
+                let (radius_x, radius_y) = css_radial_gradient_radius(radial, center, point1, point2);
+                RadialGradientProperty::new(center, create_point(0.0, 0.0), radius_x, radius_y).into()
+            }
+            Color::ConicGradient(_, _) => {
+                GradientColorProperty::ConicGradient(ImageGradientProperty::new(point1, point2.x - point1.x, point2.y - point1.y))
+            }
+            Color::SolidColor(_) | Color::Pattern(_) => {
                 GradientColorProperty::None
             }
         }
@@ -463,6 +581,72 @@ impl Drop for Graphic {
     }
 }
 
+/// 将渐变几何中的点转换到绘制坐标系
+/// point1: 图形的左上角
+/// point2: 图形的右下角
+fn resolve_gradient_point(units: GradientUnits, point: Direct2DPoint, point1: Direct2DPoint, point2: Direct2DPoint) -> Direct2DPoint {
+    match units {
+        GradientUnits::ObjectBoundingBox => create_point(
+            point1.x + point.x * (point2.x - point1.x),
+            point1.y + point.y * (point2.y - point1.y),
+        ),
+        GradientUnits::UserSpace => point,
+    }
+}
+
+/// 按 CSS `linear-gradient(<angle>)` 计算渐变色的起始点和结束点
+/// 渐变线经过中心，长度随角度变化，使得外接矩形的角刚好落在 0% 和 100% 处
+fn css_linear_gradient_start_end(point1: Direct2DPoint, point2: Direct2DPoint, angle: f32) -> (Direct2DPoint, Direct2DPoint) {
+    let width = point2.x - point1.x;
+    let height = point2.y - point1.y;
+    let center = create_point(point1.x + width / 2.0, point1.y + height / 2.0);
+    let (sin, cos) = angle.to_radians().sin_cos();
+    let half_length = (width * sin.abs() + height * cos.abs()) / 2.0;
+    //0度指向上方，y轴向下
+    let (dx, dy) = (sin * half_length, -cos * half_length);
+    (
+        create_point(center.x - dx, center.y - dy),
+        create_point(center.x + dx, center.y + dy),
+    )
+}
+
+/// 按 CSS `radial-gradient()` 的尺寸关键字计算 x、y 半径
+fn css_radial_gradient_radius(radial: &CssRadialGradient, center: Direct2DPoint, point1: Direct2DPoint, point2: Direct2DPoint) -> (f32, f32) {
+    //中心到左右、上下两边的距离
+    let (near_x, far_x) = {
+        let (left, right) = ((center.x - point1.x).abs(), (point2.x - center.x).abs());
+        (left.min(right), left.max(right))
+    };
+    let (near_y, far_y) = {
+        let (top, bottom) = ((center.y - point1.y).abs(), (point2.y - center.y).abs());
+        (top.min(bottom), top.max(bottom))
+    };
+
+    match (radial.shape, radial.extent) {
+        (RadialShape::Circle, RadialExtent::ClosestSide) => {
+            let radius = near_x.min(near_y);
+            (radius, radius)
+        }
+        (RadialShape::Circle, RadialExtent::FarthestSide) => {
+            let radius = far_x.max(far_y);
+            (radius, radius)
+        }
+        (RadialShape::Circle, RadialExtent::ClosestCorner) => {
+            let radius = near_x.hypot(near_y);
+            (radius, radius)
+        }
+        (RadialShape::Circle, RadialExtent::FarthestCorner) => {
+            let radius = far_x.hypot(far_y);
+            (radius, radius)
+        }
+        (RadialShape::Ellipse, RadialExtent::ClosestSide) => (near_x, near_y),
+        (RadialShape::Ellipse, RadialExtent::FarthestSide) => (far_x, far_y),
+        //经过角的椭圆保持与对应 side 关键字相同的长宽比
+        (RadialShape::Ellipse, RadialExtent::ClosestCorner) => (near_x * std::f32::consts::SQRT_2, near_y * std::f32::consts::SQRT_2),
+        (RadialShape::Ellipse, RadialExtent::FarthestCorner) => (far_x * std::f32::consts::SQRT_2, far_y * std::f32::consts::SQRT_2),
+    }
+}
+
 /// 根据圆心、半径、角度计算渐变色的起始点和结束点
 fn calc_gradient_start_end(center: Direct2DPoint, radius: f32, angle: f32) -> (Direct2DPoint, Direct2DPoint) {
     let angle = angle % 360.0;
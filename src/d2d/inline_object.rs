@@ -0,0 +1,77 @@
+use std::ffi::c_void;
+use windows::core::{implement, IUnknown, Result};
+use windows::Win32::Foundation::BOOL;
+use windows::Win32::Graphics::DirectWrite::{DWRITE_BREAK_CONDITION, DWRITE_BREAK_CONDITION_MUST_BREAK, DWRITE_BREAK_CONDITION_NEUTRAL, DWRITE_INLINE_OBJECT_METRICS, DWRITE_OVERHANG_METRICS, IDWriteInlineObject, IDWriteInlineObject_Impl, IDWriteTextRenderer};
+use crate::{Direct2DRect, InlineBoxMetrics};
+
+/// 绘制内联对象，参数为内联对象在绘制坐标系中的区域
+pub(crate) type InlineDrawing = Box<dyn Fn(Direct2DRect)>;
+
+/// 占据一块空间的内联对象，有 drawing 时在绘制文本时一起绘制
+#[implement(IDWriteInlineObject)]
+pub(crate) struct InlineObject {
+    metrics: DWRITE_INLINE_OBJECT_METRICS,
+    break_after: DWRITE_BREAK_CONDITION,
+    drawing: Option<InlineDrawing>,
+}
+
+impl InlineObject {
+    /// 替换换行符的占位对象，之后必须换行，用来撑开所在行的行高实现段落间距
+    /// baseline 和 height 应为所在行原本的基线位置和行高再加上间距，避免改变行内文字的位置
+    pub(crate) fn paragraph_break(height: f32, baseline: f32) -> IDWriteInlineObject {
+        InlineObject {
+            metrics: DWRITE_INLINE_OBJECT_METRICS {
+                width: 0.0,
+                height,
+                baseline,
+                supportsSideways: true.into(),
+            },
+            break_after: DWRITE_BREAK_CONDITION_MUST_BREAK,
+            drawing: None,
+        }.into()
+    }
+
+    /// 内联框，前后能否换行由相邻的文字决定
+    pub(crate) fn inline_box(metrics: &InlineBoxMetrics, drawing: Option<InlineDrawing>) -> IDWriteInlineObject {
+        InlineObject {
+            metrics: DWRITE_INLINE_OBJECT_METRICS {
+                width: metrics.width,
+                height: metrics.height,
+                baseline: metrics.baseline,
+                supportsSideways: true.into(),
+            },
+            break_after: DWRITE_BREAK_CONDITION_NEUTRAL,
+            drawing,
+        }.into()
+    }
+}
+
+impl IDWriteInlineObject_Impl for InlineObject {
+    fn Draw(&self, _: *const c_void, _: Option<&IDWriteTextRenderer>, origin_x: f32, origin_y: f32, _: BOOL, _: BOOL, _: Option<&IUnknown>) -> Result<()> {
+        if let Some(drawing) = &self.drawing {
+            drawing(Direct2DRect {
+                left: origin_x,
+                top: origin_y,
+                right: origin_x + self.metrics.width,
+                bottom: origin_y + self.metrics.height,
+            });
+        }
+        Ok(())
+    }
+
+    fn GetMetrics(&self) -> Result<DWRITE_INLINE_OBJECT_METRICS> {
+        Ok(self.metrics)
+    }
+
+    fn GetOverhangMetrics(&self) -> Result<DWRITE_OVERHANG_METRICS> {
+        Ok(DWRITE_OVERHANG_METRICS::default())
+    }
+
+    fn GetBreakConditions(&self, break_before: *mut DWRITE_BREAK_CONDITION, break_after: *mut DWRITE_BREAK_CONDITION) -> Result<()> {
+        unsafe {
+            *break_before = DWRITE_BREAK_CONDITION_NEUTRAL;
+            *break_after = self.break_after;
+        }
+        Ok(())
+    }
+}
@@ -4,6 +4,10 @@ mod render_target;
 mod brush;
 mod geometry_sink;
 mod factory;
+mod text_layout;
+mod inline_object;
+mod outline_renderer;
+mod cluster_renderer;
 
 pub(crate) use d2d_fun::*;
 pub(crate) use graphic::*;
@@ -11,3 +15,7 @@ pub(crate) use render_target::*;
 pub(crate) use brush::*;
 pub(crate) use geometry_sink::*;
 pub(crate) use factory::*;
+pub(crate) use text_layout::*;
+pub(crate) use inline_object::*;
+pub(crate) use outline_renderer::*;
+pub(crate) use cluster_renderer::*;
@@ -0,0 +1,149 @@
+use std::cell::RefCell;
+use std::ffi::c_void;
+use std::rc::Rc;
+use windows::core::{implement, IUnknown, Result};
+use windows::Win32::Foundation::BOOL;
+use windows::Win32::Graphics::Direct2D::Common::{D2D1_BEZIER_SEGMENT, D2D1_FIGURE_BEGIN, D2D1_FIGURE_END, D2D1_FIGURE_END_CLOSED, D2D1_FILL_MODE, D2D1_PATH_SEGMENT, D2D_POINT_2F, ID2D1SimplifiedGeometrySink, ID2D1SimplifiedGeometrySink_Impl};
+use windows::Win32::Graphics::DirectWrite::{DWRITE_GLYPH_RUN, DWRITE_GLYPH_RUN_DESCRIPTION, DWRITE_MATRIX, DWRITE_MEASURING_MODE, DWRITE_READING_DIRECTION_RIGHT_TO_LEFT, DWRITE_STRIKETHROUGH, DWRITE_UNDERLINE, IDWriteInlineObject, IDWritePixelSnapping_Impl, IDWriteTextLayout, IDWriteTextRenderer, IDWriteTextRenderer_Impl};
+use crate::{Direct2DPoint, Path, Point};
+
+/// 收集字形轮廓，所有点加上 offset
+#[implement(ID2D1SimplifiedGeometrySink)]
+struct PathSink {
+    path: Rc<RefCell<Path>>,
+    offset: (f32, f32),
+}
+
+impl PathSink {
+    fn point(&self, point: &D2D_POINT_2F) -> Point {
+        Point::new(point.x + self.offset.0, point.y + self.offset.1)
+    }
+}
+
+impl ID2D1SimplifiedGeometrySink_Impl for PathSink {
+    //字形轮廓总是使用非零环绕规则，与 Path 相同
+    fn SetFillMode(&self, _: D2D1_FILL_MODE) {}
+
+    fn SetSegmentFlags(&self, _: D2D1_PATH_SEGMENT) {}
+
+    fn BeginFigure(&self, start_point: &D2D_POINT_2F, _: D2D1_FIGURE_BEGIN) {
+        self.path.borrow_mut().move_to(self.point(start_point));
+    }
+
+    fn AddLines(&self, points: *const D2D_POINT_2F, points_count: u32) {
+        let points = unsafe { std::slice::from_raw_parts(points, points_count as usize) };
+        let mut path = self.path.borrow_mut();
+        for point in points {
+            path.line_to(self.point(point));
+        }
+    }
+
+    fn AddBeziers(&self, beziers: *const D2D1_BEZIER_SEGMENT, beziers_count: u32) {
+        let beziers = unsafe { std::slice::from_raw_parts(beziers, beziers_count as usize) };
+        let mut path = self.path.borrow_mut();
+        for bezier in beziers {
+            path.cubic_to(self.point(&bezier.point1), self.point(&bezier.point2), self.point(&bezier.point3));
+        }
+    }
+
+    fn EndFigure(&self, figure_end: D2D1_FIGURE_END) {
+        if figure_end == D2D1_FIGURE_END_CLOSED {
+            self.path.borrow_mut().close();
+        }
+    }
+
+    fn Close(&self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// 把布局中的字形、下划线和删除线转换为路径的文字渲染器，内联对象不会被转换
+#[implement(IDWriteTextRenderer)]
+struct OutlineRenderer {
+    path: Rc<RefCell<Path>>,
+}
+
+impl OutlineRenderer {
+    /// 从基线原点开始、沿阅读方向的矩形，offset 为矩形顶部相对于基线的偏移
+    fn add_line(&self, baseline_origin: (f32, f32), width: f32, thickness: f32, offset: f32, right_to_left: bool) {
+        let (x, y) = baseline_origin;
+        let (left, right) = if right_to_left { (x - width, x) } else { (x, x + width) };
+        self.path.borrow_mut().add_rect(left, y + offset, right, y + offset + thickness);
+    }
+}
+
+impl IDWritePixelSnapping_Impl for OutlineRenderer {
+    fn IsPixelSnappingDisabled(&self, _: *const c_void) -> Result<BOOL> {
+        Ok(true.into())
+    }
+
+    fn GetCurrentTransform(&self, _: *const c_void, transform: *mut DWRITE_MATRIX) -> Result<()> {
+        unsafe {
+            *transform = DWRITE_MATRIX {
+                m11: 1.0,
+                m12: 0.0,
+                m21: 0.0,
+                m22: 1.0,
+                dx: 0.0,
+                dy: 0.0,
+            };
+        }
+        Ok(())
+    }
+
+    fn GetPixelsPerDip(&self, _: *const c_void) -> Result<f32> {
+        Ok(1.0)
+    }
+}
+
+impl IDWriteTextRenderer_Impl for OutlineRenderer {
+    fn DrawGlyphRun(&self, _: *const c_void, baseline_origin_x: f32, baseline_origin_y: f32, _: DWRITE_MEASURING_MODE, glyph_run: *const DWRITE_GLYPH_RUN, _: *const DWRITE_GLYPH_RUN_DESCRIPTION, _: Option<&IUnknown>) -> Result<()> {
+        let glyph_run = unsafe { &*glyph_run };
+        let Some(font_face) = glyph_run.fontFace.as_ref() else {
+            return Ok(());
+        };
+        let sink: ID2D1SimplifiedGeometrySink = PathSink {
+            path: self.path.clone(),
+            offset: (baseline_origin_x, baseline_origin_y),
+        }.into();
+        unsafe {
+            font_face.GetGlyphRunOutline(
+                glyph_run.fontEmSize,
+                glyph_run.glyphIndices,
+                Some(glyph_run.glyphAdvances),
+                Some(glyph_run.glyphOffsets),
+                glyph_run.glyphCount,
+                glyph_run.isSideways,
+                BOOL::from(glyph_run.bidiLevel % 2 == 1),
+                &sink,
+            )
+        }
+    }
+
+    fn DrawUnderline(&self, _: *const c_void, baseline_origin_x: f32, baseline_origin_y: f32, underline: *const DWRITE_UNDERLINE, _: Option<&IUnknown>) -> Result<()> {
+        let underline = unsafe { &*underline };
+        let right_to_left = underline.readingDirection == DWRITE_READING_DIRECTION_RIGHT_TO_LEFT;
+        self.add_line((baseline_origin_x, baseline_origin_y), underline.width, underline.thickness, underline.offset, right_to_left);
+        Ok(())
+    }
+
+    fn DrawStrikethrough(&self, _: *const c_void, baseline_origin_x: f32, baseline_origin_y: f32, strikethrough: *const DWRITE_STRIKETHROUGH, _: Option<&IUnknown>) -> Result<()> {
+        let strikethrough = unsafe { &*strikethrough };
+        let right_to_left = strikethrough.readingDirection == DWRITE_READING_DIRECTION_RIGHT_TO_LEFT;
+        self.add_line((baseline_origin_x, baseline_origin_y), strikethrough.width, strikethrough.thickness, strikethrough.offset, right_to_left);
+        Ok(())
+    }
+
+    fn DrawInlineObject(&self, _: *const c_void, _: f32, _: f32, _: Option<&IDWriteInlineObject>, _: BOOL, _: BOOL, _: Option<&IUnknown>) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// 布局中所有文字的轮廓，坐标加上了 origin
+pub(crate) unsafe fn text_outline(text_layout: &IDWriteTextLayout, origin: Direct2DPoint) -> Result<Path> {
+    let path = Rc::new(RefCell::new(Path::new()));
+    let renderer: IDWriteTextRenderer = OutlineRenderer { path: path.clone() }.into();
+    text_layout.Draw(None, &renderer, origin.x, origin.y)?;
+    drop(renderer);
+    Ok(path.take())
+}
@@ -1,17 +1,41 @@
+use std::cell::RefCell;
 use std::ops::Deref;
+use std::rc::Rc;
 use windows::core::IntoParam;
-use windows::Win32::Graphics::Direct2D::{D2D1_EXTEND_MODE_CLAMP, D2D1_GAMMA_2_2, D2D1_LINEAR_GRADIENT_BRUSH_PROPERTIES, D2D1_RADIAL_GRADIENT_BRUSH_PROPERTIES, ID2D1DeviceContext, ID2D1Image};
+use windows::core::{Error, Result};
+use windows::Win32::Foundation::E_OUTOFMEMORY;
+use windows::Foundation::Numerics::Matrix3x2;
+use windows::Win32::Graphics::Direct2D::{D2D1_BITMAP_BRUSH_PROPERTIES, D2D1_BITMAP_INTERPOLATION_MODE_LINEAR, D2D1_BITMAP_PROPERTIES, D2D1_EXTEND_MODE, D2D1_EXTEND_MODE_CLAMP, D2D1_EXTEND_MODE_WRAP, D2D1_LINEAR_GRADIENT_BRUSH_PROPERTIES, D2D1_RADIAL_GRADIENT_BRUSH_PROPERTIES, ID2D1Bitmap, ID2D1BitmapBrush, ID2D1DeviceContext, ID2D1GradientStopCollection, ID2D1Image};
+use windows::Win32::Graphics::Direct2D::Common::{D2D1_ALPHA_MODE_PREMULTIPLIED, D2D1_PIXEL_FORMAT, D2D_SIZE_U};
+use windows::Win32::Graphics::Dxgi::Common::DXGI_FORMAT_B8G8R8A8_UNORM;
 use crate::d2d::Brush;
-use crate::{Color, GradientColorProperty};
+use crate::{BASE_DPI, Color, Direct2DGradientStop, Direct2DPoint, GradientColor, GradientColorProperty, Pattern, SolidColor};
 
+/// 逐像素生成的位图每边的最大像素数，渐变是平滑的，超出时生成较小的位图再拉伸
+const IMAGE_MAX_SIZE: u32 = 2048;
+/// 缓存的逐像素生成的位图数量
+const IMAGE_CACHE_CAPACITY: usize = 8;
+
+/// 逐像素生成的位图和生成时使用的参数
+struct ImageCacheEntry {
+    color: Color,
+    size: (f32, f32),
+    pixel_size: (u32, u32),
+    bitmap: ID2D1Bitmap,
+}
+
+#[derive(Clone)]
 pub struct RenderTarget {
     device_context: ID2D1DeviceContext,
+    /// 按使用顺序排列，最近使用的在最后
+    image_cache: Rc<RefCell<Vec<ImageCacheEntry>>>,
 }
 
 impl RenderTarget {
     pub fn new(device_context: ID2D1DeviceContext) -> Self {
         Self {
             device_context,
+            image_cache: Rc::new(RefCell::new(vec![])),
         }
     }
 
@@ -41,6 +65,155 @@ impl RenderTarget {
         }
     }
 
+    fn create_gradient_stop_collection(&self, color: &GradientColor) -> Result<ID2D1GradientStopCollection> {
+        let stops = color.expanded_stops().iter().map(|stop| **stop).collect::<Vec<Direct2DGradientStop>>();
+        unsafe {
+            self.CreateGradientStopCollection(stops.as_slice(), color.interpolation.into(), color.spread.into())
+        }
+    }
+
+    fn get_dpi(&self) -> (f32, f32) {
+        let (mut dpi_x, mut dpi_y) = (BASE_DPI, BASE_DPI);
+        unsafe {
+            self.device_context.GetDpi(&mut dpi_x, &mut dpi_y);
+        }
+        (dpi_x, dpi_y)
+    }
+
+    /// 用像素创建位图，像素按行排列且未预乘透明度
+    pub(crate) fn create_bitmap(&self, width: u32, height: u32, pixels: &[SolidColor]) -> Result<ID2D1Bitmap> {
+        let (dpi_x, dpi_y) = self.get_dpi();
+        let mut data = Vec::with_capacity(pixels.len() * 4);
+        for color in pixels {
+            let color = color.clamped().premultiply();
+            data.extend([color.b, color.g, color.r, color.a].map(|c| (c * 255.0).round() as u8));
+        }
+
+        unsafe {
+            self.device_context.CreateBitmap(
+                D2D_SIZE_U {
+                    width,
+                    height,
+                },
+                Some(data.as_ptr() as *const _),
+                width * 4,
+                &D2D1_BITMAP_PROPERTIES {
+                    pixelFormat: D2D1_PIXEL_FORMAT {
+                        format: DXGI_FORMAT_B8G8R8A8_UNORM,
+                        alphaMode: D2D1_ALPHA_MODE_PREMULTIPLIED,
+                    },
+                    dpiX: dpi_x,
+                    dpiY: dpi_y,
+                },
+            )
+        }
+    }
+
+    /// 用像素创建位图画刷，像素按行排列且未预乘透明度
+    fn create_bitmap_brush(&self, width: u32, height: u32, pixels: &[SolidColor], extend_mode: D2D1_EXTEND_MODE, transform: Matrix3x2) -> Result<ID2D1BitmapBrush> {
+        let bitmap = self.create_bitmap(width, height, pixels)?;
+        unsafe {
+            let brush = self.device_context.CreateBitmapBrush(
+                &bitmap,
+                Some(&D2D1_BITMAP_BRUSH_PROPERTIES {
+                    extendModeX: extend_mode,
+                    extendModeY: extend_mode,
+                    interpolationMode: D2D1_BITMAP_INTERPOLATION_MODE_LINEAR,
+                }),
+                None,
+            )?;
+            brush.SetTransform(&transform);
+            Ok(brush)
+        }
+    }
+
+    /// 逐像素生成覆盖 origin 开始、width x height 区域的图像画刷
+    /// fun 的参数为相对于 origin 的坐标，生成的位图按 color 和尺寸缓存
+    fn create_image_brush<F>(&self, color: &Color, origin: Direct2DPoint, width: f32, height: f32, fun: F) -> Result<ID2D1BitmapBrush>
+        where F: Fn(f32, f32) -> SolidColor {
+        let (dpi_x, dpi_y) = self.get_dpi();
+        let max_size = unsafe { self.device_context.GetMaximumBitmapSize() }.min(IMAGE_MAX_SIZE) as f32;
+        //按照设备像素生成，避免高 DPI 下模糊，超出最大尺寸时通过画刷的变换拉伸
+        let pixel_width = (width * dpi_x / BASE_DPI).ceil().max(1.0).min(max_size) as u32;
+        let pixel_height = (height * dpi_y / BASE_DPI).ceil().max(1.0).min(max_size) as u32;
+        let (width, height) = (width.max(0.0), height.max(0.0));
+        let bitmap = self.cached_image(color, (width, height), (pixel_width, pixel_height), || {
+            let count = (pixel_width as usize).checked_mul(pixel_height as usize).ok_or_else(|| Error::from(E_OUTOFMEMORY))?;
+            let mut pixels = Vec::with_capacity(count);
+            for py in 0..pixel_height {
+                let y = (py as f32 + 0.5) * height / pixel_height as f32;
+                for px in 0..pixel_width {
+                    let x = (px as f32 + 0.5) * width / pixel_width as f32;
+                    pixels.push(fun(x, y));
+                }
+            }
+            self.create_bitmap(pixel_width, pixel_height, &pixels)
+        })?;
+        //位图以设备 DPI 创建，大小为 pixel * BASE_DPI / dpi
+        let scale_x = width * dpi_x / BASE_DPI / pixel_width as f32;
+        let scale_y = height * dpi_y / BASE_DPI / pixel_height as f32;
+        let transform = Matrix3x2 {
+            M11: scale_x,
+            M12: 0.0,
+            M21: 0.0,
+            M22: scale_y,
+            M31: origin.x,
+            M32: origin.y,
+        };
+        unsafe {
+            let brush = self.device_context.CreateBitmapBrush(
+                &bitmap,
+                Some(&D2D1_BITMAP_BRUSH_PROPERTIES {
+                    extendModeX: D2D1_EXTEND_MODE_CLAMP,
+                    extendModeY: D2D1_EXTEND_MODE_CLAMP,
+                    interpolationMode: D2D1_BITMAP_INTERPOLATION_MODE_LINEAR,
+                }),
+                None,
+            )?;
+            brush.SetTransform(&transform);
+            Ok(brush)
+        }
+    }
+
+    /// 查找参数相同的位图，没有时用 create 生成并缓存，超出容量时丢弃最久未使用的位图
+    fn cached_image(&self, color: &Color, size: (f32, f32), pixel_size: (u32, u32), create: impl FnOnce() -> Result<ID2D1Bitmap>) -> Result<ID2D1Bitmap> {
+        let mut cache = self.image_cache.borrow_mut();
+        let found = cache.iter().position(|entry| entry.size == size && entry.pixel_size == pixel_size && entry.color == *color);
+        if let Some(index) = found {
+            let entry = cache.remove(index);
+            let bitmap = entry.bitmap.clone();
+            cache.push(entry);
+            return Ok(bitmap);
+        }
+        let bitmap = create()?;
+        if cache.len() >= IMAGE_CACHE_CAPACITY {
+            cache.remove(0);
+        }
+        cache.push(ImageCacheEntry {
+            color: color.clone(),
+            size,
+            pixel_size,
+            bitmap: bitmap.clone(),
+        });
+        Ok(bitmap)
+    }
+
+    /// 图案按平铺单元生成位图，以绘制坐标系原点对齐并旋转
+    fn create_pattern_brush(&self, pattern: &Pattern) -> Result<ID2D1BitmapBrush> {
+        let (dpi_x, _) = self.get_dpi();
+        let tile = pattern.generate_tile(dpi_x / BASE_DPI);
+        let (sin, cos) = pattern.tile_angle().to_radians().sin_cos();
+        let transform = Matrix3x2 {
+            M11: cos,
+            M12: sin,
+            M21: -sin,
+            M22: cos,
+            M31: 0.0,
+            M32: 0.0,
+        };
+        self.create_bitmap_brush(tile.width, tile.height, &tile.pixels, D2D1_EXTEND_MODE_WRAP, transform)
+    }
+
     pub fn create_brush(&self, color: Color, gradient_color_property: GradientColorProperty) -> Brush {
         match (color, gradient_color_property) {
             (Color::SolidColor(color), _) => {
@@ -49,9 +222,9 @@ impl RenderTarget {
                     self.device_context.CreateSolidColorBrush(&color, None).unwrap().into()
                 }
             }
-            (Color::LinearGradient(color, _), GradientColorProperty::LinearGradient(liner_property)) => {
+            (Color::LinearGradient(color, _) | Color::LinearGradientGeometry(color, _) | Color::CssLinearGradient(color, _), GradientColorProperty::LinearGradient(liner_property)) => {
                 unsafe {
-                    let collection = self.CreateGradientStopCollection(color.as_slice(), D2D1_GAMMA_2_2, D2D1_EXTEND_MODE_CLAMP).unwrap();
+                    let collection = self.create_gradient_stop_collection(&color).unwrap();
                     self.device_context.CreateLinearGradientBrush(
                         &D2D1_LINEAR_GRADIENT_BRUSH_PROPERTIES {
                             startPoint: liner_property.start,
@@ -62,9 +235,9 @@ impl RenderTarget {
                     ).unwrap().into()
                 }
             }
-            (Color::RadialGradient(color), GradientColorProperty::RadialGradient(radial_property)) => {
+            (Color::RadialGradient(color) | Color::RadialGradientGeometry(color, _) | Color::CssRadialGradient(color, _), GradientColorProperty::RadialGradient(radial_property)) => {
                 unsafe {
-                    let collection = self.CreateGradientStopCollection(color.as_slice(), D2D1_GAMMA_2_2, D2D1_EXTEND_MODE_CLAMP).unwrap();
+                    let collection = self.create_gradient_stop_collection(&color).unwrap();
                     self.device_context.CreateRadialGradientBrush(
                         &D2D1_RADIAL_GRADIENT_BRUSH_PROPERTIES {
                             center: radial_property.center,
@@ -77,6 +250,16 @@ impl RenderTarget {
                     ).unwrap().into()
                 }
             }
+            (Color::ConicGradient(color, conic), GradientColorProperty::ConicGradient(image_property)) => {
+                let (width, height) = (image_property.width, image_property.height);
+                let key = Color::ConicGradient(color.clone(), conic);
+                self.create_image_brush(&key, image_property.origin, width, height, |x, y| {
+                    conic.sample(&color, width, height, x, y)
+                }).unwrap().into()
+            }
+            (Color::Pattern(pattern), _) => {
+                self.create_pattern_brush(&pattern).unwrap().into()
+            }
             _ => {
                 panic!("not support");
             }
@@ -0,0 +1,599 @@
+use std::iter::once;
+use windows::core::{ComInterface, Error, HSTRING, PCWSTR, Result};
+use windows::Win32::Graphics::DirectWrite::{DWRITE_CLUSTER_METRICS, DWRITE_HIT_TEST_METRICS, DWRITE_LINE_METRICS, DWRITE_FONT_LINE_GAP_USAGE_DEFAULT, DWRITE_LINE_SPACING, DWRITE_LINE_SPACING_METHOD_PROPORTIONAL, DWRITE_LINE_SPACING_METHOD_UNIFORM, DWRITE_PARAGRAPH_ALIGNMENT_CENTER, DWRITE_PARAGRAPH_ALIGNMENT_FAR, DWRITE_PARAGRAPH_ALIGNMENT_NEAR, DWRITE_TEXT_METRICS, DWRITE_TEXT_RANGE, DWRITE_FONT_AXIS_TAG, DWRITE_FONT_AXIS_VALUE, DWRITE_FONT_FEATURE, DWRITE_FONT_FEATURE_TAG, DWRITE_UNICODE_RANGE, IDWriteFactory2, IDWriteFontFallback, IDWriteTextFormat1, IDWriteTextLayout, IDWriteTextLayout1, IDWriteTextLayout3, IDWriteTextLayout4};
+use crate::{find_font_collection, max_lines_height, to_text_range, utf16_len, utf16_to_byte, DecorationSpan, InlineObject, LineHeight, Direct2DPoint, Direct2DRect, FontCollection, TextDefaults, TextProperty, TextTrimming, TextVerticalAlign};
+
+pub(crate) unsafe fn get_line_metrics(text_layout: &IDWriteTextLayout) -> Result<Vec<DWRITE_LINE_METRICS>> {
+    let mut text_metrics = DWRITE_TEXT_METRICS::default();
+    text_layout.GetMetrics(&mut text_metrics)?;
+    let mut line_count = text_metrics.lineCount;
+    let mut raw_line_metrics = vec![DWRITE_LINE_METRICS::default(); line_count as usize];
+    text_layout.GetLineMetrics(Some(raw_line_metrics.as_mut_slice()), &mut line_count)?;
+    raw_line_metrics.truncate(line_count as usize);
+    Ok(raw_line_metrics)
+}
+
+pub(crate) struct TextResources {
+    write_factory: IDWriteFactory2,
+    font_collections: Vec<FontCollection>,
+    defaults: TextDefaults,
+    fallback: IDWriteFontFallback,
+}
+
+impl TextResources {
+    pub(crate) fn new(write_factory: IDWriteFactory2) -> Result<Self> {
+        let defaults = TextDefaults::default();
+        let fallback = unsafe { create_font_fallback(&write_factory, &[], &defaults)? };
+        Ok(Self {
+            write_factory,
+            font_collections: vec![],
+            defaults,
+            fallback,
+        })
+    }
+
+    pub(crate) fn defaults(&self) -> &TextDefaults {
+        &self.defaults
+    }
+
+    /// 回退字体可能在新的字体集合中，需要重新创建
+    pub(crate) fn register_font_collection(&mut self, font_collection: FontCollection) -> Result<()> {
+        self.font_collections.push(font_collection);
+        self.fallback = unsafe { create_font_fallback(&self.write_factory, &self.font_collections, &self.defaults)? };
+        Ok(())
+    }
+
+    pub(crate) fn set_defaults(&mut self, defaults: TextDefaults) -> Result<()> {
+        self.fallback = unsafe { create_font_fallback(&self.write_factory, &self.font_collections, &defaults)? };
+        self.defaults = defaults;
+        Ok(())
+    }
+
+    pub(crate) unsafe fn create_text_layout(&self, text_property: TextProperty) -> Result<IDWriteTextLayout> {
+        //固定行高时内联对象无法撑高行，与 FallbackMeasurer 相同忽略段落间距，TextProperty::validate 会报告这种冲突
+        let paragraph_spacing = text_property.resolved_paragraph_spacing();
+        //方向冲突时与 FallbackMeasurer 相同使用与阅读方向匹配的排列方向
+        let writing_mode = text_property.resolved_writing_mode();
+        let (reading_direction, flow_direction) = text_property.resolved_directions();
+        let write_factory = &self.write_factory;
+        let font_collections = self.font_collections.as_slice();
+        let font_size = text_property.font_size.unwrap_or(self.defaults.font_size);
+        let text = text_property.text.as_ref().encode_utf16().chain(once(0)).collect::<Vec<u16>>();
+        let styles = text_property.resolved_styles();
+        let font_family = HSTRING::from(text_property.font_family.as_ref().unwrap_or(&self.defaults.font_family).as_ref());
+        //字体族在注册的字体集合中时使用该集合，否则使用系统字体
+        let font_collection = find_font_collection(font_collections, &font_family.to_string_lossy());
+        let text_format = write_factory.CreateTextFormat(
+            &font_family,
+            font_collection,
+            text_property.font_weight.into(),
+            text_property.font_style.into(),
+            text_property.font_stretch.into(),
+            font_size,
+            &HSTRING::from(text_property.locale.as_ref().unwrap_or(&self.defaults.locale).as_ref()),
+        )?;
+        let text_format = text_format.cast::<IDWriteTextFormat1>()?;
+        text_format.SetFontFallback(&self.fallback)?;
+        text_format.SetParagraphAlignment(DWRITE_PARAGRAPH_ALIGNMENT_CENTER)?;
+        text_format.SetReadingDirection(reading_direction.into())?;
+        text_format.SetFlowDirection(flow_direction.into())?;
+        if writing_mode.is_vertical() {
+            text_format.SetVerticalGlyphOrientation(text_property.glyph_orientation.into())?;
+        }
+        let text_layout = write_factory.CreateTextLayout(text.as_slice(), &text_format, f32::MAX, f32::MAX)?;
+
+        text_layout.SetIncrementalTabStop(text_property.tab_stop.unwrap_or(font_size * 4.0))?;
+
+        //以第一行的基线和行高作为字体自身的基线和行高
+        let (natural_baseline, natural_height) = get_line_metrics(&text_layout)?
+            .first()
+            .map(|line_metrics| (line_metrics.baseline, line_metrics.height))
+            .unwrap_or((font_size * 0.8, font_size));
+        let baseline_policy = text_property.baseline_policy;
+        match text_property.line_height {
+            LineHeight::Default => {}
+            LineHeight::Proportional(multiplier) => {
+                text_layout.cast::<IDWriteTextLayout3>()?.SetLineSpacing2(&DWRITE_LINE_SPACING {
+                    method: DWRITE_LINE_SPACING_METHOD_PROPORTIONAL,
+                    height: multiplier,
+                    baseline: 1.0,
+                    leadingBefore: baseline_policy.leading_before(),
+                    fontLineGapUsage: DWRITE_FONT_LINE_GAP_USAGE_DEFAULT,
+                })?;
+            }
+            line_height => {
+                let height = line_height.uniform_height(font_size).unwrap_or(natural_height);
+                let baseline = baseline_policy.baseline(height, natural_baseline, natural_height);
+                text_layout.SetLineSpacing(DWRITE_LINE_SPACING_METHOD_UNIFORM, height, baseline)?;
+            }
+        }
+
+        if text_property.underline {
+            text_layout.SetUnderline(true, DWRITE_TEXT_RANGE { startPosition: 0, length: utf16_len(&text_property.text) as u32 })?;
+        }
+
+        for styled_range in styles.iter() {
+            let range = to_text_range(&text_property.text, styled_range.range.clone());
+            let style = &styled_range.style;
+            if let Some(font_family) = &style.font_family {
+                text_layout.SetFontFamilyName(&HSTRING::from(font_family.as_ref()), range)?;
+                match find_font_collection(font_collections, font_family) {
+                    Some(collection) => text_layout.SetFontCollection(collection, range)?,
+                    //整体使用自定义字体集合时，这段文本要切换回系统字体集合
+                    None if font_collection.is_some() => {
+                        let mut system_collection = None;
+                        write_factory.GetSystemFontCollection(&mut system_collection, false)?;
+                        text_layout.SetFontCollection(system_collection.as_ref(), range)?;
+                    }
+                    None => {}
+                }
+            }
+            if let Some(font_size) = style.font_size {
+                text_layout.SetFontSize(font_size, range)?;
+            }
+            if let Some(font_weight) = style.font_weight.clone() {
+                text_layout.SetFontWeight(font_weight.into(), range)?;
+            }
+            if let Some(font_style) = style.font_style.clone() {
+                text_layout.SetFontStyle(font_style.into(), range)?;
+            }
+            if let Some(underline) = style.underline {
+                text_layout.SetUnderline(underline, range)?;
+            }
+            if let Some(strikethrough) = style.strikethrough {
+                text_layout.SetStrikethrough(strikethrough, range)?;
+            }
+        }
+        //这里只确定内联框的大小和位置，绘制时会替换成带有绘制内容的内联对象
+        for inline_box in text_property.inline_boxes.iter() {
+            let inline_object = InlineObject::inline_box(&inline_box.inline_box.metrics, None);
+            text_layout.SetInlineObject(&inline_object, to_text_range(&text_property.text, inline_box.range.clone()))?;
+        }
+
+        let full_range = DWRITE_TEXT_RANGE { startPosition: 0, length: utf16_len(&text_property.text) as u32 };
+        let spacing = CharacterSpacing::new(text_property.letter_spacing, text_property.word_spacing);
+        let first_line_indent = text_property.first_line_indent;
+        let character_layout = match spacing.is_empty() && first_line_indent == 0.0 && text_property.hanging_indent == 0.0 {
+            true => None,
+            false => Some(text_layout.cast::<IDWriteTextLayout1>()?),
+        };
+        if let Some(text_layout) = &character_layout {
+            text_layout.SetCharacterSpacing(0.0, spacing.letter, 0.0, full_range)?;
+            if spacing.word != 0.0 {
+                for (index, _) in text_property.text.char_indices().filter(|(_, c)| is_word_separator(*c)) {
+                    spacing.apply(text_layout, &text_property.text, index, 0.0)?;
+                }
+            }
+            //首行缩进通过每段第一个字符前的间距实现
+            if first_line_indent != 0.0 {
+                for index in paragraph_starts(&text_property.text) {
+                    spacing.apply(text_layout, &text_property.text, index, first_line_indent)?;
+                }
+            }
+        }
+
+        if !text_property.font_features.is_empty() {
+            let typography = write_factory.CreateTypography()?;
+            for feature in text_property.font_features.iter() {
+                typography.AddFontFeature(DWRITE_FONT_FEATURE {
+                    nameTag: DWRITE_FONT_FEATURE_TAG(feature.tag.to_u32()),
+                    parameter: feature.value,
+                })?;
+            }
+            text_layout.SetTypography(&typography, full_range)?;
+        }
+
+        //旧版本的系统不支持可变字体，忽略这些设置
+        if let (false, Ok(text_layout)) = (text_property.font_variations.is_empty(), text_layout.cast::<IDWriteTextLayout4>()) {
+            let axis_values = text_property.font_variations.iter().map(|variation| DWRITE_FONT_AXIS_VALUE {
+                axisTag: DWRITE_FONT_AXIS_TAG(variation.tag.to_u32()),
+                value: variation.value,
+            }).collect::<Vec<DWRITE_FONT_AXIS_VALUE>>();
+            text_layout.SetFontAxisValues(&axis_values, full_range)?;
+        }
+
+        let align = text_property.align.to_dwrite(reading_direction);
+        text_layout.SetTextAlignment(align)?;
+
+        let vertical_align = match text_property.vertical_align {
+            TextVerticalAlign::Top => {
+                DWRITE_PARAGRAPH_ALIGNMENT_NEAR
+            }
+            TextVerticalAlign::Center => {
+                DWRITE_PARAGRAPH_ALIGNMENT_CENTER
+            }
+            TextVerticalAlign::Bottom => {
+                DWRITE_PARAGRAPH_ALIGNMENT_FAR
+            }
+        };
+
+        text_layout.SetParagraphAlignment(vertical_align)?;
+
+        text_layout.SetWordWrapping(text_property.wrap.into())?;
+
+        //需要省略时不能根据内容扩大布局，否则永远不会超出
+        let trimmed = text_property.trimming != TextTrimming::None;
+        if trimmed {
+            let sign = write_factory.CreateEllipsisTrimmingSign(&text_layout)?;
+            text_layout.SetTrimming(&text_property.trimming.into(), &sign)?;
+        }
+
+        //必须最后再设置宽高，因为其他属性的设置会导致宽高的变化
+        let mut text_metrics = DWRITE_TEXT_METRICS::default();
+
+        text_layout.GetMetrics(&mut text_metrics)?;
+
+        //竖排时行的长度由高度限制，行数由宽度限制
+        let (inline_limit, block_limit) = writing_mode.to_logical(text_property.width, text_property.height);
+        let set_max_inline = |size: f32| match writing_mode.is_vertical() {
+            true => text_layout.SetMaxHeight(size),
+            false => text_layout.SetMaxWidth(size),
+        };
+        let set_max_block = |size: f32| match writing_mode.is_vertical() {
+            true => text_layout.SetMaxWidth(size),
+            false => text_layout.SetMaxHeight(size),
+        };
+        let logical_size = |text_metrics: &DWRITE_TEXT_METRICS| writing_mode.to_logical(text_metrics.width, text_metrics.height);
+
+        let max_inline = inline_limit.unwrap_or(logical_size(&text_metrics).0);
+        set_max_inline(max_inline)?;
+
+        //设置宽度后需要重新获取
+        text_layout.GetMetrics(&mut text_metrics)?;
+
+        if !(trimmed && inline_limit.is_some()) {
+            set_max_inline(max_inline.max(logical_size(&text_metrics).0))?;
+        }
+
+        //悬挂缩进需要在确定宽度之后根据换行结果设置
+        if let (Some(character_layout), true) = (&character_layout, text_property.hanging_indent != 0.0) {
+            apply_hanging_indent(character_layout, &text_property.text, &spacing, text_property.hanging_indent)?;
+            text_layout.GetMetrics(&mut text_metrics)?;
+        }
+
+        //段落间距根据换行之后每段最后一行的行高设置，内联对象宽度为 0，不影响换行
+        if paragraph_spacing != 0.0 {
+            apply_paragraph_spacing(&text_layout, &text_property.text, paragraph_spacing)?;
+            text_layout.GetMetrics(&mut text_metrics)?;
+        }
+
+        let content_block = logical_size(&text_metrics).1;
+        let max_block = block_limit.unwrap_or(content_block);
+        let lines_height = match text_property.max_lines {
+            Some(max_lines) => {
+                let line_heights = get_line_metrics(&text_layout)?.iter().map(|lm| lm.height).collect::<Vec<f32>>();
+                max_lines_height(&line_heights, max_lines)
+            }
+            None => None,
+        };
+        match lines_height {
+            Some(lines_height) => set_max_block(max_block.min(lines_height))?,
+            None if trimmed && block_limit.is_some() => set_max_block(max_block)?,
+            None => set_max_block(max_block.max(content_block))?,
+        }
+
+        Ok(text_layout)
+    }
+}
+
+/// TextProperty 中的字间距和词间距
+struct CharacterSpacing {
+    letter: f32,
+    word: f32,
+}
+
+impl CharacterSpacing {
+    fn new(letter: f32, word: f32) -> Self {
+        Self { letter, word }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.letter == 0.0 && self.word == 0.0
+    }
+
+    /// 设置 index 处字符前的间距，同时保留字符后的字间距和词间距
+    unsafe fn apply(&self, text_layout: &IDWriteTextLayout1, text: &str, index: usize, leading: f32) -> Result<()> {
+        let Some(c) = text[index..].chars().next() else {
+            return Ok(());
+        };
+        let trailing = if is_word_separator(c) { self.letter + self.word } else { self.letter };
+        text_layout.SetCharacterSpacing(leading, trailing, 0.0, to_text_range(text, index..index + c.len_utf8()))
+    }
+}
+
+/// 每个段落第一个字符的字节位置，不包括文本末尾
+pub(crate) fn paragraph_starts(text: &str) -> impl Iterator<Item = usize> + '_ {
+    once(0).chain(text.match_indices('\n').map(|(index, _)| index + 1)).filter(|index| *index < text.len())
+}
+
+/// 用撑高的内联对象替换每段末尾的换行符，内联对象的基线和行高取自所在行，只在下方加上 spacing
+unsafe fn apply_paragraph_spacing(text_layout: &IDWriteTextLayout, text: &str, spacing: f32) -> Result<()> {
+    let mut line_start = 0;
+    for line_metrics in get_line_metrics(text_layout)? {
+        let line_end = line_start + line_metrics.length as usize;
+        let end = utf16_to_byte(text, line_end);
+        line_start = line_end;
+        if !text[..end].ends_with('\n') {
+            continue;
+        }
+        let start = if text[..end - 1].ends_with('\r') { end - 2 } else { end - 1 };
+        let spacer = InlineObject::paragraph_break(line_metrics.height + spacing, line_metrics.baseline);
+        text_layout.SetInlineObject(&spacer, to_text_range(text, start..end))?;
+    }
+    Ok(())
+}
+
+/// 给自动换行产生的每一行的第一个字符前加上间距
+/// 加上间距后换行位置可能改变，由 settle_line_starts 重复换行直到稳定
+unsafe fn apply_hanging_indent(text_layout: &IDWriteTextLayout1, text: &str, spacing: &CharacterSpacing, indent: f32) -> Result<()> {
+    let paragraph_starts = paragraph_starts(text).collect::<Vec<usize>>();
+    let text_layout0: IDWriteTextLayout = text_layout.cast()?;
+    let mut applied: Vec<usize> = vec![];
+    settle_line_starts(|indented| {
+        for index in applied.iter().filter(|index| !indented.contains(index)) {
+            spacing.apply(text_layout, text, *index, 0.0)?;
+        }
+        for index in indented.iter().filter(|index| !applied.contains(index)) {
+            spacing.apply(text_layout, text, *index, indent)?;
+        }
+        applied = indented.to_vec();
+
+        let mut line_start = 0;
+        let mut wrapped_starts = vec![];
+        for line_metrics in get_line_metrics(&text_layout0)? {
+            let start = utf16_to_byte(text, line_start);
+            if start < text.len() && !paragraph_starts.contains(&start) {
+                wrapped_starts.push(start);
+            }
+            line_start += line_metrics.length as usize;
+        }
+        Ok::<_, Error>(wrapped_starts)
+    })?;
+    Ok(())
+}
+
+/// 重新换行的最多轮数，通常两轮之内就能稳定，每轮都需要重新布局，不随文本长度增加
+const HANGING_INDENT_PASSES: usize = 4;
+
+/// 反复调用 wrap 直到自动换行产生的行首不再变化，返回最后一次传给 wrap 的行首
+/// wrap 只缩进参数中的行首后重新换行，返回新的行首
+/// 超过 HANGING_INDENT_PASSES 轮仍不稳定时保留最后一轮的缩进，个别行的缩进可能与换行结果不一致
+fn settle_line_starts<E>(mut wrap: impl FnMut(&[usize]) -> std::result::Result<Vec<usize>, E>) -> std::result::Result<Vec<usize>, E> {
+    let mut indented = vec![];
+    for _ in 0..HANGING_INDENT_PASSES {
+        let starts = wrap(&indented)?;
+        if starts == indented {
+            return Ok(starts);
+        }
+        indented = starts;
+    }
+    wrap(&indented)?;
+    Ok(indented)
+}
+
+/// CSS word-spacing 作用的字符
+pub(crate) fn is_word_separator(c: char) -> bool {
+    matches!(c, ' ' | '\u{A0}' | '\u{1361}' | '\u{10100}' | '\u{10101}' | '\u{1039F}' | '\u{1091F}')
+}
+
+/// 按 TextDefaults::fallbacks 的顺序添加回退字体，最后追加系统的回退字体
+unsafe fn create_font_fallback(write_factory: &IDWriteFactory2, font_collections: &[FontCollection], defaults: &TextDefaults) -> Result<IDWriteFontFallback> {
+    let builder = write_factory.CreateFontFallbackBuilder()?;
+    for fallback in defaults.fallbacks.iter() {
+        let ranges = fallback.script.ranges().iter().map(|range| DWRITE_UNICODE_RANGE {
+            first: *range.start(),
+            last: *range.end(),
+        }).collect::<Vec<DWRITE_UNICODE_RANGE>>();
+        //每个字体族可能来自不同的字体集合，分别添加
+        for family in fallback.families.iter() {
+            let family_name = HSTRING::from(family.as_ref());
+            builder.AddMapping(&ranges, &[family_name.as_ptr()], find_font_collection(font_collections, family), PCWSTR::null(), PCWSTR::null(), 1.0)?;
+        }
+    }
+    builder.AddMappings(&write_factory.GetSystemFontFallback()?)?;
+    builder.CreateFontFallback()
+}
+
+/// 获取文本范围所占的矩形，origin 为文本布局的位置
+pub(crate) unsafe fn hit_test_metrics(text_layout: &IDWriteTextLayout, range: DWRITE_TEXT_RANGE, origin: Direct2DPoint) -> Result<Vec<DWRITE_HIT_TEST_METRICS>> {
+    let mut count = 0;
+    //先获取数量，缓冲区不足时会返回错误
+    let _ = text_layout.HitTestTextRange(range.startPosition, range.length, origin.x, origin.y, None, &mut count);
+    let mut hit_test_metrics = vec![DWRITE_HIT_TEST_METRICS::default(); count as usize];
+    text_layout.HitTestTextRange(range.startPosition, range.length, origin.x, origin.y, Some(hit_test_metrics.as_mut_slice()), &mut count)?;
+    hit_test_metrics.truncate(count as usize);
+    Ok(hit_test_metrics)
+}
+
+pub(crate) unsafe fn hit_test_text_range(text_layout: &IDWriteTextLayout, range: DWRITE_TEXT_RANGE, origin: Direct2DPoint) -> Result<Vec<Direct2DRect>> {
+    Ok(hit_test_metrics(text_layout, range, origin)?.iter().map(|metrics| Direct2DRect {
+        left: metrics.left,
+        top: metrics.top,
+        right: metrics.left + metrics.width,
+        bottom: metrics.top + metrics.height,
+    }).collect())
+}
+
+/// 第一行中每个字簇的 (左边缘, 宽度, 第一个字符的 UTF-16 位置)，按从左到右的显示顺序排列
+pub(crate) unsafe fn first_line_clusters(text_layout: &IDWriteTextLayout) -> Result<Vec<(f32, f32, u32)>> {
+    let mut count = 0;
+    //先获取数量，缓冲区不足时会返回错误
+    let _ = text_layout.GetClusterMetrics(None, &mut count);
+    let mut cluster_metrics = vec![DWRITE_CLUSTER_METRICS::default(); count as usize];
+    text_layout.GetClusterMetrics(Some(cluster_metrics.as_mut_slice()), &mut count)?;
+    cluster_metrics.truncate(count as usize);
+    let first_line_length = get_line_metrics(text_layout)?.first().map(|line_metrics| line_metrics.length).unwrap_or_default();
+
+    let mut position = 0;
+    let mut clusters = vec![];
+    for cluster in cluster_metrics.iter() {
+        if position >= first_line_length {
+            break;
+        }
+        let (mut x, mut y) = (0.0, 0.0);
+        let mut metrics = DWRITE_HIT_TEST_METRICS::default();
+        text_layout.HitTestTextPosition(position, false, &mut x, &mut y, &mut metrics)?;
+        clusters.push((metrics.left, cluster.width, position));
+        position += cluster.length as u32;
+    }
+    //双向文本中字簇的逻辑顺序与显示顺序不同
+    clusters.sort_by(|a, b| a.0.total_cmp(&b.0));
+    Ok(clusters)
+}
+
+/// 一段文本在每行中需要绘制装饰线的部分，坐标加上了 origin
+pub(crate) unsafe fn decoration_spans(text_layout: &IDWriteTextLayout, range: DWRITE_TEXT_RANGE, origin: Direct2DPoint) -> Result<Vec<DecorationSpan>> {
+    let mut text_metrics = DWRITE_TEXT_METRICS::default();
+    text_layout.GetMetrics(&mut text_metrics)?;
+    //行的位置不包含垂直对齐产生的偏移，需要加上 top
+    let mut line_top = origin.y + text_metrics.top;
+    let lines = get_line_metrics(text_layout)?.iter().map(|line_metrics| {
+        let line = (line_top, line_top + line_metrics.height, line_top + line_metrics.baseline);
+        line_top += line_metrics.height;
+        line
+    }).collect::<Vec<(f32, f32, f32)>>();
+
+    let mut spans = vec![];
+    for metrics in hit_test_metrics(text_layout, range, origin)? {
+        if metrics.width <= 0.0 {
+            continue;
+        }
+        let center = metrics.top + metrics.height / 2.0;
+        let Some((_, _, baseline)) = lines.iter().find(|(top, bottom, _)| (*top..*bottom).contains(&center)).or(lines.last()) else {
+            continue;
+        };
+        let mut font_size = 0.0;
+        text_layout.GetFontSize2(metrics.textPosition, &mut font_size, None)?;
+        spans.push(DecorationSpan {
+            left: metrics.left,
+            right: metrics.left + metrics.width,
+            baseline: *baseline,
+            font_size,
+        });
+    }
+    Ok(spans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(windows)]
+    use crate::TextMeasurer;
+
+    /// 按字符贪心换行，缩进加在参数中的字符前面，返回自动换行产生的行首
+    fn wrap(advances: &[f32], width: f32, indent: f32, indented: &[usize]) -> Vec<usize> {
+        let mut starts = vec![];
+        let mut line_width = 0.0;
+        for (index, advance) in advances.iter().enumerate() {
+            let advance = advance + if indented.contains(&index) { indent } else { 0.0 };
+            if line_width > 0.0 && line_width + advance > width {
+                starts.push(index);
+                line_width = 0.0;
+            }
+            line_width += advance;
+        }
+        starts
+    }
+
+    /// 直接按悬挂缩进换行的结果
+    fn hanging(advances: &[f32], width: f32, indent: f32) -> Vec<usize> {
+        let mut starts = vec![];
+        let mut line_width = 0.0;
+        for (index, advance) in advances.iter().enumerate() {
+            if line_width > 0.0 && line_width + advance > width {
+                starts.push(index);
+                line_width = indent;
+            }
+            line_width += advance;
+        }
+        starts
+    }
+
+    fn settle(advances: &[f32], width: f32, indent: f32) -> Vec<usize> {
+        settle_line_starts(|indented| Ok::<_, ()>(wrap(advances, width, indent, indented))).unwrap()
+    }
+
+    #[test]
+    fn no_wrap_settles_immediately() {
+        let mut passes = 0;
+        let starts = settle_line_starts(|_| {
+            passes += 1;
+            Ok::<_, ()>(vec![])
+        }).unwrap();
+        assert!(starts.is_empty());
+        assert_eq!(passes, 1);
+    }
+
+    #[test]
+    fn settles_to_hanging_indent() {
+        let advances = [5.0; 40];
+        assert_eq!(settle(&advances, 50.0, 20.0), hanging(&advances, 50.0, 20.0));
+        assert_eq!(settle(&advances, 50.0, 20.0), vec![10, 16, 22, 28, 34]);
+
+        let advances = [5.0, 3.0, 8.0, 2.0, 7.0, 4.0, 9.0, 1.0, 6.0, 5.0].repeat(6);
+        for indent in [1.0, 4.0, 7.5, 12.0, 18.0] {
+            assert_eq!(settle(&advances, 30.0, indent), hanging(&advances, 30.0, indent), "indent {indent}");
+        }
+    }
+
+    #[test]
+    fn settled_starts_are_stable() {
+        let advances = [5.0, 3.0, 8.0, 2.0, 7.0, 4.0, 9.0, 1.0, 6.0, 5.0].repeat(6);
+        let starts = settle(&advances, 30.0, 7.5);
+        assert_eq!(wrap(&advances, 30.0, 7.5, &starts), starts);
+    }
+
+    #[test]
+    fn stops_after_max_passes() {
+        //整体缩进时在 [4, 8] 和 [3, 8] 之间来回变化
+        let mut calls = vec![];
+        let starts = settle_line_starts(|indented| {
+            calls.push(indented.to_vec());
+            Ok::<_, ()>(match indented {
+                [] | [3, 8] => vec![4, 8],
+                _ => vec![3, 8],
+            })
+        }).unwrap();
+        assert_eq!(calls.len(), HANGING_INDENT_PASSES + 1);
+        //最后一次 wrap 使用返回的行首
+        assert_eq!(calls.last(), Some(&starts));
+    }
+
+    #[test]
+    fn passes_do_not_grow_with_line_count() {
+        let mut passes = 0;
+        settle_line_starts(|indented| {
+            passes += 1;
+            Ok::<_, ()>(vec![indented.len() + 1; indented.len() + 1])
+        }).unwrap();
+        assert_eq!(passes, HANGING_INDENT_PASSES + 1);
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn paragraph_spacing_extends_last_line_of_paragraph() {
+        let measurer = TextMeasurer::new().unwrap();
+        let text_property = |spacing: f32| TextProperty::builder().text("aa\nbb\ncc").font_size(20.0).paragraph_spacing(spacing).build().unwrap();
+        let plain = measurer.create_text_layout(text_property(0.0)).unwrap();
+        let spaced = measurer.create_text_layout(text_property(6.0)).unwrap();
+        let heights = |info: &crate::TextLayoutInfo| info.line_metrics.iter().map(|line| line.height).collect::<Vec<f32>>();
+        let (plain_heights, spaced_heights) = (heights(&plain), heights(&spaced));
+        //间距只加在前两段下方，基线不变
+        assert_eq!(spaced_heights.len(), 3);
+        assert_eq!(spaced_heights[0], plain_heights[0] + 6.0);
+        assert_eq!(spaced_heights[1], plain_heights[1] + 6.0);
+        assert_eq!(spaced_heights[2], plain_heights[2]);
+        for (plain, spaced) in plain.line_metrics.iter().zip(spaced.line_metrics.iter()) {
+            assert_eq!(plain.baseline, spaced.baseline);
+            assert_eq!(plain.range, spaced.range);
+        }
+        assert_eq!(spaced.metrics.height, plain.metrics.height + 12.0);
+        //光标和选区不包含间距
+        assert_eq!(spaced.caret_rect(0).bottom - spaced.caret_rect(0).top, plain_heights[0]);
+        assert_eq!(spaced.caret_rect(3).top, plain_heights[0] + 6.0);
+        let rects = spaced.selection_rects(0..5);
+        assert_eq!(rects.len(), 2);
+        assert_eq!(rects[0].bottom - rects[0].top, plain_heights[0]);
+        assert_eq!(rects[1].top, plain_heights[0] + 6.0);
+    }
+}
@@ -0,0 +1,159 @@
+use std::path::Path;
+use std::sync::Arc;
+use windows::core::{ComInterface, IUnknown, Result, HSTRING};
+use windows::Win32::Foundation::BOOL;
+use windows::Win32::Graphics::DirectWrite::{IDWriteFactory5, IDWriteFontCollection, IDWriteFontFile, IDWriteInMemoryFontFileLoader, IDWriteLocalizedStrings};
+use windows::core::w;
+use crate::{create_write_factory, FontStretch, FontStyle, FontWeight};
+
+/// 字体族中的一个字体
+#[derive(Debug, Clone, PartialEq)]
+pub struct FontFace {
+    pub weight: FontWeight,
+    pub style: FontStyle,
+    pub stretch: FontStretch,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FontFamily {
+    /// 可以直接用于 TextProperty::font_family 的名称
+    pub name: String,
+    pub faces: Vec<FontFace>,
+}
+
+/// 注册在共享的 DirectWrite 工厂上的内存字体加载器，释放时注销
+/// 已经创建的字体文件引用会保留加载器，注销后仍然可以使用
+struct InMemoryLoader {
+    write_factory: IDWriteFactory5,
+    loader: IDWriteInMemoryFontFileLoader,
+}
+
+impl Drop for InMemoryLoader {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = self.write_factory.UnregisterFontFileLoader(&self.loader);
+        }
+    }
+}
+
+/// 一组字体，注册到 ContextHolder 或 TextMeasurer 后可以通过 TextProperty::font_family 使用其中的字体族
+#[derive(Clone)]
+pub struct FontCollection {
+    collection: IDWriteFontCollection,
+    /// 内存中的字体依赖这个加载器，最后一个克隆释放时注销
+    /// 字段按声明顺序释放，字体集合先于加载器释放
+    _loader: Option<Arc<InMemoryLoader>>,
+}
+
+impl FontCollection {
+    /// 系统已安装的字体
+    pub fn system() -> Result<Self> {
+        unsafe {
+            let write_factory = create_write_factory()?;
+            let mut collection = None;
+            write_factory.GetSystemFontCollection(&mut collection, false)?;
+            Ok(Self {
+                collection: collection.ok_or_else(windows::core::Error::from_win32)?,
+                _loader: None,
+            })
+        }
+    }
+
+    /// 从内存中的 TTF/OTF/TTC 数据加载字体，数据会被复制，调用后可以释放
+    pub fn from_bytes<T: AsRef<[u8]>>(fonts: &[T]) -> Result<Self> {
+        unsafe {
+            let write_factory = create_write_factory()?.cast::<IDWriteFactory5>()?;
+            let loader = write_factory.CreateInMemoryFontFileLoader()?;
+            write_factory.RegisterFontFileLoader(&loader)?;
+            //之后出错时也需要注销
+            let registration = Arc::new(InMemoryLoader {
+                write_factory: write_factory.clone(),
+                loader: loader.clone(),
+            });
+            let font_files = fonts.iter().map(|font| {
+                let font = font.as_ref();
+                //不传入 owner 时加载器会复制一份数据
+                loader.CreateInMemoryFontFileReference(&write_factory, font.as_ptr() as _, font.len() as u32, None::<&IUnknown>)
+            }).collect::<Result<Vec<IDWriteFontFile>>>()?;
+            Ok(Self {
+                collection: Self::create_collection(&write_factory, &font_files)?,
+                _loader: Some(registration),
+            })
+        }
+    }
+
+    pub fn from_files<P: AsRef<Path>>(paths: &[P]) -> Result<Self> {
+        unsafe {
+            let write_factory = create_write_factory()?.cast::<IDWriteFactory5>()?;
+            let font_files = paths.iter().map(|path| {
+                write_factory.CreateFontFileReference(&HSTRING::from(path.as_ref().to_string_lossy().as_ref()), None)
+            }).collect::<Result<Vec<IDWriteFontFile>>>()?;
+            Ok(Self {
+                collection: Self::create_collection(&write_factory, &font_files)?,
+                _loader: None,
+            })
+        }
+    }
+
+    unsafe fn create_collection(write_factory: &IDWriteFactory5, font_files: &[IDWriteFontFile]) -> Result<IDWriteFontCollection> {
+        let builder = write_factory.CreateFontSetBuilder2()?;
+        for font_file in font_files {
+            builder.AddFontFile(font_file)?;
+        }
+        let font_set = builder.CreateFontSet()?;
+        write_factory.CreateFontCollectionFromFontSet(&font_set)?.cast()
+    }
+
+    /// 是否包含指定名称的字体族，名称不区分大小写
+    pub fn contains_family(&self, family_name: &str) -> bool {
+        let mut index = 0;
+        let mut exists = BOOL::default();
+        unsafe {
+            self.collection.FindFamilyName(&HSTRING::from(family_name), &mut index, &mut exists).is_ok() && exists.as_bool()
+        }
+    }
+
+    /// 枚举所有字体族及其中的字体
+    pub fn families(&self) -> Result<Vec<FontFamily>> {
+        unsafe {
+            (0..self.collection.GetFontFamilyCount()).map(|index| {
+                let family = self.collection.GetFontFamily(index)?;
+                let faces = (0..family.GetFontCount()).map(|index| {
+                    let font = family.GetFont(index)?;
+                    Ok(FontFace {
+                        weight: font.GetWeight().into(),
+                        style: font.GetStyle().into(),
+                        stretch: font.GetStretch().into(),
+                    })
+                }).collect::<Result<Vec<FontFace>>>()?;
+                Ok(FontFamily {
+                    name: get_localized_string(&family.GetFamilyNames()?)?,
+                    faces,
+                })
+            }).collect()
+        }
+    }
+
+    pub(crate) fn as_raw(&self) -> &IDWriteFontCollection {
+        &self.collection
+    }
+}
+
+/// 查找包含指定字体族的字体集合，先注册的优先
+pub(crate) fn find_font_collection<'a>(font_collections: &'a [FontCollection], family_name: &str) -> Option<&'a IDWriteFontCollection> {
+    font_collections.iter().find(|collection| collection.contains_family(family_name)).map(FontCollection::as_raw)
+}
+
+/// 优先读取英文名称，没有时使用第一个
+unsafe fn get_localized_string(strings: &IDWriteLocalizedStrings) -> Result<String> {
+    let mut index = 0;
+    let mut exists = BOOL::default();
+    strings.FindLocaleName(w!("en-us"), &mut index, &mut exists)?;
+    if !exists.as_bool() {
+        index = 0;
+    }
+    let length = strings.GetStringLength(index)?;
+    let mut buffer = vec![0u16; length as usize + 1];
+    strings.GetString(index, &mut buffer)?;
+    Ok(String::from_utf16_lossy(&buffer[..length as usize]))
+}
@@ -0,0 +1,522 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FontFileError {
+    /// 数据长度不足，读取的位置超出了范围
+    Truncated,
+    MissingTable(&'static str),
+    /// 没有可用的 Unicode cmap 子表
+    UnsupportedCmap,
+}
+
+impl Display for FontFileError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FontFileError::Truncated => write!(f, "font data is truncated"),
+            FontFileError::MissingTable(tag) => write!(f, "font data has no `{}` table", tag),
+            FontFileError::UnsupportedCmap => write!(f, "font data has no supported unicode cmap subtable"),
+        }
+    }
+}
+
+impl std::error::Error for FontFileError {}
+
+/// 按大端序读取 TrueType/OpenType 数据
+#[derive(Clone, Copy)]
+pub(crate) struct FontReader<'a> {
+    data: &'a [u8],
+    /// 表目录的位置，字体集合（TTC）中每个字体的表目录位置不同
+    directory: usize,
+}
+
+impl<'a> FontReader<'a> {
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        Self { data, directory: 0 }
+    }
+
+    /// 字体数据中的所有字体，普通字体文件只有一个，字体集合（TTC）可能有多个
+    pub(crate) fn faces(data: &'a [u8]) -> Result<Vec<FontReader<'a>>, FontFileError> {
+        let font = FontReader::new(data);
+        if font.bytes(0, 4)? != b"ttcf" {
+            return Ok(vec![font]);
+        }
+        let num_fonts = font.u32(8)? as usize;
+        (0..num_fonts)
+            .map(|index| font.u32(12 + index * 4).map(|directory| FontReader { data, directory: directory as usize }))
+            .collect()
+    }
+
+    pub(crate) fn bytes(&self, offset: usize, length: usize) -> Result<&'a [u8], FontFileError> {
+        self.data.get(offset..offset + length).ok_or(FontFileError::Truncated)
+    }
+
+    pub(crate) fn u16(&self, offset: usize) -> Result<u16, FontFileError> {
+        let bytes = self.bytes(offset, 2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    pub(crate) fn i16(&self, offset: usize) -> Result<i16, FontFileError> {
+        self.u16(offset).map(|value| value as i16)
+    }
+
+    pub(crate) fn u32(&self, offset: usize) -> Result<u32, FontFileError> {
+        let bytes = self.bytes(offset, 4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// 在表目录中查找指定的表
+    pub(crate) fn table(&self, tag: &'static str) -> Result<FontReader<'a>, FontFileError> {
+        let num_tables = self.u16(self.directory + 4)? as usize;
+        for index in 0..num_tables {
+            let record = self.directory + 12 + index * 16;
+            if self.bytes(record, 4)? == tag.as_bytes() {
+                let offset = self.u32(record + 8)? as usize;
+                let length = self.u32(record + 12)? as usize;
+                return Ok(FontReader::new(self.bytes(offset, length)?));
+            }
+        }
+        Err(FontFileError::MissingTable(tag))
+    }
+}
+
+/// 从字体文件中读取的字形宽度和行高信息，单位为字体设计单位
+#[derive(Debug, Clone, PartialEq)]
+pub struct FontAdvances {
+    pub units_per_em: u16,
+    pub ascender: i16,
+    /// 通常为负数
+    pub descender: i16,
+    pub line_gap: i16,
+    glyphs: HashMap<char, u16>,
+    advances: Vec<u16>,
+}
+
+impl FontAdvances {
+    /// 解析 TTF/OTF 数据中的 head、hhea、hmtx 和 cmap 表，字体集合（TTC）只读取第一个字体
+    pub fn parse(data: &[u8]) -> Result<Self, FontFileError> {
+        let font = FontReader::faces(data)?.into_iter().next().ok_or(FontFileError::Truncated)?;
+        let head = font.table("head")?;
+        let hhea = font.table("hhea")?;
+        let hmtx = font.table("hmtx")?;
+        let number_of_h_metrics = hhea.u16(34)? as usize;
+        let advances = (0..number_of_h_metrics)
+            .map(|index| hmtx.u16(index * 4))
+            .collect::<Result<Vec<u16>, FontFileError>>()?;
+        Ok(Self {
+            units_per_em: head.u16(18)?,
+            ascender: hhea.i16(4)?,
+            descender: hhea.i16(6)?,
+            line_gap: hhea.i16(8)?,
+            glyphs: parse_cmap(font.table("cmap")?)?,
+            advances,
+        })
+    }
+
+    pub fn glyph_index(&self, c: char) -> Option<u16> {
+        self.glyphs.get(&c).copied()
+    }
+
+    /// 字符在指定字号下的宽度，字体中没有的字符使用 .notdef 的宽度
+    pub fn advance(&self, c: char, font_size: f32) -> f32 {
+        let glyph = self.glyph_index(c).unwrap_or(0) as usize;
+        //超出 numberOfHMetrics 的字形使用最后一个宽度
+        let advance = self.advances.get(glyph).or(self.advances.last()).copied().unwrap_or(0);
+        self.scale(advance as f32, font_size)
+    }
+
+    pub fn ascent(&self, font_size: f32) -> f32 {
+        self.scale(self.ascender as f32, font_size)
+    }
+
+    pub fn descent(&self, font_size: f32) -> f32 {
+        self.scale(-(self.descender as f32), font_size)
+    }
+
+    pub fn line_gap(&self, font_size: f32) -> f32 {
+        self.scale(self.line_gap as f32, font_size)
+    }
+
+    fn scale(&self, value: f32, font_size: f32) -> f32 {
+        value * font_size / self.units_per_em.max(1) as f32
+    }
+}
+
+/// 从字体的 name 表中读取的名称
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct FontNames {
+    /// 旧式的字体族名称（名称 ID 1），每个族最多只有常规、粗体、斜体、粗斜体四种样式
+    pub family: String,
+    /// 名称 ID 2，如 Regular、Bold
+    pub subfamily: String,
+    /// 名称 ID 16，存在时与 DirectWrite 枚举出的族名称一致
+    pub typographic_family: Option<String>,
+    /// 名称 ID 17
+    pub typographic_subfamily: Option<String>,
+    /// 名称 ID 4
+    pub full_name: Option<String>,
+}
+
+impl FontNames {
+    /// 用于 TextProperty::font_family 的族名称
+    pub fn preferred_family(&self) -> &str {
+        self.typographic_family.as_deref().unwrap_or(&self.family)
+    }
+
+    /// 读取字体数据中每个字体的名称，字体集合（TTC）会返回多个
+    pub fn parse(data: &[u8]) -> Result<Vec<Self>, FontFileError> {
+        FontReader::faces(data)?.into_iter().map(|font| parse_names(font.table("name")?)).collect()
+    }
+}
+
+const NAME_FAMILY: u16 = 1;
+const NAME_SUBFAMILY: u16 = 2;
+const NAME_FULL_NAME: u16 = 4;
+const NAME_TYPOGRAPHIC_FAMILY: u16 = 16;
+const NAME_TYPOGRAPHIC_SUBFAMILY: u16 = 17;
+
+fn parse_names(name: FontReader) -> Result<FontNames, FontFileError> {
+    let count = name.u16(2)? as usize;
+    let storage = name.u16(4)? as usize;
+    //每个名称 ID 记录找到的最佳名称及其优先级，数值越小越优先
+    let mut names: HashMap<u16, (u8, String)> = HashMap::new();
+    for index in 0..count {
+        let record = 6 + index * 12;
+        let platform_id = name.u16(record)?;
+        let encoding_id = name.u16(record + 2)?;
+        let language_id = name.u16(record + 4)?;
+        let name_id = name.u16(record + 6)?;
+        let length = name.u16(record + 8)? as usize;
+        let offset = name.u16(record + 10)? as usize;
+        let bytes = name.bytes(storage + offset, length)?;
+        //优先使用英文名称，其次是 Windows 平台的其他语言
+        let (priority, text) = match (platform_id, encoding_id) {
+            (0, _) | (3, 0) | (3, 1) | (3, 10) => {
+                let priority = if platform_id == 3 && language_id == 0x0409 { 0 } else if platform_id == 0 { 1 } else { 3 };
+                (priority, decode_utf16_be(bytes))
+            }
+            //Macintosh Roman 编码，只接受 ASCII 部分
+            (1, 0) if bytes.is_ascii() => (if language_id == 0 { 2 } else { 4 }, bytes.iter().map(|b| *b as char).collect()),
+            _ => continue,
+        };
+        let better = match names.get(&name_id) {
+            Some((best, _)) => priority < *best,
+            None => true,
+        };
+        if better {
+            names.insert(name_id, (priority, text));
+        }
+    }
+    let mut take = |name_id: u16| names.remove(&name_id).map(|(_, text)| text);
+    Ok(FontNames {
+        family: take(NAME_FAMILY).unwrap_or_default(),
+        subfamily: take(NAME_SUBFAMILY).unwrap_or_default(),
+        typographic_family: take(NAME_TYPOGRAPHIC_FAMILY),
+        typographic_subfamily: take(NAME_TYPOGRAPHIC_SUBFAMILY),
+        full_name: take(NAME_FULL_NAME),
+    })
+}
+
+fn decode_utf16_be(bytes: &[u8]) -> String {
+    let units = bytes.chunks_exact(2).map(|pair| u16::from_be_bytes([pair[0], pair[1]]));
+    char::decode_utf16(units).map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER)).collect()
+}
+
+/// 优先使用完整 Unicode 的 format 12 子表，其次是 BMP 的 format 4 子表
+fn parse_cmap(cmap: FontReader) -> Result<HashMap<char, u16>, FontFileError> {
+    let num_tables = cmap.u16(2)? as usize;
+    let mut format_4 = None;
+    let mut format_12 = None;
+    for index in 0..num_tables {
+        let record = 4 + index * 8;
+        let platform_id = cmap.u16(record)?;
+        let encoding_id = cmap.u16(record + 2)?;
+        let offset = cmap.u32(record + 4)? as usize;
+        let unicode = platform_id == 0 || (platform_id == 3 && (encoding_id == 1 || encoding_id == 10));
+        if !unicode {
+            continue;
+        }
+        match cmap.u16(offset)? {
+            4 => format_4 = format_4.or(Some(offset)),
+            12 => format_12 = format_12.or(Some(offset)),
+            _ => {}
+        }
+    }
+    match (format_12, format_4) {
+        (Some(offset), _) => parse_cmap_format_12(cmap, offset),
+        (None, Some(offset)) => parse_cmap_format_4(cmap, offset),
+        _ => Err(FontFileError::UnsupportedCmap),
+    }
+}
+
+fn parse_cmap_format_4(cmap: FontReader, offset: usize) -> Result<HashMap<char, u16>, FontFileError> {
+    let seg_count = cmap.u16(offset + 6)? as usize / 2;
+    let end_codes = offset + 14;
+    let start_codes = end_codes + seg_count * 2 + 2;
+    let id_deltas = start_codes + seg_count * 2;
+    let id_range_offsets = id_deltas + seg_count * 2;
+    let mut glyphs = HashMap::new();
+    for segment in 0..seg_count {
+        let end = cmap.u16(end_codes + segment * 2)?;
+        let start = cmap.u16(start_codes + segment * 2)?;
+        let delta = cmap.u16(id_deltas + segment * 2)?;
+        let range_offset_position = id_range_offsets + segment * 2;
+        let range_offset = cmap.u16(range_offset_position)? as usize;
+        for code in start..=end {
+            if code == 0xFFFF {
+                break;
+            }
+            let glyph = if range_offset == 0 {
+                code.wrapping_add(delta)
+            } else {
+                let glyph = cmap.u16(range_offset_position + range_offset + (code - start) as usize * 2)?;
+                if glyph == 0 { 0 } else { glyph.wrapping_add(delta) }
+            };
+            if let (Some(c), true) = (char::from_u32(code as u32), glyph != 0) {
+                glyphs.insert(c, glyph);
+            }
+        }
+    }
+    Ok(glyphs)
+}
+
+/// format 12 子表最多映射的字符数，超出的部分忽略，避免损坏的字体占用大量内存
+/// 分组的字形编号是连续的，字形编号超出 u16 后的字符也会忽略，因此每个分组最多映射 65536 个字符
+const CMAP_MAX_CHARS: u32 = 1 << 18;
+
+fn parse_cmap_format_12(cmap: FontReader, offset: usize) -> Result<HashMap<char, u16>, FontFileError> {
+    let num_groups = cmap.u32(offset + 12)? as usize;
+    let mut glyphs = HashMap::new();
+    let mut remaining = CMAP_MAX_CHARS;
+    for group in 0..num_groups {
+        let record = offset + 16 + group * 12;
+        let start = cmap.u32(record)?;
+        let end = cmap.u32(record + 4)?.min(0x10FFFF);
+        let start_glyph = cmap.u32(record + 8)?;
+        for code in start..=end {
+            if remaining == 0 {
+                return Ok(glyphs);
+            }
+            remaining -= 1;
+            //损坏的字体中字形编号可能溢出，溢出之后的字符都不再映射
+            let Some(glyph) = start_glyph.checked_add(code - start).and_then(|glyph| u16::try_from(glyph).ok()) else {
+                break;
+            };
+            if let Some(c) = char::from_u32(code) {
+                glyphs.insert(c, glyph);
+            }
+        }
+    }
+    Ok(glyphs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// format 12 子表，groups 为 (起始字符, 结束字符, 起始字形编号)
+    fn cmap_format_12(groups: &[(u32, u32, u32)]) -> Vec<u8> {
+        let mut data = vec![];
+        data.extend(12u16.to_be_bytes());
+        data.extend(0u16.to_be_bytes());
+        data.extend((16 + groups.len() as u32 * 12).to_be_bytes());
+        data.extend(0u32.to_be_bytes());
+        data.extend((groups.len() as u32).to_be_bytes());
+        for (start, end, start_glyph) in groups {
+            data.extend(start.to_be_bytes());
+            data.extend(end.to_be_bytes());
+            data.extend(start_glyph.to_be_bytes());
+        }
+        data
+    }
+
+    #[test]
+    fn format_12_maps_groups() {
+        let data = cmap_format_12(&[('A' as u32, 'C' as u32, 10), ('中' as u32, '中' as u32, 20)]);
+        let glyphs = parse_cmap_format_12(FontReader::new(&data), 0).unwrap();
+        assert_eq!(glyphs.len(), 4);
+        assert_eq!(glyphs[&'A'], 10);
+        assert_eq!(glyphs[&'C'], 12);
+        assert_eq!(glyphs[&'中'], 20);
+    }
+
+    #[test]
+    fn format_12_glyph_overflow() {
+        let data = cmap_format_12(&[(0x41, 0x50, u32::MAX - 1), (0x60, 0x70, 0xFFFE)]);
+        let glyphs = parse_cmap_format_12(FontReader::new(&data), 0).unwrap();
+        //第一个分组的字形编号超出范围，第二个分组在字形编号超出 u16 之前停止
+        assert_eq!(glyphs.len(), 2);
+        assert_eq!(glyphs[&'\u{60}'], 0xFFFE);
+        assert_eq!(glyphs[&'\u{61}'], 0xFFFF);
+    }
+
+    #[test]
+    fn format_12_large_groups_are_capped() {
+        let data = cmap_format_12(&[(0, 0x10FFFF, 0), (0, 0x10FFFF, 0), (0, u32::MAX, 0)]);
+        let glyphs = parse_cmap_format_12(FontReader::new(&data), 0).unwrap();
+        //每个分组最多映射 65536 个编码，其中 2048 个代理项不是有效的字符
+        assert_eq!(glyphs.len(), (1 << 16) - 0x800);
+        assert_eq!(parse_cmap_format_12(FontReader::new(&data[..20]), 0), Err(FontFileError::Truncated));
+    }
+
+    /// name 表记录为 (平台, 编码, 语言, 名称 ID, 内容)
+    fn name_table(records: &[(u16, u16, u16, u16, Vec<u8>)]) -> Vec<u8> {
+        let mut data = vec![];
+        data.extend(0u16.to_be_bytes());
+        data.extend((records.len() as u16).to_be_bytes());
+        data.extend((6 + records.len() as u16 * 12).to_be_bytes());
+        let mut storage: Vec<u8> = vec![];
+        for (platform_id, encoding_id, language_id, name_id, bytes) in records {
+            for value in [*platform_id, *encoding_id, *language_id, *name_id, bytes.len() as u16, storage.len() as u16] {
+                data.extend(value.to_be_bytes());
+            }
+            storage.extend(bytes);
+        }
+        data.extend(storage);
+        data
+    }
+
+    /// 只有一个表的字体文件
+    fn font_with_table(tag: &[u8; 4], table: &[u8]) -> Vec<u8> {
+        let mut data = vec![];
+        data.extend(0x0001_0000u32.to_be_bytes());
+        data.extend(1u16.to_be_bytes());
+        data.extend([0; 6]);
+        data.extend(tag);
+        data.extend(0u32.to_be_bytes());
+        data.extend(28u32.to_be_bytes());
+        data.extend((table.len() as u32).to_be_bytes());
+        data.extend(table);
+        data
+    }
+
+    fn utf16_be(text: &str) -> Vec<u8> {
+        text.encode_utf16().flat_map(u16::to_be_bytes).collect()
+    }
+
+    fn parse_one(records: &[(u16, u16, u16, u16, Vec<u8>)]) -> FontNames {
+        let names = FontNames::parse(&font_with_table(b"name", &name_table(records))).unwrap();
+        assert_eq!(names.len(), 1);
+        names.into_iter().next().unwrap()
+    }
+
+    #[test]
+    fn windows_names() {
+        let names = parse_one(&[
+            (3, 1, 0x0409, NAME_FAMILY, utf16_be("Noto Sans SemiBold")),
+            (3, 1, 0x0409, NAME_SUBFAMILY, utf16_be("Regular")),
+            (3, 1, 0x0409, NAME_FULL_NAME, utf16_be("Noto Sans SemiBold")),
+            (3, 1, 0x0409, NAME_TYPOGRAPHIC_FAMILY, utf16_be("Noto Sans")),
+            (3, 1, 0x0409, NAME_TYPOGRAPHIC_SUBFAMILY, utf16_be("SemiBold")),
+        ]);
+        assert_eq!(names.family, "Noto Sans SemiBold");
+        assert_eq!(names.subfamily, "Regular");
+        assert_eq!(names.full_name.as_deref(), Some("Noto Sans SemiBold"));
+        assert_eq!(names.typographic_subfamily.as_deref(), Some("SemiBold"));
+        assert_eq!(names.preferred_family(), "Noto Sans");
+    }
+
+    #[test]
+    fn english_name_is_preferred() {
+        //非 BMP 字符以代理对编码
+        let names = parse_one(&[
+            (3, 1, 0x0804, NAME_FAMILY, utf16_be("思源黑体")),
+            (3, 10, 0x0409, NAME_FAMILY, utf16_be("Source Han Sans 𝄞")),
+            (3, 1, 0x0411, NAME_FAMILY, utf16_be("源ノ角ゴシック")),
+            (3, 1, 0x0804, NAME_SUBFAMILY, utf16_be("常规")),
+        ]);
+        assert_eq!(names.family, "Source Han Sans 𝄞");
+        //没有英文名称时使用其他语言
+        assert_eq!(names.subfamily, "常规");
+        assert_eq!(names.typographic_family, None);
+        assert_eq!(names.preferred_family(), "Source Han Sans 𝄞");
+    }
+
+    #[test]
+    fn macintosh_names() {
+        let names = parse_one(&[
+            (1, 0, 0, NAME_FAMILY, b"Mac Family".to_vec()),
+            (1, 0, 0, NAME_SUBFAMILY, b"Italic".to_vec()),
+            //非 ASCII 的 Mac Roman 名称和不支持的编码被忽略
+            (1, 0, 0, NAME_FULL_NAME, vec![b'A', 0x8A]),
+            (3, 2, 0x0409, NAME_TYPOGRAPHIC_FAMILY, utf16_be("ShiftJIS")),
+            (3, 1, 0x0409, NAME_SUBFAMILY, utf16_be("Bold Italic")),
+        ]);
+        assert_eq!(names.family, "Mac Family");
+        //Windows 英文名称优先于 Macintosh 名称，与记录顺序无关
+        assert_eq!(names.subfamily, "Bold Italic");
+        assert_eq!(names.full_name, None);
+        assert_eq!(names.typographic_family, None);
+    }
+
+    #[test]
+    fn unicode_platform_names() {
+        let names = parse_one(&[
+            (1, 0, 0, NAME_FAMILY, b"Mac".to_vec()),
+            (0, 3, 0, NAME_FAMILY, utf16_be("Unicode")),
+        ]);
+        assert_eq!(names.family, "Unicode");
+    }
+
+    #[test]
+    fn invalid_utf16() {
+        //单独的代理项替换为 U+FFFD，多余的字节被忽略
+        assert_eq!(decode_utf16_be(&[0x00, 0x41, 0xD8, 0x00, 0x00, 0x42, 0x00]), "A\u{FFFD}B");
+        assert_eq!(decode_utf16_be(&[]), "");
+    }
+
+    #[test]
+    fn truncated_name_table() {
+        let table = name_table(&[(3, 1, 0x0409, NAME_FAMILY, utf16_be("Family"))]);
+        //记录数量超出数据
+        let mut more_records = table.clone();
+        more_records[2..4].copy_from_slice(&2u16.to_be_bytes());
+        assert_eq!(FontNames::parse(&font_with_table(b"name", &more_records)), Err(FontFileError::Truncated));
+        //名称内容超出数据
+        assert_eq!(FontNames::parse(&font_with_table(b"name", &table[..table.len() - 1])), Err(FontFileError::Truncated));
+        assert_eq!(FontNames::parse(&font_with_table(b"name", &table[..3])), Err(FontFileError::Truncated));
+    }
+
+    #[test]
+    fn bad_name_offsets() {
+        let mut table = name_table(&[(3, 1, 0x0409, NAME_FAMILY, utf16_be("Family"))]);
+        //记录中的偏移超出存储区
+        table[16..18].copy_from_slice(&0xFFF0u16.to_be_bytes());
+        assert_eq!(FontNames::parse(&font_with_table(b"name", &table)), Err(FontFileError::Truncated));
+        //存储区偏移超出数据
+        let mut table = name_table(&[(3, 1, 0x0409, NAME_FAMILY, utf16_be("Family"))]);
+        table[4..6].copy_from_slice(&0xFFFFu16.to_be_bytes());
+        assert_eq!(FontNames::parse(&font_with_table(b"name", &table)), Err(FontFileError::Truncated));
+    }
+
+    #[test]
+    fn bad_font_directory() {
+        let table = name_table(&[(3, 1, 0x0409, NAME_FAMILY, utf16_be("Family"))]);
+        assert_eq!(FontNames::parse(&font_with_table(b"cmap", &table)), Err(FontFileError::MissingTable("name")));
+        //表目录中的偏移超出数据
+        let mut data = font_with_table(b"name", &table);
+        data[20..24].copy_from_slice(&0xFFFFu32.to_be_bytes());
+        assert_eq!(FontNames::parse(&data), Err(FontFileError::Truncated));
+        assert_eq!(FontNames::parse(&data[..10]), Err(FontFileError::Truncated));
+        assert_eq!(FontNames::parse(&[]), Err(FontFileError::Truncated));
+    }
+
+    #[test]
+    fn font_collection_names() {
+        let font = font_with_table(b"name", &name_table(&[(3, 1, 0x0409, NAME_FAMILY, utf16_be("Family"))]));
+        //两个字体共用同一个表目录
+        let mut data = vec![];
+        data.extend(b"ttcf");
+        data.extend(0x0001_0000u32.to_be_bytes());
+        data.extend(2u32.to_be_bytes());
+        data.extend(20u32.to_be_bytes());
+        data.extend(20u32.to_be_bytes());
+        let table_offset = 20 + 28;
+        data.extend(&font[..20]);
+        data.extend((table_offset as u32).to_be_bytes());
+        data.extend(&font[24..]);
+        let names = FontNames::parse(&data).unwrap();
+        assert_eq!(names.len(), 2);
+        assert!(names.iter().all(|names| names.family == "Family"));
+    }
+}
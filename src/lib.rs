@@ -2,23 +2,26 @@ mod context;
 mod properties;
 mod d2d;
 mod context_holder;
+mod text_layout_info;
+mod text_metrics;
+mod text_measurer;
+mod font_file;
+mod font_collection;
 #[macro_use]
 pub mod types;
 
-use windows::Win32::Graphics::DirectWrite::{DWRITE_LINE_METRICS, DWRITE_TEXT_METRICS, IDWriteTextLayout};
 pub use types::*;
 
 pub(crate) use d2d::*;
 pub use context_holder::*;
+pub use text_layout_info::*;
+pub use text_metrics::*;
+pub use text_measurer::*;
+pub use font_file::*;
+pub use font_collection::*;
 
 pub use context::*;
 
 pub use properties::*;
 
 pub(crate) const BASE_DPI: f32 = 96.0;
-
-pub struct TextLayoutInfo {
-    pub layout: IDWriteTextLayout,
-    pub metrics: DWRITE_TEXT_METRICS,
-    pub raw_line_metrics: Vec<DWRITE_LINE_METRICS>,
-}
\ No newline at end of file
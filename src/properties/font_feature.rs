@@ -0,0 +1,220 @@
+use std::fmt::{Display, Formatter};
+use std::str::FromStr;
+
+/// OpenType 标签，由 4 个可打印 ASCII 字符组成，不足 4 个字符时在末尾补空格
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FontTag([u8; 4]);
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FontTagError {
+    /// 标签长度必须为 1~4 个字符
+    InvalidLength(usize),
+    /// 标签只能包含可打印 ASCII 字符，且不能以空格开头
+    InvalidCharacter(char),
+}
+
+impl Display for FontTagError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FontTagError::InvalidLength(length) => write!(f, "font tag must have 1 to 4 characters, found {}", length),
+            FontTagError::InvalidCharacter(c) => write!(f, "invalid character {:?} in font tag", c),
+        }
+    }
+}
+
+impl std::error::Error for FontTagError {}
+
+impl FontTag {
+    pub const KERNING: FontTag = FontTag(*b"kern");
+    pub const STANDARD_LIGATURES: FontTag = FontTag(*b"liga");
+    pub const CONTEXTUAL_LIGATURES: FontTag = FontTag(*b"clig");
+    pub const DISCRETIONARY_LIGATURES: FontTag = FontTag(*b"dlig");
+    pub const TABULAR_FIGURES: FontTag = FontTag(*b"tnum");
+    pub const PROPORTIONAL_FIGURES: FontTag = FontTag(*b"pnum");
+    pub const LINING_FIGURES: FontTag = FontTag(*b"lnum");
+    pub const OLDSTYLE_FIGURES: FontTag = FontTag(*b"onum");
+    pub const SLASHED_ZERO: FontTag = FontTag(*b"zero");
+    pub const SMALL_CAPITALS: FontTag = FontTag(*b"smcp");
+    pub const CAPITALS_TO_SMALL_CAPITALS: FontTag = FontTag(*b"c2sc");
+    pub const FRACTIONS: FontTag = FontTag(*b"frac");
+
+    /// 可变字体的字重轴
+    pub const WEIGHT: FontTag = FontTag(*b"wght");
+    /// 可变字体的宽度轴
+    pub const WIDTH: FontTag = FontTag(*b"wdth");
+    /// 可变字体的光学尺寸轴
+    pub const OPTICAL_SIZE: FontTag = FontTag(*b"opsz");
+    pub const ITALIC: FontTag = FontTag(*b"ital");
+    pub const SLANT: FontTag = FontTag(*b"slnt");
+
+    /// 样式集 ss01~ss20，超出范围时返回 None
+    pub fn stylistic_set(index: u8) -> Option<FontTag> {
+        match index {
+            1..=20 => Some(FontTag([b's', b's', b'0' + index / 10, b'0' + index % 10])),
+            _ => None,
+        }
+    }
+
+    /// 字符变体 cv01~cv99，超出范围时返回 None
+    pub fn character_variant(index: u8) -> Option<FontTag> {
+        match index {
+            1..=99 => Some(FontTag([b'c', b'v', b'0' + index / 10, b'0' + index % 10])),
+            _ => None,
+        }
+    }
+
+    pub fn as_bytes(&self) -> &[u8; 4] {
+        &self.0
+    }
+
+    /// DirectWrite 中的标签数值，第一个字符在最低字节
+    pub(crate) fn to_u32(self) -> u32 {
+        u32::from_le_bytes(self.0)
+    }
+}
+
+impl FromStr for FontTag {
+    type Err = FontTagError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let length = s.chars().count();
+        if !(1..=4).contains(&length) {
+            return Err(FontTagError::InvalidLength(length));
+        }
+        if let Some(c) = s.chars().find(|c| !(' '..='~').contains(c)) {
+            return Err(FontTagError::InvalidCharacter(c));
+        }
+        if s.starts_with(' ') {
+            return Err(FontTagError::InvalidCharacter(' '));
+        }
+        let mut tag = [b' '; 4];
+        tag[..s.len()].copy_from_slice(s.as_bytes());
+        Ok(FontTag(tag))
+    }
+}
+
+impl TryFrom<&str> for FontTag {
+    type Error = FontTagError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl Display for FontTag {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        //标签只包含 ASCII 字符
+        write!(f, "{}", self.0.iter().map(|b| *b as char).collect::<String>())
+    }
+}
+
+/// OpenType 特性开关，value 为 0 时关闭，为 1 时开启，部分特性（如 salt）用更大的值选择备选字形
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FontFeature {
+    pub tag: FontTag,
+    pub value: u32,
+}
+
+impl FontFeature {
+    pub fn new(tag: FontTag, value: u32) -> Self {
+        Self { tag, value }
+    }
+
+    pub fn enable(tag: FontTag) -> Self {
+        Self::new(tag, 1)
+    }
+
+    pub fn disable(tag: FontTag) -> Self {
+        Self::new(tag, 0)
+    }
+}
+
+/// 可变字体的轴的取值，如 wght 为 100~900
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FontVariation {
+    pub tag: FontTag,
+    pub value: f32,
+}
+
+impl FontVariation {
+    pub fn new(tag: FontTag, value: f32) -> Self {
+        Self { tag, value }
+    }
+
+    pub fn weight(value: f32) -> Self {
+        Self::new(FontTag::WEIGHT, value)
+    }
+
+    pub fn width(value: f32) -> Self {
+        Self::new(FontTag::WIDTH, value)
+    }
+
+    pub fn optical_size(value: f32) -> Self {
+        Self::new(FontTag::OPTICAL_SIZE, value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use windows::Win32::Graphics::DirectWrite::{DWRITE_FONT_AXIS_TAG_WEIGHT, DWRITE_FONT_FEATURE_TAG_KERNING, DWRITE_FONT_FEATURE_TAG_STYLISTIC_SET_1, DWRITE_FONT_FEATURE_TAG_STYLISTIC_SET_20, DWRITE_FONT_FEATURE_TAG_TABULAR_FIGURES};
+
+    /// 与 DWRITE_MAKE_OPENTYPE_TAG 相同
+    fn make_opentype_tag(a: u8, b: u8, c: u8, d: u8) -> u32 {
+        (d as u32) << 24 | (c as u32) << 16 | (b as u32) << 8 | a as u32
+    }
+
+    #[test]
+    fn parse_valid_tags() {
+        assert_eq!("liga".parse(), Ok(FontTag::STANDARD_LIGATURES));
+        assert_eq!(FontTag::try_from("wght"), Ok(FontTag::WEIGHT));
+        assert_eq!("ss01".parse::<FontTag>().unwrap().to_string(), "ss01");
+    }
+
+    #[test]
+    fn short_tags_are_padded() {
+        assert_eq!("cv".parse::<FontTag>().unwrap().as_bytes(), b"cv  ");
+        assert_eq!("a".parse::<FontTag>().unwrap().as_bytes(), b"a   ");
+        assert_eq!("a b".parse::<FontTag>().unwrap().as_bytes(), b"a b ");
+        assert_eq!("cv".parse::<FontTag>().unwrap().to_string(), "cv  ");
+    }
+
+    #[test]
+    fn invalid_tags_are_rejected() {
+        assert_eq!("".parse::<FontTag>(), Err(FontTagError::InvalidLength(0)));
+        assert_eq!("kerns".parse::<FontTag>(), Err(FontTagError::InvalidLength(5)));
+        //长度按字符计算
+        assert_eq!("字体特性表".parse::<FontTag>(), Err(FontTagError::InvalidLength(5)));
+        assert_eq!("ké".parse::<FontTag>(), Err(FontTagError::InvalidCharacter('é')));
+        assert_eq!("字".parse::<FontTag>(), Err(FontTagError::InvalidCharacter('字')));
+        assert_eq!("k\tn".parse::<FontTag>(), Err(FontTagError::InvalidCharacter('\t')));
+        assert_eq!(" kern".parse::<FontTag>(), Err(FontTagError::InvalidLength(5)));
+        assert_eq!(" ker".parse::<FontTag>(), Err(FontTagError::InvalidCharacter(' ')));
+    }
+
+    #[test]
+    fn byte_order_matches_directwrite() {
+        assert_eq!(FontTag::KERNING.to_u32(), make_opentype_tag(b'k', b'e', b'r', b'n'));
+        assert_eq!(FontTag::KERNING.to_u32(), DWRITE_FONT_FEATURE_TAG_KERNING.0);
+        assert_eq!(FontTag::TABULAR_FIGURES.to_u32(), DWRITE_FONT_FEATURE_TAG_TABULAR_FIGURES.0);
+        assert_eq!(FontTag::WEIGHT.to_u32(), DWRITE_FONT_AXIS_TAG_WEIGHT.0);
+        assert_eq!("cv".parse::<FontTag>().unwrap().to_u32(), make_opentype_tag(b'c', b'v', b' ', b' '));
+    }
+
+    #[test]
+    fn stylistic_sets() {
+        assert_eq!(FontTag::stylistic_set(0), None);
+        assert_eq!(FontTag::stylistic_set(21), None);
+        assert_eq!(FontTag::stylistic_set(1).unwrap().to_u32(), DWRITE_FONT_FEATURE_TAG_STYLISTIC_SET_1.0);
+        assert_eq!(FontTag::stylistic_set(20).unwrap().to_u32(), DWRITE_FONT_FEATURE_TAG_STYLISTIC_SET_20.0);
+        assert_eq!(FontTag::stylistic_set(7).unwrap().to_string(), "ss07");
+    }
+
+    #[test]
+    fn character_variants() {
+        assert_eq!(FontTag::character_variant(0), None);
+        assert_eq!(FontTag::character_variant(100), None);
+        assert_eq!(FontTag::character_variant(9).unwrap().to_string(), "cv09");
+        assert_eq!(FontTag::character_variant(99).unwrap().to_string(), "cv99");
+    }
+}
@@ -45,10 +45,29 @@ impl RadialGradientProperty {
 }
 
 
+/// 需要预先生成图像的渐变所覆盖的区域
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageGradientProperty {
+    pub origin: Direct2DPoint,
+    pub width: f32,
+    pub height: f32,
+}
+
+impl ImageGradientProperty {
+    pub fn new(origin: Direct2DPoint, width: f32, height: f32) -> Self {
+        Self {
+            origin,
+            width,
+            height,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum GradientColorProperty {
     LinearGradient(LinearGradientProperty),
     RadialGradient(RadialGradientProperty),
+    ConicGradient(ImageGradientProperty),
     None,
 }
 
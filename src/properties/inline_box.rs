@@ -0,0 +1,165 @@
+use std::ops::Range;
+use std::sync::Arc;
+use crate::{Color, SolidColor};
+
+/// 在文本中代表内联框的字符
+pub const OBJECT_REPLACEMENT_CHARACTER: char = '\u{FFFC}';
+
+/// 内联框的尺寸，baseline 为框顶部到与文字对齐的基线的距离
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct InlineBoxMetrics {
+    pub width: f32,
+    pub height: f32,
+    pub baseline: f32,
+}
+
+impl InlineBoxMetrics {
+    pub fn new(width: f32, height: f32, baseline: f32) -> Self {
+        Self {
+            width,
+            height,
+            baseline,
+        }
+    }
+
+    /// 底部与文字基线对齐，与 CSS 中 inline-block 的默认对齐方式相同
+    pub fn bottom_on_baseline(width: f32, height: f32) -> Self {
+        Self::new(width, height, height)
+    }
+
+    /// 在行内居中，ascent 和 descent 为所在行文字基线以上和以下的高度
+    pub fn centered(width: f32, height: f32, ascent: f32, descent: f32) -> Self {
+        Self::new(width, height, (height + ascent - descent) / 2.0)
+    }
+
+    /// 所在行的基线到行顶部的距离为 line_baseline 时，框顶部到行顶部的距离
+    pub fn top_in_line(&self, line_baseline: f32) -> f32 {
+        line_baseline - self.baseline
+    }
+
+    /// 放入基线以上高 ascent、以下高 descent 的行后，行的 (ascent, descent)
+    pub fn extend_line(&self, ascent: f32, descent: f32) -> (f32, f32) {
+        (ascent.max(self.baseline), descent.max(self.height - self.baseline))
+    }
+}
+
+/// 内联显示的图片，像素按行排列，每个像素为未预乘透明度的 RGBA 四个字节
+#[derive(Debug, Clone, PartialEq)]
+pub struct InlineImage {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Arc<[u8]>,
+}
+
+impl InlineImage {
+    /// 像素数量与宽高不符时返回 None
+    pub fn from_rgba8(width: u32, height: u32, pixels: impl Into<Arc<[u8]>>) -> Option<Self> {
+        let pixels = pixels.into();
+        match pixels.len() == width as usize * height as usize * 4 {
+            true => Some(Self { width, height, pixels }),
+            false => None,
+        }
+    }
+
+    pub(crate) fn colors(&self) -> Vec<SolidColor> {
+        self.pixels.chunks_exact(4).map(|pixel| SolidColor::from_rgba8(pixel[0], pixel[1], pixel[2], pixel[3])).collect()
+    }
+}
+
+/// 内联框中绘制的内容
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum InlineBoxContent {
+    /// 只占据空间，由调用方通过 TextLayoutInfo::inline_box_rect 得到位置后自行绘制
+    #[default]
+    Placeholder,
+    /// 图片缩放到框的大小
+    Image(InlineImage),
+    /// 用颜色填充框，radius 为圆角半径，可用于状态标签等
+    Fill { color: Color, radius: f32 },
+}
+
+/// 随文字一起排列和换行的内联框，命中测试时作为一个字符
+#[derive(Debug, Clone, PartialEq)]
+pub struct InlineBox {
+    pub metrics: InlineBoxMetrics,
+    pub content: InlineBoxContent,
+}
+
+impl InlineBox {
+    pub fn new(metrics: InlineBoxMetrics, content: InlineBoxContent) -> Self {
+        Self {
+            metrics,
+            content,
+        }
+    }
+
+    pub fn placeholder(metrics: InlineBoxMetrics) -> Self {
+        Self::new(metrics, InlineBoxContent::Placeholder)
+    }
+
+    pub fn image(metrics: InlineBoxMetrics, image: InlineImage) -> Self {
+        Self::new(metrics, InlineBoxContent::Image(image))
+    }
+
+    pub fn fill(metrics: InlineBoxMetrics, color: impl Into<Color>, radius: f32) -> Self {
+        Self::new(metrics, InlineBoxContent::Fill { color: color.into(), radius })
+    }
+}
+
+/// 替换 TextProperty::text 中一段字节范围的内联框，这段文本通常是一个 OBJECT_REPLACEMENT_CHARACTER
+#[derive(Debug, Clone, PartialEq)]
+pub struct InlineBoxRange {
+    pub range: Range<usize>,
+    pub inline_box: InlineBox,
+}
+
+impl InlineBoxRange {
+    pub fn new(range: Range<usize>, inline_box: InlineBox) -> Self {
+        Self {
+            range,
+            inline_box,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bottom_on_baseline_metrics() {
+        let metrics = InlineBoxMetrics::bottom_on_baseline(12.0, 20.0);
+        assert_eq!(metrics, InlineBoxMetrics::new(12.0, 20.0, 20.0));
+        //行的基线在 8 处时，框顶部在行顶部上方 12 处
+        assert_eq!(metrics.top_in_line(8.0), -12.0);
+        assert_eq!(metrics.extend_line(8.0, 2.0), (20.0, 2.0));
+    }
+
+    #[test]
+    fn centered_metrics() {
+        let metrics = InlineBoxMetrics::centered(10.0, 20.0, 8.0, 2.0);
+        assert_eq!(metrics.baseline, 13.0);
+        //框的中心与行的中心重合
+        let line_center = (8.0 - 2.0) / 2.0;
+        let box_center = metrics.baseline - metrics.height / 2.0;
+        assert_eq!(box_center, line_center);
+        assert_eq!(metrics.top_in_line(8.0), -5.0);
+        assert_eq!(metrics.extend_line(8.0, 2.0), (13.0, 7.0));
+    }
+
+    #[test]
+    fn small_box_keeps_line() {
+        let metrics = InlineBoxMetrics::bottom_on_baseline(4.0, 4.0);
+        assert_eq!(metrics.top_in_line(8.0), 4.0);
+        assert_eq!(metrics.extend_line(8.0, 2.0), (8.0, 2.0));
+        //基线在框外时同样只撑开需要的一侧
+        let metrics = InlineBoxMetrics::new(4.0, 4.0, -1.0);
+        assert_eq!(metrics.extend_line(8.0, 2.0), (8.0, 5.0));
+    }
+
+    #[test]
+    fn image_pixel_count() {
+        assert!(InlineImage::from_rgba8(2, 2, vec![0; 16]).is_some());
+        assert!(InlineImage::from_rgba8(2, 2, vec![0; 15]).is_none());
+    }
+}
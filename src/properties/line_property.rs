@@ -9,6 +9,8 @@ pub struct LineProperty {
     pub(crate) end: Point,
     #[builder(default, setter(into))]
     pub(crate) width: f32,
+    /// 按角度定义的线性渐变沿线段从 start 画到 end
+    /// 其他渐变以线段的外接矩形作为图形范围，线段水平或竖直时外接矩形的一边为 0
     #[builder(default, setter(into))]
     pub(crate) color: Color,
 }
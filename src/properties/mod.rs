@@ -4,10 +4,32 @@ mod circle_property;
 mod gradient_color_property;
 mod ellipse_property;
 mod text_property;
+mod rich_text;
+mod text_overflow;
+mod text_defaults;
+mod text_direction;
+mod font_feature;
+mod paragraph;
+mod inline_box;
+mod text_link;
+mod text_decoration;
+mod text_stroke;
+mod text_on_path;
 
 pub use line_property::*;
 pub use rect_property::*;
 pub use circle_property::*;
 pub use gradient_color_property::*;
 pub use ellipse_property::*;
-pub use text_property::*;
\ No newline at end of file
+pub use text_property::*;
+pub use rich_text::*;
+pub use text_overflow::*;
+pub use text_defaults::*;
+pub use text_direction::*;
+pub use font_feature::*;
+pub use paragraph::*;
+pub use inline_box::*;
+pub use text_link::*;
+pub use text_decoration::*;
+pub use text_stroke::*;
+pub use text_on_path::*;
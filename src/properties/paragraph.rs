@@ -0,0 +1,60 @@
+/// 行高的计算方式
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum LineHeight {
+    /// 使用字体自身的行高，每行可能因字号和回退字体不同而不同
+    #[default]
+    Default,
+    /// 字号的倍数，所有行高度相同
+    Multiplier(f32),
+    /// 固定高度，所有行高度相同
+    Absolute(f32),
+    /// 字体自身行高的倍数，需要 Windows 10 及以上版本
+    Proportional(f32),
+}
+
+impl From<f32> for LineHeight {
+    fn from(multiplier: f32) -> Self {
+        LineHeight::Multiplier(multiplier)
+    }
+}
+
+impl LineHeight {
+    /// 所有行使用同一高度时返回该高度
+    pub fn uniform_height(&self, font_size: f32) -> Option<f32> {
+        match self {
+            LineHeight::Multiplier(multiplier) => Some(font_size * multiplier),
+            LineHeight::Absolute(height) => Some(*height),
+            LineHeight::Default | LineHeight::Proportional(_) => None,
+        }
+    }
+
+    /// 是否支持段落间距，段落间距通过撑高每段最后一行实现，只有行高由内容决定时才能撑高
+    pub(crate) fn supports_paragraph_spacing(&self) -> bool {
+        matches!(self, LineHeight::Default)
+    }
+}
+
+/// 行高大于字体自身行高时，多出的空间如何分配
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum BaselinePolicy {
+    /// 上下各分一半，与 CSS 相同，文字在行内垂直居中
+    #[default]
+    HalfLeading,
+    /// 全部放在文字下方，基线与字体自身行高时相同
+    Top,
+}
+
+impl BaselinePolicy {
+    /// 多出的空间放在文字上方的比例
+    pub fn leading_before(&self) -> f32 {
+        match self {
+            BaselinePolicy::HalfLeading => 0.5,
+            BaselinePolicy::Top => 0.0,
+        }
+    }
+
+    /// 行高为 line_height 时基线到行顶部的距离，ascent 和 natural_height 为字体自身的基线位置和行高
+    pub fn baseline(&self, line_height: f32, ascent: f32, natural_height: f32) -> f32 {
+        ascent + (line_height - natural_height) * self.leading_before()
+    }
+}
@@ -0,0 +1,174 @@
+use std::ops::Range;
+use std::sync::Arc;
+use typed_builder::TypedBuilder;
+use crate::{Color, FontStyle, FontWeight, InlineBox, InlineBoxRange, LinkTarget, TextLink, OBJECT_REPLACEMENT_CHARACTER};
+
+/// 一段文本的样式，为 None 的属性沿用 TextProperty 中的设置
+#[derive(Debug, Clone, PartialEq, Default, TypedBuilder)]
+pub struct TextStyle {
+    #[builder(default, setter(into))]
+    pub color: Option<Color>,
+    #[builder(default, setter(into))]
+    pub font_family: Option<Arc<str>>,
+    #[builder(default, setter(into))]
+    pub font_size: Option<f32>,
+    #[builder(default, setter(into))]
+    pub font_weight: Option<FontWeight>,
+    #[builder(default, setter(into))]
+    pub font_style: Option<FontStyle>,
+    #[builder(default, setter(into))]
+    pub underline: Option<bool>,
+    #[builder(default, setter(into))]
+    pub strikethrough: Option<bool>,
+    #[builder(default, setter(into))]
+    pub background: Option<Color>,
+}
+
+impl TextStyle {
+    /// 用 other 中不为 None 的属性覆盖当前样式
+    pub fn merge(&self, other: &TextStyle) -> TextStyle {
+        TextStyle {
+            color: other.color.clone().or_else(|| self.color.clone()),
+            font_family: other.font_family.clone().or_else(|| self.font_family.clone()),
+            font_size: other.font_size.or(self.font_size),
+            font_weight: other.font_weight.clone().or_else(|| self.font_weight.clone()),
+            font_style: other.font_style.clone().or_else(|| self.font_style.clone()),
+            underline: other.underline.or(self.underline),
+            strikethrough: other.strikethrough.or(self.strikethrough),
+            background: other.background.clone().or_else(|| self.background.clone()),
+        }
+    }
+}
+
+/// 作用于 TextProperty::text 中一段字节范围的样式
+#[derive(Debug, Clone, PartialEq)]
+pub struct StyledRange {
+    pub range: Range<usize>,
+    pub style: TextStyle,
+}
+
+impl StyledRange {
+    pub fn new(range: Range<usize>, style: TextStyle) -> Self {
+        Self {
+            range,
+            style,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextSpan {
+    pub text: Arc<str>,
+    pub style: TextStyle,
+    /// 不为 None 时整段文本被内联框替换
+    pub inline_box: Option<InlineBox>,
+    /// 不为 None 时整段文本是一个链接
+    pub link: Option<LinkTarget>,
+}
+
+impl TextSpan {
+    pub fn new(text: impl Into<Arc<str>>, style: TextStyle) -> Self {
+        Self {
+            text: text.into(),
+            style,
+            inline_box: None,
+            link: None,
+        }
+    }
+
+    /// 使用默认链接样式的链接，style 会在链接样式之前应用
+    pub fn link(text: impl Into<Arc<str>>, target: LinkTarget) -> Self {
+        Self {
+            link: Some(target),
+            ..Self::plain(text)
+        }
+    }
+
+    /// 由一个 OBJECT_REPLACEMENT_CHARACTER 代表的内联框
+    pub fn inline(inline_box: InlineBox) -> Self {
+        Self {
+            text: OBJECT_REPLACEMENT_CHARACTER.to_string().into(),
+            style: TextStyle::default(),
+            inline_box: Some(inline_box),
+            link: None,
+        }
+    }
+
+    pub fn plain(text: impl Into<Arc<str>>) -> Self {
+        Self::new(text, TextStyle::default())
+    }
+}
+
+/// 由多段不同样式的文本组成的富文本
+/// 通过 into_parts 得到完整文本和样式范围，再交给 TextProperty，内联框和链接通过 inline_boxes 和 links 得到
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct RichText {
+    pub spans: Vec<TextSpan>,
+}
+
+impl RichText {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(mut self, span: TextSpan) -> Self {
+        self.spans.push(span);
+        self
+    }
+
+    pub fn text(&self) -> Arc<str> {
+        self.spans.iter().map(|span| span.text.as_ref()).collect::<String>().into()
+    }
+
+    /// 每段文本在完整文本中的字节范围及其样式，没有设置任何样式的段会被跳过
+    pub fn styled_ranges(&self) -> Vec<StyledRange> {
+        let mut start = 0;
+        let mut ranges = vec![];
+        for span in self.spans.iter() {
+            let end = start + span.text.len();
+            if span.style != TextStyle::default() {
+                ranges.push(StyledRange::new(start..end, span.style.clone()));
+            }
+            start = end;
+        }
+        ranges
+    }
+
+    /// 每个内联框在完整文本中的字节范围
+    pub fn inline_boxes(&self) -> Vec<InlineBoxRange> {
+        let mut start = 0;
+        let mut inline_boxes = vec![];
+        for span in self.spans.iter() {
+            let end = start + span.text.len();
+            if let Some(inline_box) = &span.inline_box {
+                inline_boxes.push(InlineBoxRange::new(start..end, inline_box.clone()));
+            }
+            start = end;
+        }
+        inline_boxes
+    }
+
+    /// 每个链接在完整文本中的字节范围，使用默认链接样式
+    pub fn links(&self) -> Vec<TextLink> {
+        let mut start = 0;
+        let mut links = vec![];
+        for span in self.spans.iter() {
+            let end = start + span.text.len();
+            if let Some(target) = &span.link {
+                links.push(TextLink::new(start..end, target.clone()));
+            }
+            start = end;
+        }
+        links
+    }
+
+    pub fn into_parts(self) -> (Arc<str>, Vec<StyledRange>) {
+        (self.text(), self.styled_ranges())
+    }
+}
+
+impl From<Vec<TextSpan>> for RichText {
+    fn from(spans: Vec<TextSpan>) -> Self {
+        Self { spans }
+    }
+}
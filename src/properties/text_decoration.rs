@@ -0,0 +1,330 @@
+use std::f32::consts::PI;
+use std::ops::Range;
+use typed_builder::TypedBuilder;
+use crate::{Color, Path};
+
+/// 装饰线相对于文字的位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DecorationLine {
+    #[default]
+    Underline,
+    Overline,
+    Strikethrough,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DecorationStyle {
+    #[default]
+    Solid,
+    /// 两条线，间距与线宽相同
+    Double,
+    Dotted,
+    Dashed,
+    /// 波浪线，可用于拼写检查
+    Wavy,
+}
+
+/// 文本装饰线，与 DirectWrite 自带的下划线不同，作为几何图形绘制
+#[derive(Debug, Clone, PartialEq, Default, TypedBuilder)]
+pub struct TextDecoration {
+    #[builder(default, setter(into))]
+    pub line: DecorationLine,
+    #[builder(default, setter(into))]
+    pub style: DecorationStyle,
+    /// 为 None 时使用 TextProperty::color
+    #[builder(default, setter(into))]
+    pub color: Option<Color>,
+    /// 为 None 时根据字号计算
+    #[builder(default, setter(into))]
+    pub thickness: Option<f32>,
+}
+
+/// 一行中需要绘制装饰线的部分
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DecorationSpan {
+    pub left: f32,
+    pub right: f32,
+    /// 所在行基线的纵坐标
+    pub baseline: f32,
+    /// 这段文字的字号
+    pub font_size: f32,
+}
+
+impl TextDecoration {
+    /// 实际使用的线宽，默认为字号的 1/14，至少为 1
+    pub fn resolved_thickness(&self, font_size: f32) -> f32 {
+        self.thickness.unwrap_or((font_size / 14.0).max(1.0))
+    }
+
+    /// 装饰线中心相对于基线的纵向偏移，向下为正
+    /// 没有读取字体中的下划线和删除线位置，按常见字体的比例近似
+    pub fn offset(&self, font_size: f32) -> f32 {
+        let thickness = self.resolved_thickness(font_size);
+        match self.line {
+            DecorationLine::Underline => font_size * 0.1 + thickness / 2.0,
+            DecorationLine::Overline => -font_size * 0.9 - thickness / 2.0,
+            DecorationLine::Strikethrough => -font_size * 0.3,
+        }
+    }
+
+    /// 装饰线的填充路径
+    pub fn path(&self, span: &DecorationSpan) -> Path {
+        let thickness = self.resolved_thickness(span.font_size);
+        let y = span.baseline + self.offset(span.font_size);
+        let (left, right) = (span.left.min(span.right), span.left.max(span.right));
+        match self.style {
+            DecorationStyle::Solid => solid_path(left, right, y, thickness),
+            DecorationStyle::Double => {
+                //两条线向远离文字的方向展开
+                let direction = match self.line {
+                    DecorationLine::Overline => -1.0,
+                    _ => 1.0,
+                };
+                let mut path = solid_path(left, right, y, thickness);
+                path.append(&solid_path(left, right, y + direction * thickness * 2.0, thickness));
+                path
+            }
+            DecorationStyle::Dotted => dotted_path(left, right, y, thickness),
+            DecorationStyle::Dashed => dashed_path(left, right, y, thickness * 3.0, thickness * 2.0, thickness),
+            DecorationStyle::Wavy => wavy_path(left, right, y, thickness),
+        }
+    }
+}
+
+/// 作用于 TextProperty::text 中一段字节范围的装饰线
+#[derive(Debug, Clone, PartialEq)]
+pub struct DecorationRange {
+    pub range: Range<usize>,
+    pub decoration: TextDecoration,
+}
+
+impl DecorationRange {
+    pub fn new(range: Range<usize>, decoration: TextDecoration) -> Self {
+        Self {
+            range,
+            decoration,
+        }
+    }
+}
+
+/// 中心在 y、宽为 thickness 的实线
+pub fn solid_path(left: f32, right: f32, y: f32, thickness: f32) -> Path {
+    let mut path = Path::new();
+    if right > left && thickness > 0.0 {
+        path.add_rect(left, y - thickness / 2.0, right, y + thickness / 2.0);
+    }
+    path
+}
+
+/// 长度为 dash、间隔为 gap 的虚线，最后一段截断到 right
+pub fn dashed_path(left: f32, right: f32, y: f32, dash: f32, gap: f32, thickness: f32) -> Path {
+    let mut path = Path::new();
+    if dash <= 0.0 {
+        return path;
+    }
+    let mut x = left;
+    while x < right {
+        path.append(&solid_path(x, (x + dash).min(right), y, thickness));
+        x += dash + gap.max(0.0);
+    }
+    path
+}
+
+/// 直径为 thickness 的圆点，间距与直径相同
+pub fn dotted_path(left: f32, right: f32, y: f32, thickness: f32) -> Path {
+    let mut path = Path::new();
+    if thickness <= 0.0 {
+        return path;
+    }
+    let radius = thickness / 2.0;
+    let mut x = left + radius;
+    while x + radius <= right {
+        path.add_ellipse((x, y), radius, radius);
+        x += thickness * 2.0;
+    }
+    path
+}
+
+/// 每个周期采样的点数
+const WAVE_SAMPLES: usize = 8;
+
+/// 中心线为正弦曲线、竖直方向宽为 thickness 的波浪线
+/// 波长为线宽的 4 倍，振幅与线宽相同，线宽小于 1 时按 1 计算波长和振幅
+pub fn wavy_path(left: f32, right: f32, y: f32, thickness: f32) -> Path {
+    let mut path = Path::new();
+    if right <= left || thickness <= 0.0 {
+        return path;
+    }
+    let wavelength = (thickness * 4.0).max(4.0);
+    let amplitude = thickness.max(1.0);
+    let count = ((right - left) / wavelength * WAVE_SAMPLES as f32).ceil().max(1.0) as usize;
+    let center = |index: usize| {
+        let x = left + (right - left) * index as f32 / count as f32;
+        (x, y + amplitude * (2.0 * PI * (x - left) / wavelength).sin())
+    };
+    //上边缘从左到右，下边缘从右到左，组成一个闭合的带状图形
+    let (x, cy) = center(0);
+    path.move_to((x, cy - thickness / 2.0));
+    for index in 1..=count {
+        let (x, cy) = center(index);
+        path.line_to((x, cy - thickness / 2.0));
+    }
+    for index in (0..=count).rev() {
+        let (x, cy) = center(index);
+        path.line_to((x, cy + thickness / 2.0));
+    }
+    path.close();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PathSegment, Point};
+
+    fn edges(path: &Path) -> (f32, f32, f32, f32) {
+        let bounds = path.bounds().unwrap();
+        (bounds.left, bounds.top, bounds.right, bounds.bottom)
+    }
+
+    /// 每个子路径的范围，包括控制点
+    fn figures(path: &Path) -> Vec<(f32, f32, f32, f32)> {
+        let mut figures: Vec<Vec<Point>> = vec![];
+        for segment in path.segments() {
+            match *segment {
+                PathSegment::MoveTo(point) => figures.push(vec![point]),
+                PathSegment::LineTo(point) => figures.last_mut().unwrap().push(point),
+                PathSegment::QuadTo(control, point) => figures.last_mut().unwrap().extend([control, point]),
+                PathSegment::CubicTo(control1, control2, point) => figures.last_mut().unwrap().extend([control1, control2, point]),
+                PathSegment::Close => {}
+            }
+        }
+        figures.iter().map(|points| points.iter().fold((f32::MAX, f32::MAX, f32::MIN, f32::MIN), |(left, top, right, bottom), Point(point)| {
+            (left.min(point.x), top.min(point.y), right.max(point.x), bottom.max(point.y))
+        })).collect()
+    }
+
+    fn points(path: &Path) -> Vec<(f32, f32)> {
+        path.segments().iter().filter_map(|segment| match *segment {
+            PathSegment::MoveTo(Point(point)) | PathSegment::LineTo(Point(point)) => Some((point.x, point.y)),
+            _ => None,
+        }).collect()
+    }
+
+    fn span(left: f32, right: f32) -> DecorationSpan {
+        DecorationSpan { left, right, baseline: 20.0, font_size: 14.0 }
+    }
+
+    #[test]
+    fn dashes_are_truncated_at_right() {
+        let path = dashed_path(0.0, 12.0, 5.0, 3.0, 2.0, 2.0);
+        assert_eq!(figures(&path), vec![(0.0, 4.0, 3.0, 6.0), (5.0, 4.0, 8.0, 6.0), (10.0, 4.0, 12.0, 6.0)]);
+        //间隔落在末尾时不会产生多余的虚线
+        assert_eq!(figures(&dashed_path(0.0, 9.0, 5.0, 3.0, 2.0, 2.0)).len(), 2);
+        assert!(dashed_path(0.0, 12.0, 5.0, 0.0, 2.0, 2.0).is_empty());
+    }
+
+    #[test]
+    fn dots_stay_inside_span() {
+        //半径为 1，圆心间隔 4
+        assert_eq!(figures(&dotted_path(0.0, 9.0, 5.0, 2.0)), vec![(0.0, 4.0, 2.0, 6.0), (4.0, 4.0, 6.0, 6.0)]);
+        let path = dotted_path(0.0, 10.0, 5.0, 2.0);
+        assert_eq!(figures(&path).len(), 3);
+        assert_eq!(edges(&path).2, 10.0);
+        //放不下一个点时为空
+        assert!(dotted_path(0.0, 1.5, 5.0, 2.0).is_empty());
+    }
+
+    #[test]
+    fn wavy_period_and_amplitude() {
+        //波长 8，振幅 2，每个周期 8 个采样点
+        let path = wavy_path(0.0, 40.0, 10.0, 2.0);
+        let points = points(&path);
+        let upper = &points[..41];
+        assert_eq!(upper[0], (0.0, 9.0));
+        for (index, (x, y)) in upper.iter().enumerate() {
+            assert_eq!(*x, index as f32);
+            let expected = 9.0 + 2.0 * (2.0 * PI * *x / 8.0).sin();
+            assert!((y - expected).abs() < 1e-4, "{x}: {y} != {expected}");
+        }
+        assert!((upper[2].1 - 11.0).abs() < 1e-4);
+        assert!((upper[6].1 - 7.0).abs() < 1e-4);
+        //下边缘与上边缘相差线宽
+        let lower = &points[41..];
+        assert_eq!(lower.len(), 41);
+        for ((upper_x, upper_y), (lower_x, lower_y)) in upper.iter().zip(lower.iter().rev()) {
+            assert_eq!(upper_x, lower_x);
+            assert!((lower_y - upper_y - 2.0).abs() < 1e-4);
+        }
+        let (left, top, right, bottom) = edges(&path);
+        assert_eq!((left, right), (0.0, 40.0));
+        assert!((top - 7.0).abs() < 1e-4 && (bottom - 13.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn thin_wave_uses_minimum_size() {
+        let (_, top, _, bottom) = edges(&wavy_path(0.0, 40.0, 10.0, 0.5));
+        assert!((top - 8.75).abs() < 1e-4 && (bottom - 11.25).abs() < 1e-4);
+    }
+
+    #[test]
+    fn wave_is_clipped_to_span() {
+        //不是整数个周期时最后一个点正好在 right
+        let path = wavy_path(3.0, 17.5, 10.0, 2.0);
+        let xs = points(&path).iter().map(|(x, _)| *x).collect::<Vec<f32>>();
+        assert_eq!(xs.first(), Some(&3.0));
+        assert!(xs.iter().all(|x| (3.0..=17.5).contains(x)));
+        assert_eq!(edges(&path).2, 17.5);
+    }
+
+    #[test]
+    fn empty_spans() {
+        for path in [
+            solid_path(10.0, 5.0, 0.0, 1.0),
+            dashed_path(10.0, 5.0, 0.0, 3.0, 2.0, 1.0),
+            dotted_path(10.0, 5.0, 0.0, 1.0),
+            wavy_path(10.0, 5.0, 0.0, 1.0),
+            wavy_path(5.0, 5.0, 0.0, 1.0),
+        ] {
+            assert!(path.is_empty());
+        }
+    }
+
+    #[test]
+    fn zero_thickness_draws_nothing() {
+        for style in [DecorationStyle::Solid, DecorationStyle::Double, DecorationStyle::Dotted, DecorationStyle::Dashed, DecorationStyle::Wavy] {
+            let decoration = TextDecoration::builder().style(style).thickness(0.0).build();
+            assert!(decoration.path(&span(0.0, 50.0)).is_empty(), "{style:?}");
+        }
+    }
+
+    #[test]
+    fn reversed_span_is_normalized() {
+        for style in [DecorationStyle::Solid, DecorationStyle::Double, DecorationStyle::Dotted, DecorationStyle::Dashed, DecorationStyle::Wavy] {
+            let decoration = TextDecoration::builder().style(style).build();
+            assert_eq!(decoration.path(&span(50.0, 10.0)), decoration.path(&span(10.0, 50.0)), "{style:?}");
+            assert!(!decoration.path(&span(50.0, 10.0)).is_empty());
+        }
+    }
+
+    #[test]
+    fn decoration_position() {
+        //字号 14 时线宽为 1
+        let underline = TextDecoration::builder().build();
+        assert_eq!(edges(&underline.path(&span(0.0, 50.0))), (0.0, 21.4, 50.0, 22.4));
+        let strikethrough = TextDecoration::builder().line(DecorationLine::Strikethrough).thickness(2.0).build();
+        let (_, top, _, bottom) = edges(&strikethrough.path(&span(0.0, 50.0)));
+        assert!((top - 14.8).abs() < 1e-4 && (bottom - 16.8).abs() < 1e-4);
+    }
+
+    #[test]
+    fn double_lines_spread_away_from_text() {
+        let underline = TextDecoration::builder().style(DecorationStyle::Double).thickness(2.0).build();
+        let lines = figures(&underline.path(&span(0.0, 50.0)));
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1].1 - lines[0].1, 4.0);
+        let overline = TextDecoration::builder().line(DecorationLine::Overline).style(DecorationStyle::Double).thickness(2.0).build();
+        let lines = figures(&overline.path(&span(0.0, 50.0)));
+        assert_eq!(lines[1].1 - lines[0].1, -4.0);
+    }
+}
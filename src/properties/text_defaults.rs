@@ -0,0 +1,204 @@
+use std::ops::RangeInclusive;
+use std::sync::Arc;
+use typed_builder::TypedBuilder;
+
+/// 用于选择回退字体的文字类别，每个类别对应一组 Unicode 范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Script {
+    Latin,
+    Greek,
+    Cyrillic,
+    Hebrew,
+    Arabic,
+    Devanagari,
+    Thai,
+    Hangul,
+    /// 汉字、假名、注音及全角标点
+    Cjk,
+    Emoji,
+}
+
+impl Script {
+    pub const ALL: [Script; 10] = [
+        Script::Latin,
+        Script::Greek,
+        Script::Cyrillic,
+        Script::Hebrew,
+        Script::Arabic,
+        Script::Devanagari,
+        Script::Thai,
+        Script::Hangul,
+        Script::Cjk,
+        Script::Emoji,
+    ];
+
+    pub fn ranges(&self) -> &'static [RangeInclusive<u32>] {
+        match self {
+            Script::Latin => &[0x0000..=0x024F, 0x1E00..=0x1EFF, 0x2C60..=0x2C7F, 0xA720..=0xA7FF],
+            Script::Greek => &[0x0370..=0x03FF, 0x1F00..=0x1FFF],
+            Script::Cyrillic => &[0x0400..=0x052F, 0x2DE0..=0x2DFF, 0xA640..=0xA69F],
+            Script::Hebrew => &[0x0590..=0x05FF, 0xFB1D..=0xFB4F],
+            Script::Arabic => &[0x0600..=0x06FF, 0x0750..=0x077F, 0x08A0..=0x08FF, 0xFB50..=0xFDFF, 0xFE70..=0xFEFF],
+            Script::Devanagari => &[0x0900..=0x097F, 0xA8E0..=0xA8FF],
+            Script::Thai => &[0x0E00..=0x0E7F],
+            Script::Hangul => &[0x1100..=0x11FF, 0x3130..=0x318F, 0xA960..=0xA97F, 0xAC00..=0xD7FF],
+            Script::Cjk => &[
+                0x2E80..=0x2FDF,
+                0x3000..=0x312F,
+                0x31A0..=0x31FF,
+                0x3200..=0x33FF,
+                0x3400..=0x4DBF,
+                0x4E00..=0x9FFF,
+                0xF900..=0xFAFF,
+                0xFE30..=0xFE4F,
+                0xFF00..=0xFFEF,
+                0x20000..=0x3134F,
+            ],
+            Script::Emoji => &[0x2600..=0x27BF, 0x1F000..=0x1FAFF],
+        }
+    }
+
+    pub fn contains(&self, c: char) -> bool {
+        self.ranges().iter().any(|range| range.contains(&(c as u32)))
+    }
+
+    /// 字符所属的类别，不属于任何类别时返回 None
+    pub fn of(c: char) -> Option<Script> {
+        Script::ALL.into_iter().find(|script| script.contains(c))
+    }
+}
+
+/// 一种文字的回退字体，按顺序尝试
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptFallback {
+    pub script: Script,
+    pub families: Vec<Arc<str>>,
+}
+
+impl ScriptFallback {
+    pub fn new<T: Into<Arc<str>>>(script: Script, families: impl IntoIterator<Item = T>) -> Self {
+        Self {
+            script,
+            families: families.into_iter().map(Into::into).collect(),
+        }
+    }
+}
+
+fn default_fallbacks() -> Vec<ScriptFallback> {
+    vec![
+        ScriptFallback::new(Script::Cjk, ["Microsoft YaHei UI", "Yu Gothic UI", "Microsoft JhengHei UI"]),
+        ScriptFallback::new(Script::Hangul, ["Malgun Gothic"]),
+        ScriptFallback::new(Script::Arabic, ["Segoe UI"]),
+        ScriptFallback::new(Script::Emoji, ["Segoe UI Emoji"]),
+    ]
+}
+
+/// TextProperty 没有指定字体和字号时使用的默认值，以及按文字选择的回退字体
+/// 回退字体都找不到时再使用系统的回退字体
+#[derive(Debug, Clone, PartialEq, TypedBuilder)]
+pub struct TextDefaults {
+    #[builder(default = "Segoe UI".into(), setter(into))]
+    pub font_family: Arc<str>,
+    #[builder(default = 14.0)]
+    pub font_size: f32,
+    /// 如 zh-CN、en-US，为空时使用系统的区域设置
+    #[builder(default, setter(into))]
+    pub locale: Arc<str>,
+    #[builder(default = default_fallbacks(), setter(into))]
+    pub fallbacks: Vec<ScriptFallback>,
+}
+
+impl Default for TextDefaults {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+impl TextDefaults {
+    /// 字符对应的回退字体，同一文字有多条设置时使用第一条
+    pub fn fallback_families(&self, c: char) -> &[Arc<str>] {
+        self.fallbacks.iter()
+            .find(|fallback| fallback.script.contains(c))
+            .map(|fallback| fallback.families.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// 字符在回退字体中的首选字体，没有设置时返回 None
+    pub fn resolve_family(&self, c: char) -> Option<&str> {
+        self.fallback_families(c).first().map(AsRef::as_ref)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn script_of_representative_chars() {
+        assert_eq!(Script::of('a'), Some(Script::Latin));
+        assert_eq!(Script::of('é'), Some(Script::Latin));
+        assert_eq!(Script::of('Ω'), Some(Script::Greek));
+        assert_eq!(Script::of('Ж'), Some(Script::Cyrillic));
+        assert_eq!(Script::of('א'), Some(Script::Hebrew));
+        assert_eq!(Script::of('ع'), Some(Script::Arabic));
+        assert_eq!(Script::of('\u{FEFB}'), Some(Script::Arabic));
+        assert_eq!(Script::of('क'), Some(Script::Devanagari));
+        assert_eq!(Script::of('ก'), Some(Script::Thai));
+        assert_eq!(Script::of('한'), Some(Script::Hangul));
+        assert_eq!(Script::of('ㄱ'), Some(Script::Hangul));
+        assert_eq!(Script::of('中'), Some(Script::Cjk));
+        assert_eq!(Script::of('の'), Some(Script::Cjk));
+        assert_eq!(Script::of('。'), Some(Script::Cjk));
+        assert_eq!(Script::of('\u{20BB7}'), Some(Script::Cjk));
+        assert_eq!(Script::of('😀'), Some(Script::Emoji));
+        assert_eq!(Script::of('☀'), Some(Script::Emoji));
+        assert_eq!(Script::of('\u{1F9E1}'), Some(Script::Emoji));
+        assert_eq!(Script::of('\u{E000}'), None);
+    }
+
+    #[test]
+    fn script_ranges_do_not_overlap() {
+        for (index, script) in Script::ALL.iter().enumerate() {
+            for other in &Script::ALL[index + 1..] {
+                for range in script.ranges() {
+                    for other_range in other.ranges() {
+                        let overlap = range.start() <= other_range.end() && other_range.start() <= range.end();
+                        assert!(!overlap, "{script:?} {range:?} overlaps {other:?} {other_range:?}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn resolve_family_uses_default_fallbacks() {
+        let defaults = TextDefaults::default();
+        assert_eq!(defaults.resolve_family('中'), Some("Microsoft YaHei UI"));
+        assert_eq!(defaults.resolve_family('한'), Some("Malgun Gothic"));
+        assert_eq!(defaults.resolve_family('😀'), Some("Segoe UI Emoji"));
+        assert_eq!(defaults.resolve_family('ع'), Some("Segoe UI"));
+        //没有设置回退字体的文字
+        assert_eq!(defaults.resolve_family('a'), None);
+        assert_eq!(defaults.resolve_family('ก'), None);
+    }
+
+    #[test]
+    fn resolve_family_picks_first_matching_fallback() {
+        let defaults = TextDefaults::builder()
+            .fallbacks(vec![
+                ScriptFallback::new(Script::Emoji, ["Noto Color Emoji"]),
+                ScriptFallback::new(Script::Cjk, ["Noto Sans CJK SC", "Source Han Sans"]),
+                ScriptFallback::new(Script::Cjk, ["SimSun"]),
+            ])
+            .build();
+        assert_eq!(defaults.resolve_family('中'), Some("Noto Sans CJK SC"));
+        assert_eq!(defaults.fallback_families('中').len(), 2);
+        assert_eq!(defaults.resolve_family('😀'), Some("Noto Color Emoji"));
+        assert_eq!(defaults.resolve_family('한'), None);
+        //第一条设置没有字体时不会继续查找
+        let defaults = TextDefaults::builder()
+            .fallbacks(vec![ScriptFallback::new(Script::Cjk, Vec::<&str>::new()), ScriptFallback::new(Script::Cjk, ["SimSun"])])
+            .build();
+        assert_eq!(defaults.resolve_family('中'), None);
+    }
+}
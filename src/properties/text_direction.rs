@@ -0,0 +1,365 @@
+use std::ops::Range;
+use windows::Win32::Graphics::DirectWrite::{DWRITE_FLOW_DIRECTION, DWRITE_FLOW_DIRECTION_BOTTOM_TO_TOP, DWRITE_FLOW_DIRECTION_LEFT_TO_RIGHT, DWRITE_FLOW_DIRECTION_RIGHT_TO_LEFT, DWRITE_FLOW_DIRECTION_TOP_TO_BOTTOM, DWRITE_READING_DIRECTION, DWRITE_READING_DIRECTION_BOTTOM_TO_TOP, DWRITE_READING_DIRECTION_LEFT_TO_RIGHT, DWRITE_READING_DIRECTION_RIGHT_TO_LEFT, DWRITE_READING_DIRECTION_TOP_TO_BOTTOM, DWRITE_VERTICAL_GLYPH_ORIENTATION, DWRITE_VERTICAL_GLYPH_ORIENTATION_DEFAULT, DWRITE_VERTICAL_GLYPH_ORIENTATION_STACKED};
+
+/// 一行之内文字的排列方向
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ReadingDirection {
+    /// 根据整个文本中第一个强方向字符决定，没有时从左到右
+    /// DirectWrite 的阅读方向作用于整个布局，多个段落使用同一个方向
+    #[default]
+    Auto,
+    LeftToRight,
+    RightToLeft,
+    /// 竖排，需要同时把 flow_direction 设置为 LeftToRight 或 RightToLeft，通常直接使用 WritingMode
+    /// 使用默认的 FlowDirection::TopToBottom 时 TextProperty 的 build 返回 TextPropertyError::ConflictingDirections
+    TopToBottom,
+}
+
+impl ReadingDirection {
+    /// 将 Auto 转换为具体的方向
+    pub fn resolve(&self, text: &str) -> ReadingDirection {
+        match self {
+            ReadingDirection::Auto => match detect_direction(text) {
+                Some(BidiDirection::RightToLeft) => ReadingDirection::RightToLeft,
+                _ => ReadingDirection::LeftToRight,
+            },
+            direction => *direction,
+        }
+    }
+
+    pub fn is_vertical(&self) -> bool {
+        *self == ReadingDirection::TopToBottom
+    }
+
+    /// 横排时行只能上下排列，竖排时列只能左右排列
+    pub fn accepts_flow(&self, flow_direction: FlowDirection) -> bool {
+        match flow_direction {
+            FlowDirection::TopToBottom | FlowDirection::BottomToTop => !self.is_vertical(),
+            FlowDirection::LeftToRight | FlowDirection::RightToLeft => self.is_vertical(),
+        }
+    }
+
+    /// 与阅读方向匹配的默认排列方向，竖排时与 WritingMode::VerticalRl 相同
+    pub fn default_flow(&self) -> FlowDirection {
+        match self.is_vertical() {
+            true => FlowDirection::RightToLeft,
+            false => FlowDirection::TopToBottom,
+        }
+    }
+}
+
+impl From<ReadingDirection> for DWRITE_READING_DIRECTION {
+    fn from(direction: ReadingDirection) -> Self {
+        match direction {
+            ReadingDirection::Auto | ReadingDirection::LeftToRight => DWRITE_READING_DIRECTION_LEFT_TO_RIGHT,
+            ReadingDirection::RightToLeft => DWRITE_READING_DIRECTION_RIGHT_TO_LEFT,
+            ReadingDirection::TopToBottom => DWRITE_READING_DIRECTION_TOP_TO_BOTTOM,
+        }
+    }
+}
+
+/// 行与行之间的排列方向
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum FlowDirection {
+    #[default]
+    TopToBottom,
+    BottomToTop,
+    LeftToRight,
+    RightToLeft,
+}
+
+impl From<FlowDirection> for DWRITE_FLOW_DIRECTION {
+    fn from(direction: FlowDirection) -> Self {
+        match direction {
+            FlowDirection::TopToBottom => DWRITE_FLOW_DIRECTION_TOP_TO_BOTTOM,
+            FlowDirection::BottomToTop => DWRITE_FLOW_DIRECTION_BOTTOM_TO_TOP,
+            FlowDirection::LeftToRight => DWRITE_FLOW_DIRECTION_LEFT_TO_RIGHT,
+            FlowDirection::RightToLeft => DWRITE_FLOW_DIRECTION_RIGHT_TO_LEFT,
+        }
+    }
+}
+
+/// 书写模式，与 CSS writing-mode 相同，竖排时覆盖 reading_direction 和 flow_direction
+/// 竖排时每一行是一列，行方向称为 inline，列的排列方向称为 block
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WritingMode {
+    /// 横排，行从上到下排列
+    #[default]
+    HorizontalTb,
+    /// 竖排，列从右到左排列，中文和日文的传统排版
+    VerticalRl,
+    /// 竖排，列从左到右排列，如蒙古文
+    VerticalLr,
+}
+
+impl WritingMode {
+    pub fn is_vertical(&self) -> bool {
+        *self != WritingMode::HorizontalTb
+    }
+
+    /// 把 (水平, 竖直) 方向的值转换为 (inline, block) 方向的值
+    pub fn to_logical<T>(&self, horizontal: T, vertical: T) -> (T, T) {
+        match self.is_vertical() {
+            true => (vertical, horizontal),
+            false => (horizontal, vertical),
+        }
+    }
+
+    /// 把 (inline, block) 方向的值转换为 (水平, 竖直) 方向的值
+    pub fn to_physical<T>(&self, inline: T, block: T) -> (T, T) {
+        match self.is_vertical() {
+            true => (block, inline),
+            false => (inline, block),
+        }
+    }
+
+    /// 竖排时使用的阅读方向和排列方向，横排时返回 None
+    pub(crate) fn vertical_directions(&self) -> Option<(ReadingDirection, FlowDirection)> {
+        match self {
+            WritingMode::HorizontalTb => None,
+            WritingMode::VerticalRl => Some((ReadingDirection::TopToBottom, FlowDirection::RightToLeft)),
+            WritingMode::VerticalLr => Some((ReadingDirection::TopToBottom, FlowDirection::LeftToRight)),
+        }
+    }
+
+    pub(crate) fn from_directions(reading_direction: DWRITE_READING_DIRECTION, flow_direction: DWRITE_FLOW_DIRECTION) -> Self {
+        match (reading_direction, flow_direction) {
+            (DWRITE_READING_DIRECTION_TOP_TO_BOTTOM | DWRITE_READING_DIRECTION_BOTTOM_TO_TOP, DWRITE_FLOW_DIRECTION_LEFT_TO_RIGHT) => WritingMode::VerticalLr,
+            (DWRITE_READING_DIRECTION_TOP_TO_BOTTOM | DWRITE_READING_DIRECTION_BOTTOM_TO_TOP, _) => WritingMode::VerticalRl,
+            _ => WritingMode::HorizontalTb,
+        }
+    }
+}
+
+/// 竖排时字形的方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GlyphOrientation {
+    /// 中日韩文字直立，拉丁字母等横排文字顺时针旋转 90 度
+    #[default]
+    Default,
+    /// 所有字形都直立堆叠
+    Stacked,
+}
+
+impl From<GlyphOrientation> for DWRITE_VERTICAL_GLYPH_ORIENTATION {
+    fn from(orientation: GlyphOrientation) -> Self {
+        match orientation {
+            GlyphOrientation::Default => DWRITE_VERTICAL_GLYPH_ORIENTATION_DEFAULT,
+            GlyphOrientation::Stacked => DWRITE_VERTICAL_GLYPH_ORIENTATION_STACKED,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BidiDirection {
+    LeftToRight,
+    RightToLeft,
+}
+
+/// 希伯来文等从右到左书写的文字，对应 Unicode 双向类别 R
+const RIGHT_TO_LEFT_RANGES: [Range<u32>; 5] = [
+    0x0590..0x0600,
+    0x07C0..0x0860,
+    0xFB1D..0xFB50,
+    0x10800..0x10D00,
+    0x1E800..0x1EC70,
+];
+
+/// 阿拉伯文等从右到左书写的文字，对应 Unicode 双向类别 AL
+const ARABIC_LETTER_RANGES: [Range<u32>; 7] = [
+    0x0600..0x0660,
+    0x066A..0x06F0,
+    0x06FA..0x07C0,
+    0x0860..0x0900,
+    0xFB50..0xFE00,
+    0xFE70..0xFF00,
+    0x1EC70..0x1F000,
+];
+
+/// 组合符号，双向类别为 NSM，其中一部分的 Alphabetic 属性为真，但不是字母
+/// 使用区块近似，包括通用的组合符号区块和从右到左文字中的符号
+const COMBINING_MARK_RANGES: [Range<u32>; 22] = [
+    0x0300..0x0370,
+    0x0483..0x048A,
+    0x0591..0x05C8,
+    0x0610..0x061B,
+    0x064B..0x0660,
+    0x0670..0x0671,
+    0x06D6..0x06DD,
+    0x06DF..0x06E5,
+    0x06E7..0x06E9,
+    0x06EA..0x06EE,
+    0x0711..0x0712,
+    0x0730..0x074B,
+    0x07A6..0x07B1,
+    0x07EB..0x07F4,
+    0x0816..0x082E,
+    0x0859..0x085C,
+    0x0898..0x08A0,
+    0x08CA..0x0903,
+    0x1AB0..0x1B00,
+    0x1DC0..0x1E00,
+    0x20D0..0x2100,
+    0xFE20..0xFE30,
+];
+
+const LEFT_TO_RIGHT_MARK: char = '\u{200E}';
+const RIGHT_TO_LEFT_MARK: char = '\u{200F}';
+const ARABIC_LETTER_MARK: char = '\u{061C}';
+/// LRI、RLI、FSI，直到对应的 PDI 之间的字符不参与段落方向的判断
+const ISOLATE_INITIATORS: [char; 3] = ['\u{2066}', '\u{2067}', '\u{2068}'];
+const POP_DIRECTIONAL_ISOLATE: char = '\u{2069}';
+
+/// 字符的强方向，中性字符（数字、标点、空白、组合符号等）返回 None
+/// 使用 Unicode 区块近似双向类别，不属于从右到左区块的字母都视为从左到右
+pub fn char_direction(c: char) -> Option<BidiDirection> {
+    let code = c as u32;
+    match c {
+        LEFT_TO_RIGHT_MARK => Some(BidiDirection::LeftToRight),
+        RIGHT_TO_LEFT_MARK | ARABIC_LETTER_MARK => Some(BidiDirection::RightToLeft),
+        _ if !is_letter(c) => None,
+        _ if RIGHT_TO_LEFT_RANGES.iter().chain(ARABIC_LETTER_RANGES.iter()).any(|range| range.contains(&code)) => Some(BidiDirection::RightToLeft),
+        _ => Some(BidiDirection::LeftToRight),
+    }
+}
+
+/// is_alphabetic 包含组合符号，排除后近似为字母类别
+fn is_letter(c: char) -> bool {
+    let code = c as u32;
+    c.is_alphabetic() && !COMBINING_MARK_RANGES.iter().any(|range| range.contains(&code))
+}
+
+/// 按 UAX #9 的规则 P2、P3 判断文本方向：跳过隔离区间，取第一个强方向字符的方向
+pub fn detect_direction(text: &str) -> Option<BidiDirection> {
+    let mut isolate_depth = 0usize;
+    for c in text.chars() {
+        if ISOLATE_INITIATORS.contains(&c) {
+            isolate_depth += 1;
+        } else if c == POP_DIRECTIONAL_ISOLATE {
+            isolate_depth = isolate_depth.saturating_sub(1);
+        } else if isolate_depth == 0 {
+            if let Some(direction) = char_direction(c) {
+                return Some(direction);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strong_characters() {
+        assert_eq!(char_direction('a'), Some(BidiDirection::LeftToRight));
+        assert_eq!(char_direction('中'), Some(BidiDirection::LeftToRight));
+        assert_eq!(char_direction('Ж'), Some(BidiDirection::LeftToRight));
+        assert_eq!(char_direction('א'), Some(BidiDirection::RightToLeft));
+        assert_eq!(char_direction('ب'), Some(BidiDirection::RightToLeft));
+        assert_eq!(char_direction('ߊ'), Some(BidiDirection::RightToLeft));
+        assert_eq!(char_direction(LEFT_TO_RIGHT_MARK), Some(BidiDirection::LeftToRight));
+        assert_eq!(char_direction(RIGHT_TO_LEFT_MARK), Some(BidiDirection::RightToLeft));
+        assert_eq!(char_direction(ARABIC_LETTER_MARK), Some(BidiDirection::RightToLeft));
+    }
+
+    #[test]
+    fn neutral_characters() {
+        for c in ['1', ' ', '.', '\n', '٣', '€', '😀'] {
+            assert_eq!(char_direction(c), None, "{c:?}");
+        }
+    }
+
+    #[test]
+    fn combining_marks_are_neutral() {
+        //希腊文下标 iota、阿拉伯文的开口符和叠音符、希伯来文的点符，is_alphabetic 都为真
+        for c in ['\u{0345}', '\u{064E}', '\u{0651}', '\u{05B0}', '\u{08F0}'] {
+            assert!(c.is_alphabetic(), "{c:?}");
+            assert_eq!(char_direction(c), None, "{c:?}");
+        }
+        assert_eq!(char_direction('\u{0301}'), None);
+    }
+
+    #[test]
+    fn right_to_left_text() {
+        assert_eq!(detect_direction("שלום"), Some(BidiDirection::RightToLeft));
+        assert_eq!(detect_direction("مرحبا"), Some(BidiDirection::RightToLeft));
+        assert_eq!(ReadingDirection::Auto.resolve("مرحبا"), ReadingDirection::RightToLeft);
+    }
+
+    #[test]
+    fn mixed_text_uses_first_strong_character() {
+        assert_eq!(detect_direction("hello שלום"), Some(BidiDirection::LeftToRight));
+        assert_eq!(detect_direction("שלום hello"), Some(BidiDirection::RightToLeft));
+        assert_eq!(ReadingDirection::Auto.resolve("مرحبا world"), ReadingDirection::RightToLeft);
+    }
+
+    #[test]
+    fn neutral_leading_characters_are_skipped() {
+        assert_eq!(detect_direction("123, (שלום)"), Some(BidiDirection::RightToLeft));
+        assert_eq!(detect_direction(" \u{064E}\u{0345}abc"), Some(BidiDirection::LeftToRight));
+        assert_eq!(detect_direction("123 !?"), None);
+        assert_eq!(detect_direction(""), None);
+        //没有强方向字符时从左到右
+        assert_eq!(ReadingDirection::Auto.resolve("123"), ReadingDirection::LeftToRight);
+    }
+
+    #[test]
+    fn isolates_are_skipped() {
+        assert_eq!(detect_direction("\u{2067}שלום\u{2069} hello"), Some(BidiDirection::LeftToRight));
+        assert_eq!(detect_direction("\u{2066}a\u{2068}b\u{2069}c\u{2069}א"), Some(BidiDirection::RightToLeft));
+        //没有对应 LRI 的 PDI 被忽略
+        assert_eq!(detect_direction("\u{2069}א"), Some(BidiDirection::RightToLeft));
+        //没有结束的隔离区间一直到文本末尾
+        assert_eq!(detect_direction("\u{2068}hello"), None);
+    }
+
+    #[test]
+    fn multiple_paragraphs_use_first_strong_character() {
+        assert_eq!(ReadingDirection::Auto.resolve("שלום\nhello"), ReadingDirection::RightToLeft);
+        assert_eq!(ReadingDirection::Auto.resolve("hello\nשלום\nمرحبا"), ReadingDirection::LeftToRight);
+        //前面的段落没有强方向字符时由后面的段落决定
+        assert_eq!(ReadingDirection::Auto.resolve("123\n\nשלום"), ReadingDirection::RightToLeft);
+        assert_eq!(ReadingDirection::LeftToRight.resolve("שלום"), ReadingDirection::LeftToRight);
+    }
+
+    #[test]
+    fn writing_mode_swaps_axes() {
+        assert_eq!(WritingMode::HorizontalTb.to_logical(30.0, 50.0), (30.0, 50.0));
+        assert_eq!(WritingMode::VerticalRl.to_logical(30.0, 50.0), (50.0, 30.0));
+        assert_eq!(WritingMode::VerticalLr.to_logical(30.0, 50.0), (50.0, 30.0));
+        for mode in [WritingMode::HorizontalTb, WritingMode::VerticalRl, WritingMode::VerticalLr] {
+            let (inline, block) = mode.to_logical(30.0, 50.0);
+            assert_eq!(mode.to_physical(inline, block), (30.0, 50.0));
+        }
+        assert!(!WritingMode::HorizontalTb.is_vertical());
+        assert!(WritingMode::VerticalRl.is_vertical() && WritingMode::VerticalLr.is_vertical());
+    }
+
+    #[test]
+    fn writing_mode_round_trips_through_directions() {
+        assert_eq!(WritingMode::HorizontalTb.vertical_directions(), None);
+        for mode in [WritingMode::VerticalRl, WritingMode::VerticalLr] {
+            let (reading_direction, flow_direction) = mode.vertical_directions().unwrap();
+            assert!(reading_direction.accepts_flow(flow_direction));
+            assert_eq!(WritingMode::from_directions(reading_direction.into(), flow_direction.into()), mode);
+        }
+        assert_eq!(WritingMode::from_directions(DWRITE_READING_DIRECTION_RIGHT_TO_LEFT, DWRITE_FLOW_DIRECTION_TOP_TO_BOTTOM), WritingMode::HorizontalTb);
+    }
+
+    #[test]
+    fn flow_must_cross_reading_direction() {
+        for reading_direction in [ReadingDirection::Auto, ReadingDirection::LeftToRight, ReadingDirection::RightToLeft] {
+            assert!(reading_direction.accepts_flow(FlowDirection::TopToBottom));
+            assert!(reading_direction.accepts_flow(FlowDirection::BottomToTop));
+            assert!(!reading_direction.accepts_flow(FlowDirection::LeftToRight));
+            assert!(reading_direction.accepts_flow(reading_direction.default_flow()));
+        }
+        let vertical = ReadingDirection::TopToBottom;
+        assert!(!vertical.accepts_flow(FlowDirection::TopToBottom));
+        assert!(!vertical.accepts_flow(FlowDirection::BottomToTop));
+        assert!(vertical.accepts_flow(FlowDirection::LeftToRight));
+        assert!(vertical.accepts_flow(FlowDirection::RightToLeft));
+        assert_eq!(vertical.default_flow(), FlowDirection::RightToLeft);
+    }
+}
@@ -0,0 +1,167 @@
+use std::ops::Range;
+use std::sync::Arc;
+use crate::{expand_to_char_boundaries, Color, StyledRange, TextStyle};
+
+/// 点击链接时的目标
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum LinkTarget {
+    Url(Arc<str>),
+    /// 由调用方自行解释的数据，如命令编号
+    Payload(u64),
+}
+
+/// 链接当前的交互状态，由调用方根据指针位置和点击记录设置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LinkState {
+    #[default]
+    Normal,
+    Hover,
+    Visited,
+}
+
+/// 可点击的一段文本
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextLink {
+    /// 在 TextProperty::text 中的字节范围，不在字符边界上时扩展到完整的字符
+    pub range: Range<usize>,
+    pub target: LinkTarget,
+    pub state: LinkState,
+    /// 所有状态下都使用的样式
+    pub style: TextStyle,
+    /// 悬停时覆盖 style 的样式
+    pub hover_style: TextStyle,
+    /// 访问过后覆盖 style 的样式
+    pub visited_style: TextStyle,
+}
+
+impl TextLink {
+    /// 默认为蓝色带下划线，悬停时变深，访问过后为紫色
+    pub fn new(range: Range<usize>, target: LinkTarget) -> Self {
+        Self {
+            range,
+            target,
+            state: LinkState::Normal,
+            style: TextStyle::builder().color(Color::from((0x06, 0x45, 0xAD))).underline(true).build(),
+            hover_style: TextStyle::builder().color(Color::from((0x04, 0x2E, 0x75))).build(),
+            visited_style: TextStyle::builder().color(Color::from((0x68, 0x1D, 0xA8))).build(),
+        }
+    }
+
+    pub fn url(range: Range<usize>, url: impl Into<Arc<str>>) -> Self {
+        Self::new(range, LinkTarget::Url(url.into()))
+    }
+
+    pub fn payload(range: Range<usize>, payload: u64) -> Self {
+        Self::new(range, LinkTarget::Payload(payload))
+    }
+
+    pub fn with_state(mut self, state: LinkState) -> Self {
+        self.state = state;
+        self
+    }
+
+    pub fn with_styles(mut self, style: TextStyle, hover_style: TextStyle, visited_style: TextStyle) -> Self {
+        self.style = style;
+        self.hover_style = hover_style;
+        self.visited_style = visited_style;
+        self
+    }
+
+    /// 当前状态下的样式
+    pub fn current_style(&self) -> TextStyle {
+        match self.state {
+            LinkState::Normal => self.style.clone(),
+            LinkState::Hover => self.style.merge(&self.hover_style),
+            LinkState::Visited => self.style.merge(&self.visited_style),
+        }
+    }
+
+    /// 链接在 text 中实际覆盖的字节范围
+    pub fn char_range(&self, text: &str) -> Range<usize> {
+        expand_to_char_boundaries(text, self.range.clone())
+    }
+
+    pub(crate) fn styled_range(&self, text: &str) -> StyledRange {
+        StyledRange::new(self.char_range(text), self.current_style())
+    }
+}
+
+/// 覆盖字节偏移 index 的链接在 links 中的下标，链接重叠时后面的优先，与样式的覆盖顺序一致
+pub fn link_at_index(text: &str, links: &[TextLink], index: usize) -> Option<usize> {
+    links.iter().rposition(|link| link.char_range(text).contains(&index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 😀 占 0..4、6..10、12..16 三段
+    const TEXT: &str = "😀ab😀cd😀";
+
+    #[test]
+    fn char_range_expands_to_char_boundaries() {
+        assert_eq!(TextLink::payload(4..10, 0).char_range(TEXT), 4..10);
+        //起点或终点落在表情中间时扩展到整个表情
+        assert_eq!(TextLink::payload(7..11, 0).char_range(TEXT), 6..11);
+        assert_eq!(TextLink::payload(4..7, 0).char_range(TEXT), 4..10);
+        assert_eq!(TextLink::payload(1..3, 0).char_range(TEXT), 0..4);
+        //超出文本的部分被截断
+        assert_eq!(TextLink::payload(13..20, 0).char_range(TEXT), 12..16);
+        assert_eq!(TextLink::payload(4..4, 0).char_range(TEXT), 4..4);
+    }
+
+    #[test]
+    fn styled_range_uses_char_range() {
+        let link = TextLink::payload(7..11, 0);
+        assert_eq!(link.styled_range(TEXT).range, 6..11);
+    }
+
+    #[test]
+    fn link_at_index_around_emoji() {
+        let links = [TextLink::payload(4..10, 0)];
+        //链接之前的表情
+        assert_eq!(link_at_index(TEXT, &links, 0), None);
+        assert_eq!(link_at_index(TEXT, &links, 3), None);
+        //链接内的文字和表情
+        assert_eq!(link_at_index(TEXT, &links, 4), Some(0));
+        assert_eq!(link_at_index(TEXT, &links, 8), Some(0));
+        //链接之后
+        assert_eq!(link_at_index(TEXT, &links, 10), None);
+        assert_eq!(link_at_index(TEXT, &links, 16), None);
+        assert_eq!(link_at_index(TEXT, &links, 100), None);
+    }
+
+    #[test]
+    fn link_at_index_inside_expanded_emoji() {
+        //范围从表情中间开始时，点击整个表情都命中链接
+        let links = [TextLink::payload(7..12, 0)];
+        assert_eq!(link_at_index(TEXT, &links, 6), Some(0));
+        assert_eq!(link_at_index(TEXT, &links, 5), None);
+        assert_eq!(link_at_index(TEXT, &links, 12), None);
+    }
+
+    #[test]
+    fn overlapping_links_prefer_later() {
+        let links = [TextLink::payload(4..10, 0), TextLink::payload(6..12, 1)];
+        assert_eq!(link_at_index(TEXT, &links, 5), Some(0));
+        assert_eq!(link_at_index(TEXT, &links, 6), Some(1));
+        assert_eq!(link_at_index(TEXT, &links, 11), Some(1));
+        assert_eq!(link_at_index(TEXT, &links, 12), None);
+    }
+
+    #[test]
+    fn adjacent_links_do_not_share_boundary() {
+        let links = [TextLink::payload(4..6, 0), TextLink::payload(6..10, 1)];
+        assert_eq!(link_at_index(TEXT, &links, 5), Some(0));
+        assert_eq!(link_at_index(TEXT, &links, 6), Some(1));
+        assert_eq!(link_at_index(TEXT, &links, 9), Some(1));
+        assert_eq!(link_at_index(TEXT, &links, 10), None);
+    }
+
+    #[test]
+    fn empty_link_never_matches() {
+        let links = [TextLink::payload(4..4, 0)];
+        assert_eq!(link_at_index(TEXT, &links, 4), None);
+        assert_eq!(link_at_index(TEXT, &[], 4), None);
+    }
+}
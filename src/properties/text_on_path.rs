@@ -0,0 +1,174 @@
+use crate::{Path, PathMeasure, Point};
+
+/// 文字在路径的哪一侧，与 SVG textPath 的 side 相同
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PathSide {
+    /// 沿路径方向前进，文字在路径左侧，即水平向右的路径上文字是正的
+    #[default]
+    Left,
+    /// 相当于把路径反向后放在左侧
+    Right,
+}
+
+/// 一个字簇在路径上的位置
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClusterPlacement {
+    /// 字簇在 advances 中的下标
+    pub index: usize,
+    /// 字簇基线起点的位置
+    pub origin: Point,
+    /// 字簇绕 origin 旋转的角度，单位为度，顺时针为正
+    pub angle: f32,
+}
+
+/// 把宽度为 advances 的字簇依次放在路径上，第一个字簇从距离路径开头 offset 处开始
+/// 每个字簇的中点落在路径上，并旋转到该点的切线方向，中点超出路径的字簇不会返回
+pub fn place_clusters(advances: &[f32], path: &Path, offset: f32, side: PathSide) -> Vec<ClusterPlacement> {
+    let measure = PathMeasure::new(path);
+    let length = measure.length();
+    let mut start = offset;
+    let mut placements = vec![];
+    for (index, advance) in advances.iter().enumerate() {
+        let middle = start + advance / 2.0;
+        start += advance;
+        let distance = match side {
+            PathSide::Left => middle,
+            PathSide::Right => length - middle,
+        };
+        let Some((Point(point), angle)) = measure.point_at(distance) else {
+            continue;
+        };
+        let angle = match side {
+            PathSide::Left => angle,
+            PathSide::Right => angle + 180.0,
+        };
+        //中点在路径上，起点沿切线方向后退半个字簇宽度
+        let (sin, cos) = angle.to_radians().sin_cos();
+        placements.push(ClusterPlacement {
+            index,
+            origin: Point::new(point.x - cos * advance / 2.0, point.y - sin * advance / 2.0),
+            angle,
+        });
+    }
+    placements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(from: (f32, f32), to: (f32, f32)) -> Path {
+        let mut path = Path::new();
+        path.move_to(from);
+        path.line_to(to);
+        path
+    }
+
+    fn assert_placement(placement: &ClusterPlacement, index: usize, (x, y): (f32, f32), angle: f32) {
+        let Point(origin) = placement.origin;
+        assert_eq!(placement.index, index);
+        assert!((origin.x - x).abs() < 1e-3 && (origin.y - y).abs() < 1e-3, "{placement:?}");
+        assert!((placement.angle - angle).abs() < 1e-3, "{placement:?}");
+    }
+
+    #[test]
+    fn horizontal_path() {
+        let placements = place_clusters(&[10.0, 20.0, 10.0], &line((0.0, 10.0), (100.0, 10.0)), 0.0, PathSide::Left);
+        assert_eq!(placements.len(), 3);
+        //起点按宽度依次排列，不旋转
+        assert_placement(&placements[0], 0, (0.0, 10.0), 0.0);
+        assert_placement(&placements[1], 1, (10.0, 10.0), 0.0);
+        assert_placement(&placements[2], 2, (30.0, 10.0), 0.0);
+    }
+
+    #[test]
+    fn vertical_path() {
+        let placements = place_clusters(&[10.0], &line((0.0, 0.0), (0.0, 100.0)), 0.0, PathSide::Left);
+        assert_placement(&placements[0], 0, (0.0, 0.0), 90.0);
+    }
+
+    #[test]
+    fn right_side_reverses_path() {
+        let placements = place_clusters(&[10.0, 20.0, 10.0], &line((0.0, 10.0), (100.0, 10.0)), 0.0, PathSide::Right);
+        assert_eq!(placements.len(), 3);
+        //从路径末尾开始向左排列，旋转 180 度后起点在字簇右侧
+        assert_placement(&placements[0], 0, (100.0, 10.0), 180.0);
+        assert_placement(&placements[1], 1, (90.0, 10.0), 180.0);
+        assert_placement(&placements[2], 2, (70.0, 10.0), 180.0);
+    }
+
+    #[test]
+    fn offset_moves_clusters() {
+        let placements = place_clusters(&[10.0, 20.0, 10.0], &line((0.0, 10.0), (100.0, 10.0)), 50.0, PathSide::Left);
+        assert_placement(&placements[0], 0, (50.0, 10.0), 0.0);
+        assert_placement(&placements[1], 1, (60.0, 10.0), 0.0);
+        assert_placement(&placements[2], 2, (80.0, 10.0), 0.0);
+    }
+
+    #[test]
+    fn clusters_off_path_are_dropped() {
+        let path = line((0.0, 10.0), (100.0, 10.0));
+        //中点依次在 15、45、75、105，最后一个超出路径
+        let placements = place_clusters(&[30.0; 4], &path, 0.0, PathSide::Left);
+        assert_eq!(placements.iter().map(|placement| placement.index).collect::<Vec<usize>>(), vec![0, 1, 2]);
+        //负的偏移使前面的字簇落在路径开头之前
+        let placements = place_clusters(&[10.0; 3], &path, -20.0, PathSide::Left);
+        assert_eq!(placements.len(), 1);
+        assert_placement(&placements[0], 2, (0.0, 10.0), 0.0);
+        assert!(place_clusters(&[10.0], &Path::new(), 0.0, PathSide::Left).is_empty());
+    }
+
+    /// 圆心在 (50, 50)、半径为 40 的圆，从最右边开始顺时针
+    fn badge() -> Path {
+        let mut path = Path::new();
+        path.add_ellipse((50.0, 50.0), 40.0, 40.0);
+        path
+    }
+
+    /// 角度的差，结果在 -180..180 之间
+    fn angle_difference(a: f32, b: f32) -> f32 {
+        (a - b + 180.0).rem_euclid(360.0) - 180.0
+    }
+
+    #[test]
+    fn clusters_follow_circle_tangent() {
+        let path = badge();
+        let length = PathMeasure::new(&path).length();
+        //顶部在 3/4 处，中间的字簇中点正好在顶部
+        let placements = place_clusters(&[10.0; 5], &path, length * 0.75 - 25.0, PathSide::Left);
+        assert_eq!(placements.len(), 5);
+        for (placement, index) in placements.iter().zip(0..) {
+            //中点到顶部的弧长为 (index - 2) * 10，切线角度为对应的圆心角
+            let expected = ((index as f32 - 2.0) * 10.0 / 40.0).to_degrees();
+            assert!(angle_difference(placement.angle, expected).abs() < 3.0, "{placement:?} != {expected}");
+            //起点沿切线后退半个字簇后，中点落在圆上
+            let Point(origin) = placement.origin;
+            let (sin, cos) = placement.angle.to_radians().sin_cos();
+            let (x, y) = (origin.x + cos * 5.0, origin.y + sin * 5.0);
+            assert!(((x - 50.0).hypot(y - 50.0) - 40.0).abs() < 0.1, "{placement:?}");
+        }
+        //切线角度取自拆分曲线得到的线段，起点会有少量偏差
+        let Point(middle) = placements[2].origin;
+        assert!((middle.x - 45.0).abs() < 0.3 && (middle.y - 10.0).abs() < 0.3, "{middle:?}");
+        //左右对称
+        assert!((placements[0].angle + placements[4].angle).abs() < 3.0);
+        assert!(placements[0].angle < placements[1].angle && placements[1].angle < placements[3].angle && placements[3].angle < placements[4].angle);
+    }
+
+    #[test]
+    fn right_side_reads_along_bottom_of_circle() {
+        let path = badge();
+        let length = PathMeasure::new(&path).length();
+        //底部在 1/4 处，反向后中间的字簇中点落在底部，文字从左到右排列且是正的
+        let placements = place_clusters(&[10.0; 5], &path, length * 0.75 - 25.0, PathSide::Right);
+        assert_eq!(placements.len(), 5);
+        let xs = placements.iter().map(|placement| placement.origin.0.x).collect::<Vec<f32>>();
+        assert!(xs.windows(2).all(|pair| pair[0] < pair[1]), "{xs:?}");
+        assert!(angle_difference(placements[2].angle, 0.0).abs() < 3.0, "{:?}", placements[2]);
+        //底部的圆弧两端向上翘，左边的字簇顺时针旋转，右边的逆时针旋转
+        assert!(angle_difference(placements[0].angle, 0.0) > 20.0);
+        assert!(angle_difference(placements[4].angle, 0.0) < -20.0);
+        let Point(middle) = placements[2].origin;
+        assert!((middle.x - 45.0).abs() < 0.3 && (middle.y - 90.0).abs() < 0.3, "{middle:?}");
+    }
+}
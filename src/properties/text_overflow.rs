@@ -0,0 +1,168 @@
+use windows::Win32::Graphics::DirectWrite::{DWRITE_TRIMMING, DWRITE_TRIMMING_GRANULARITY_CHARACTER, DWRITE_TRIMMING_GRANULARITY_NONE, DWRITE_TRIMMING_GRANULARITY_WORD, DWRITE_WORD_WRAPPING, DWRITE_WORD_WRAPPING_CHARACTER, DWRITE_WORD_WRAPPING_EMERGENCY_BREAK, DWRITE_WORD_WRAPPING_NO_WRAP, DWRITE_WORD_WRAPPING_WHOLE_WORD, DWRITE_WORD_WRAPPING_WRAP};
+
+/// 换行方式
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum TextWrap {
+    /// 不换行
+    None,
+    /// 只在单词之间换行，单词超出宽度时溢出
+    Word,
+    /// 可以在任意字符之间换行
+    Character,
+    /// 优先在单词之间换行，单词超出宽度时在单词内部换行
+    #[default]
+    EmergencyBreak,
+}
+
+impl From<TextWrap> for DWRITE_WORD_WRAPPING {
+    fn from(wrap: TextWrap) -> Self {
+        match wrap {
+            TextWrap::None => DWRITE_WORD_WRAPPING_NO_WRAP,
+            TextWrap::Word => DWRITE_WORD_WRAPPING_WHOLE_WORD,
+            TextWrap::Character => DWRITE_WORD_WRAPPING_CHARACTER,
+            TextWrap::EmergencyBreak => DWRITE_WORD_WRAPPING_EMERGENCY_BREAK,
+        }
+    }
+}
+
+impl TryFrom<DWRITE_WORD_WRAPPING> for TextWrap {
+    type Error = DWRITE_WORD_WRAPPING;
+
+    fn try_from(wrapping: DWRITE_WORD_WRAPPING) -> Result<Self, Self::Error> {
+        match wrapping {
+            DWRITE_WORD_WRAPPING_NO_WRAP => Ok(TextWrap::None),
+            DWRITE_WORD_WRAPPING_WHOLE_WORD => Ok(TextWrap::Word),
+            DWRITE_WORD_WRAPPING_CHARACTER => Ok(TextWrap::Character),
+            // WRAP 与 EMERGENCY_BREAK 的行为相同
+            DWRITE_WORD_WRAPPING_WRAP | DWRITE_WORD_WRAPPING_EMERGENCY_BREAK => Ok(TextWrap::EmergencyBreak),
+            wrapping => Err(wrapping),
+        }
+    }
+}
+
+/// 文本超出布局范围时的省略方式
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum TextTrimming {
+    #[default]
+    None,
+    /// 在字符边界处省略并显示省略号
+    CharacterEllipsis,
+    /// 在单词边界处省略并显示省略号
+    WordEllipsis,
+    /// 保留最后一个分隔符之后的部分，在前面省略，适合文件路径，如 `C:\...\file.txt`
+    PathEllipsis(char),
+}
+
+impl TextTrimming {
+    pub fn path() -> Self {
+        TextTrimming::PathEllipsis('\\')
+    }
+}
+
+impl From<TextTrimming> for DWRITE_TRIMMING {
+    fn from(trimming: TextTrimming) -> Self {
+        let (granularity, delimiter, delimiter_count) = match trimming {
+            TextTrimming::None => (DWRITE_TRIMMING_GRANULARITY_NONE, 0, 0),
+            TextTrimming::CharacterEllipsis => (DWRITE_TRIMMING_GRANULARITY_CHARACTER, 0, 0),
+            TextTrimming::WordEllipsis => (DWRITE_TRIMMING_GRANULARITY_WORD, 0, 0),
+            TextTrimming::PathEllipsis(delimiter) => (DWRITE_TRIMMING_GRANULARITY_CHARACTER, delimiter as u32, 1),
+        };
+        DWRITE_TRIMMING {
+            granularity,
+            delimiter,
+            delimiterCount: delimiter_count,
+        }
+    }
+}
+
+impl TryFrom<DWRITE_TRIMMING> for TextTrimming {
+    type Error = DWRITE_TRIMMING;
+
+    fn try_from(trimming: DWRITE_TRIMMING) -> Result<Self, Self::Error> {
+        match (trimming.granularity, trimming.delimiterCount, char::from_u32(trimming.delimiter)) {
+            (DWRITE_TRIMMING_GRANULARITY_NONE, 0, _) => Ok(TextTrimming::None),
+            (DWRITE_TRIMMING_GRANULARITY_CHARACTER, 0, _) => Ok(TextTrimming::CharacterEllipsis),
+            (DWRITE_TRIMMING_GRANULARITY_WORD, 0, _) => Ok(TextTrimming::WordEllipsis),
+            (DWRITE_TRIMMING_GRANULARITY_CHARACTER, 1, Some(delimiter)) => Ok(TextTrimming::PathEllipsis(delimiter)),
+            _ => Err(trimming),
+        }
+    }
+}
+
+/// 计算最多显示 max_lines 行时需要的高度，行数没有超出时返回 None
+pub fn max_lines_height(line_heights: &[f32], max_lines: u32) -> Option<f32> {
+    if line_heights.len() <= max_lines as usize {
+        return None;
+    }
+    Some(line_heights.iter().take(max_lines as usize).sum())
+}
+
+#[cfg(test)]
+mod tests {
+    use windows::Win32::Graphics::DirectWrite::DWRITE_TRIMMING_GRANULARITY;
+    use super::*;
+
+    #[test]
+    fn wrap_round_trip() {
+        for wrap in [TextWrap::None, TextWrap::Word, TextWrap::Character, TextWrap::EmergencyBreak] {
+            assert_eq!(TextWrap::try_from(DWRITE_WORD_WRAPPING::from(wrap)), Ok(wrap));
+        }
+    }
+
+    #[test]
+    fn wrap_from_dwrite() {
+        assert_eq!(TextWrap::try_from(DWRITE_WORD_WRAPPING_WRAP), Ok(TextWrap::EmergencyBreak));
+        assert_eq!(TextWrap::try_from(DWRITE_WORD_WRAPPING(100)), Err(DWRITE_WORD_WRAPPING(100)));
+    }
+
+    #[test]
+    fn trimming_round_trip() {
+        for trimming in [
+            TextTrimming::None,
+            TextTrimming::CharacterEllipsis,
+            TextTrimming::WordEllipsis,
+            TextTrimming::path(),
+            TextTrimming::PathEllipsis('/'),
+            TextTrimming::PathEllipsis('文'),
+        ] {
+            assert_eq!(TextTrimming::try_from(DWRITE_TRIMMING::from(trimming)), Ok(trimming));
+        }
+    }
+
+    #[test]
+    fn trimming_to_dwrite() {
+        let trimming = DWRITE_TRIMMING::from(TextTrimming::PathEllipsis('/'));
+        assert_eq!(trimming.granularity, DWRITE_TRIMMING_GRANULARITY_CHARACTER);
+        assert_eq!(trimming.delimiter, '/' as u32);
+        assert_eq!(trimming.delimiterCount, 1);
+        let trimming = DWRITE_TRIMMING::from(TextTrimming::WordEllipsis);
+        assert_eq!((trimming.granularity, trimming.delimiter, trimming.delimiterCount), (DWRITE_TRIMMING_GRANULARITY_WORD, 0, 0));
+    }
+
+    #[test]
+    fn trimming_from_invalid_dwrite() {
+        let invalid = [
+            //分隔符不是有效的字符
+            DWRITE_TRIMMING { granularity: DWRITE_TRIMMING_GRANULARITY_CHARACTER, delimiter: 0xD800, delimiterCount: 1 },
+            //只支持保留一个分隔符之后的部分
+            DWRITE_TRIMMING { granularity: DWRITE_TRIMMING_GRANULARITY_CHARACTER, delimiter: '/' as u32, delimiterCount: 2 },
+            DWRITE_TRIMMING { granularity: DWRITE_TRIMMING_GRANULARITY_WORD, delimiter: '/' as u32, delimiterCount: 1 },
+            DWRITE_TRIMMING { granularity: DWRITE_TRIMMING_GRANULARITY(100), delimiter: 0, delimiterCount: 0 },
+        ];
+        for trimming in invalid {
+            assert_eq!(TextTrimming::try_from(trimming), Err(trimming));
+        }
+    }
+
+    #[test]
+    fn max_lines_height_sums_visible_lines() {
+        let line_heights = [10.0, 12.0, 14.0];
+        assert_eq!(max_lines_height(&line_heights, 1), Some(10.0));
+        assert_eq!(max_lines_height(&line_heights, 2), Some(22.0));
+        assert_eq!(max_lines_height(&line_heights, 0), Some(0.0));
+        //行数没有超出时不限制高度
+        assert_eq!(max_lines_height(&line_heights, 3), None);
+        assert_eq!(max_lines_height(&line_heights, 4), None);
+        assert_eq!(max_lines_height(&[], 1), None);
+    }
+}
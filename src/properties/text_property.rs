@@ -1,20 +1,28 @@
+use std::fmt::{Display, Formatter};
 use std::sync::Arc;
 use typed_builder::TypedBuilder;
-use windows::Win32::Graphics::DirectWrite::{DWRITE_FONT_STRETCH, DWRITE_FONT_STRETCH_CONDENSED, DWRITE_FONT_STRETCH_EXPANDED, DWRITE_FONT_STRETCH_EXTRA_CONDENSED, DWRITE_FONT_STRETCH_EXTRA_EXPANDED, DWRITE_FONT_STRETCH_MEDIUM, DWRITE_FONT_STRETCH_NORMAL, DWRITE_FONT_STRETCH_SEMI_CONDENSED, DWRITE_FONT_STRETCH_SEMI_EXPANDED, DWRITE_FONT_STRETCH_ULTRA_CONDENSED, DWRITE_FONT_STRETCH_ULTRA_EXPANDED, DWRITE_FONT_STYLE, DWRITE_FONT_STYLE_ITALIC, DWRITE_FONT_STYLE_NORMAL, DWRITE_FONT_STYLE_OBLIQUE, DWRITE_FONT_WEIGHT, DWRITE_FONT_WEIGHT_BLACK, DWRITE_FONT_WEIGHT_BOLD, DWRITE_FONT_WEIGHT_DEMI_BOLD, DWRITE_FONT_WEIGHT_EXTRA_BLACK, DWRITE_FONT_WEIGHT_EXTRA_BOLD, DWRITE_FONT_WEIGHT_EXTRA_LIGHT, DWRITE_FONT_WEIGHT_HEAVY, DWRITE_FONT_WEIGHT_LIGHT, DWRITE_FONT_WEIGHT_MEDIUM, DWRITE_FONT_WEIGHT_NORMAL, DWRITE_FONT_WEIGHT_REGULAR, DWRITE_FONT_WEIGHT_SEMI_BOLD, DWRITE_FONT_WEIGHT_SEMI_LIGHT, DWRITE_FONT_WEIGHT_THIN, DWRITE_FONT_WEIGHT_ULTRA_BLACK, DWRITE_FONT_WEIGHT_ULTRA_BOLD, DWRITE_FONT_WEIGHT_ULTRA_LIGHT};
-use crate::{Color, Direct2DPoint};
+use windows::Win32::Graphics::DirectWrite::{DWRITE_FONT_STRETCH, DWRITE_FONT_STRETCH_CONDENSED, DWRITE_FONT_STRETCH_EXPANDED, DWRITE_FONT_STRETCH_EXTRA_CONDENSED, DWRITE_FONT_STRETCH_EXTRA_EXPANDED, DWRITE_FONT_STRETCH_MEDIUM, DWRITE_FONT_STRETCH_NORMAL, DWRITE_FONT_STRETCH_SEMI_CONDENSED, DWRITE_FONT_STRETCH_SEMI_EXPANDED, DWRITE_FONT_STRETCH_ULTRA_CONDENSED, DWRITE_FONT_STRETCH_ULTRA_EXPANDED, DWRITE_FONT_STYLE, DWRITE_FONT_STYLE_ITALIC, DWRITE_FONT_STYLE_NORMAL, DWRITE_FONT_STYLE_OBLIQUE, DWRITE_FONT_WEIGHT, DWRITE_FONT_WEIGHT_BLACK, DWRITE_FONT_WEIGHT_BOLD, DWRITE_FONT_WEIGHT_DEMI_BOLD, DWRITE_FONT_WEIGHT_EXTRA_BLACK, DWRITE_FONT_WEIGHT_EXTRA_BOLD, DWRITE_FONT_WEIGHT_EXTRA_LIGHT, DWRITE_FONT_WEIGHT_HEAVY, DWRITE_FONT_WEIGHT_LIGHT, DWRITE_FONT_WEIGHT_MEDIUM, DWRITE_FONT_WEIGHT_NORMAL, DWRITE_FONT_WEIGHT_REGULAR, DWRITE_FONT_WEIGHT_SEMI_BOLD, DWRITE_FONT_WEIGHT_SEMI_LIGHT, DWRITE_FONT_WEIGHT_THIN, DWRITE_FONT_WEIGHT_ULTRA_BLACK, DWRITE_FONT_WEIGHT_ULTRA_BOLD, DWRITE_FONT_WEIGHT_ULTRA_LIGHT, DWRITE_TEXT_ALIGNMENT, DWRITE_TEXT_ALIGNMENT_CENTER, DWRITE_TEXT_ALIGNMENT_LEADING, DWRITE_TEXT_ALIGNMENT_TRAILING};
+use crate::{BaselinePolicy, Color, DecorationRange, Direct2DPoint, InlineBoxRange, LineHeight, TextLink, FlowDirection, FontFeature, FontVariation, GlyphOrientation, ReadingDirection, StyledRange, TextStroke, TextTrimming, TextWrap, WritingMode};
 
+/// build 返回 Result，属性之间互相冲突时返回 TextPropertyError
+/// 直接修改字段得到的冲突属性不会报错，绘制和测量时忽略冲突的部分，可以用 validate 检查
 #[derive(Debug, Clone, PartialEq, TypedBuilder)]
+#[builder(build_method(into = TextPropertyResult))]
 pub struct TextProperty {
     #[builder(setter(into))]
     pub text: Arc<str>,
-    #[builder(setter(into))]
-    pub font_size: f32,
+    /// 为 None 时使用 TextDefaults::font_size
+    #[builder(default, setter(into))]
+    pub font_size: Option<f32>,
     #[builder(default, setter(into))]
     pub width: Option<f32>,
     #[builder(default, setter(into))]
     pub height: Option<f32>,
+    /// 传入 f32 时为字号的倍数
+    #[builder(default, setter(into))]
+    pub line_height: LineHeight,
     #[builder(default, setter(into))]
-    pub line_height: Option<f32>,
+    pub baseline_policy: BaselinePolicy,
     #[builder(default, setter(into))]
     pub underline: bool,
     #[builder(default, setter(into))]
@@ -25,14 +33,158 @@ pub struct TextProperty {
     pub font_weight: FontWeight,
     #[builder(default, setter(into))]
     pub position: Direct2DPoint,
+    /// 为 None 时使用 TextDefaults::font_family
     #[builder(default, setter(into))]
     pub font_family: Option<Arc<str>>,
+    /// 渐变以布局后文字的实际范围（TextMetrics 的 left、top、width、height）作为图形范围
+    /// 与 styles 中的文字颜色相同，不受布局框大小影响
     #[builder(default, setter(into))]
     pub color: Color,
     #[builder(default, setter(into))]
     pub font_style: FontStyle,
     #[builder(default, setter(into))]
     pub font_stretch: FontStretch,
+    /// 按字节范围覆盖部分文本的样式，可由 RichText::into_parts 生成
+    #[builder(default, setter(into))]
+    pub styles: Vec<StyledRange>,
+    /// 为 None 时只填充不描边
+    #[builder(default, setter(into))]
+    pub stroke: Option<TextStroke>,
+    /// 按字节范围绘制的装饰线，在文字之后绘制，与 underline 和 TextStyle 中的下划线、删除线相互独立
+    #[builder(default, setter(into))]
+    pub decorations: Vec<DecorationRange>,
+    /// 可点击的文本，样式覆盖 styles 中的样式，可由 RichText::links 生成
+    #[builder(default, setter(into))]
+    pub links: Vec<TextLink>,
+    /// 按字节范围用内联框替换部分文本，可由 RichText::inline_boxes 生成
+    #[builder(default, setter(into))]
+    pub inline_boxes: Vec<InlineBoxRange>,
+    #[builder(default, setter(into))]
+    pub wrap: TextWrap,
+    /// 需要设置 width 或 height 才会生效
+    #[builder(default, setter(into))]
+    pub trimming: TextTrimming,
+    /// 超出的行会被裁剪，同时设置 trimming 时最后一行显示省略号
+    #[builder(default, setter(into))]
+    pub max_lines: Option<u32>,
+    /// 如 ar-EG、he-IL，影响字形选择和换行规则，为 None 时使用 TextDefaults::locale
+    #[builder(default, setter(into))]
+    pub locale: Option<Arc<str>>,
+    #[builder(default, setter(into))]
+    pub reading_direction: ReadingDirection,
+    /// 需要与 reading_direction 匹配，横排时为 TopToBottom 或 BottomToTop，竖排时为 LeftToRight 或 RightToLeft
+    #[builder(default, setter(into))]
+    pub flow_direction: FlowDirection,
+    /// 竖排时 align 作用于列内的竖直方向，vertical_align 作用于列的排列方向
+    /// width 和 height 仍然是水平和竖直方向的尺寸，自动换行由 height 决定
+    #[builder(default, setter(into))]
+    pub writing_mode: WritingMode,
+    /// 只在竖排时生效
+    #[builder(default, setter(into))]
+    pub glyph_orientation: GlyphOrientation,
+    /// 每个字符后增加的间距，可以为负数
+    #[builder(default, setter(into))]
+    pub letter_spacing: f32,
+    /// 每个空格后额外增加的间距，与 letter_spacing 叠加
+    #[builder(default, setter(into))]
+    pub word_spacing: f32,
+    #[builder(default, setter(into))]
+    pub font_features: Vec<FontFeature>,
+    /// 只对可变字体生效，需要 Windows 10 1809 及以上版本
+    #[builder(default, setter(into))]
+    pub font_variations: Vec<FontVariation>,
+    /// 每个段落第一行的缩进
+    #[builder(default, setter(into))]
+    pub first_line_indent: f32,
+    /// 每个段落中自动换行产生的行的缩进
+    #[builder(default, setter(into))]
+    pub hanging_indent: f32,
+    /// 相邻段落之间的间距，第一段之前和最后一段之后不加间距
+    /// 只支持 LineHeight::Default，使用其他行高时 build 返回 TextPropertyError::ParagraphSpacingWithFixedLineHeight
+    /// 间距加在每段最后一行的下方，计入 LineMetrics::height，TextLayoutInfo 的光标和选区矩形不包含间距
+    #[builder(default, setter(into))]
+    pub paragraph_spacing: f32,
+    /// 制表符对齐的间隔，为 None 时为字号的 4 倍
+    #[builder(default, setter(into))]
+    pub tab_stop: Option<f32>,
+}
+
+/// TextProperty::builder 的 build 的返回类型
+pub type TextPropertyResult = Result<TextProperty, TextPropertyError>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TextPropertyError {
+    /// 段落间距只支持 LineHeight::Default
+    ParagraphSpacingWithFixedLineHeight,
+    /// 横排时 flow_direction 只能为 TopToBottom 或 BottomToTop，竖排时只能为 LeftToRight 或 RightToLeft
+    ConflictingDirections(ReadingDirection, FlowDirection),
+}
+
+impl Display for TextPropertyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TextPropertyError::ParagraphSpacingWithFixedLineHeight => write!(f, "paragraph spacing requires LineHeight::Default"),
+            TextPropertyError::ConflictingDirections(reading_direction, flow_direction) => {
+                write!(f, "reading direction {:?} cannot be combined with flow direction {:?}", reading_direction, flow_direction)
+            }
+        }
+    }
+}
+
+impl std::error::Error for TextPropertyError {}
+
+impl From<TextProperty> for TextPropertyResult {
+    fn from(text_property: TextProperty) -> Self {
+        text_property.validate().map(|_| text_property)
+    }
+}
+
+impl TextProperty {
+    /// 检查属性之间是否冲突，与 build 的检查相同
+    pub fn validate(&self) -> Result<(), TextPropertyError> {
+        if self.paragraph_spacing != 0.0 && !self.line_height.supports_paragraph_spacing() {
+            return Err(TextPropertyError::ParagraphSpacingWithFixedLineHeight);
+        }
+        //竖排的 writing_mode 会覆盖这两个方向
+        if !self.writing_mode.is_vertical() && !self.reading_direction.accepts_flow(self.flow_direction) {
+            return Err(TextPropertyError::ConflictingDirections(self.reading_direction, self.flow_direction));
+        }
+        Ok(())
+    }
+
+    /// 实际使用的 (阅读方向, 排列方向)，竖排时由 writing_mode 决定
+    /// 两者冲突时忽略 flow_direction，使用与阅读方向匹配的默认排列方向
+    pub(crate) fn resolved_directions(&self) -> (ReadingDirection, FlowDirection) {
+        if let Some(directions) = self.writing_mode.vertical_directions() {
+            return directions;
+        }
+        let reading_direction = self.reading_direction.resolve(&self.text);
+        match reading_direction.accepts_flow(self.flow_direction) {
+            true => (reading_direction, self.flow_direction),
+            false => (reading_direction, reading_direction.default_flow()),
+        }
+    }
+
+    /// 实际的书写模式，writing_mode 为 HorizontalTb 时也可能由 reading_direction 和 flow_direction 组成竖排
+    pub(crate) fn resolved_writing_mode(&self) -> WritingMode {
+        let (reading_direction, flow_direction) = self.resolved_directions();
+        WritingMode::from_directions(reading_direction.into(), flow_direction.into())
+    }
+
+    /// 实际生效的段落间距，行高不支持段落间距时为 0
+    pub(crate) fn resolved_paragraph_spacing(&self) -> f32 {
+        match self.line_height.supports_paragraph_spacing() {
+            true => self.paragraph_spacing,
+            false => 0.0,
+        }
+    }
+
+    /// styles 加上各个链接当前状态的样式，按顺序应用
+    pub fn resolved_styles(&self) -> Vec<StyledRange> {
+        self.styles.iter().cloned()
+            .chain(self.links.iter().map(|link| link.styled_range(&self.text)))
+            .collect()
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -40,11 +192,31 @@ pub enum TextAlign {
     Left,
     Center,
     Right,
+    /// 从左到右的文本靠左，从右到左的文本靠右
+    Start,
+    /// 从左到右的文本靠右，从右到左的文本靠左
+    End,
 }
 
 impl Default for TextAlign {
     fn default() -> Self {
-        Self::Left
+        Self::Start
+    }
+}
+
+impl TextAlign {
+    /// 转换为相对阅读方向的对齐方式，Left 和 Right 在从右到左的文本中需要对调
+    pub(crate) fn to_dwrite(&self, reading_direction: ReadingDirection) -> DWRITE_TEXT_ALIGNMENT {
+        let right_to_left = reading_direction == ReadingDirection::RightToLeft;
+        match self {
+            TextAlign::Start => DWRITE_TEXT_ALIGNMENT_LEADING,
+            TextAlign::End => DWRITE_TEXT_ALIGNMENT_TRAILING,
+            TextAlign::Center => DWRITE_TEXT_ALIGNMENT_CENTER,
+            TextAlign::Left if right_to_left => DWRITE_TEXT_ALIGNMENT_TRAILING,
+            TextAlign::Left => DWRITE_TEXT_ALIGNMENT_LEADING,
+            TextAlign::Right if right_to_left => DWRITE_TEXT_ALIGNMENT_LEADING,
+            TextAlign::Right => DWRITE_TEXT_ALIGNMENT_TRAILING,
+        }
     }
 }
 
@@ -96,6 +268,26 @@ impl From<i32> for FontWeight {
     }
 }
 
+impl From<DWRITE_FONT_WEIGHT> for FontWeight {
+    /// 数值相同的名称只会得到其中一个，如 400 总是得到 Normal
+    fn from(weight: DWRITE_FONT_WEIGHT) -> Self {
+        match weight {
+            DWRITE_FONT_WEIGHT_THIN => FontWeight::Thin,
+            DWRITE_FONT_WEIGHT_EXTRA_LIGHT => FontWeight::ExtraLight,
+            DWRITE_FONT_WEIGHT_LIGHT => FontWeight::Light,
+            DWRITE_FONT_WEIGHT_SEMI_LIGHT => FontWeight::SemiLight,
+            DWRITE_FONT_WEIGHT_NORMAL => FontWeight::Normal,
+            DWRITE_FONT_WEIGHT_MEDIUM => FontWeight::Medium,
+            DWRITE_FONT_WEIGHT_SEMI_BOLD => FontWeight::SemiBold,
+            DWRITE_FONT_WEIGHT_BOLD => FontWeight::Bold,
+            DWRITE_FONT_WEIGHT_EXTRA_BOLD => FontWeight::ExtraBold,
+            DWRITE_FONT_WEIGHT_BLACK => FontWeight::Black,
+            DWRITE_FONT_WEIGHT_EXTRA_BLACK => FontWeight::ExtraBlack,
+            DWRITE_FONT_WEIGHT(weight) => FontWeight::Custom(weight),
+        }
+    }
+}
+
 impl Into<DWRITE_FONT_WEIGHT> for FontWeight {
     fn into(self) -> DWRITE_FONT_WEIGHT {
         match self {
@@ -134,6 +326,16 @@ impl Default for FontStyle {
     }
 }
 
+impl From<DWRITE_FONT_STYLE> for FontStyle {
+    fn from(style: DWRITE_FONT_STYLE) -> Self {
+        match style {
+            DWRITE_FONT_STYLE_OBLIQUE => FontStyle::Oblique,
+            DWRITE_FONT_STYLE_ITALIC => FontStyle::Italic,
+            _ => FontStyle::Normal,
+        }
+    }
+}
+
 impl Into<DWRITE_FONT_STYLE> for FontStyle {
     fn into(self) -> DWRITE_FONT_STYLE {
         match self {
@@ -172,6 +374,23 @@ impl From<i32> for FontStretch {
     }
 }
 
+impl From<DWRITE_FONT_STRETCH> for FontStretch {
+    fn from(stretch: DWRITE_FONT_STRETCH) -> Self {
+        match stretch {
+            DWRITE_FONT_STRETCH_ULTRA_CONDENSED => FontStretch::UltraCondensed,
+            DWRITE_FONT_STRETCH_EXTRA_CONDENSED => FontStretch::ExtraCondensed,
+            DWRITE_FONT_STRETCH_CONDENSED => FontStretch::Condensed,
+            DWRITE_FONT_STRETCH_SEMI_CONDENSED => FontStretch::SemiCondensed,
+            DWRITE_FONT_STRETCH_NORMAL => FontStretch::Normal,
+            DWRITE_FONT_STRETCH_SEMI_EXPANDED => FontStretch::SemiExpanded,
+            DWRITE_FONT_STRETCH_EXPANDED => FontStretch::Expanded,
+            DWRITE_FONT_STRETCH_EXTRA_EXPANDED => FontStretch::ExtraExpanded,
+            DWRITE_FONT_STRETCH_ULTRA_EXPANDED => FontStretch::UltraExpanded,
+            DWRITE_FONT_STRETCH(stretch) => FontStretch::Custom(stretch),
+        }
+    }
+}
+
 impl Into<DWRITE_FONT_STRETCH> for FontStretch {
     fn into(self) -> DWRITE_FONT_STRETCH {
         match self {
@@ -0,0 +1,52 @@
+use typed_builder::TypedBuilder;
+use windows::Win32::Graphics::Direct2D::{D2D1_LINE_JOIN, D2D1_LINE_JOIN_BEVEL, D2D1_LINE_JOIN_MITER_OR_BEVEL, D2D1_LINE_JOIN_ROUND};
+use crate::Color;
+
+/// 描边在拐角处的连接方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StrokeJoin {
+    /// 尖角，过尖时改为斜角
+    #[default]
+    Miter,
+    Bevel,
+    Round,
+}
+
+impl From<StrokeJoin> for D2D1_LINE_JOIN {
+    fn from(join: StrokeJoin) -> Self {
+        match join {
+            StrokeJoin::Miter => D2D1_LINE_JOIN_MITER_OR_BEVEL,
+            StrokeJoin::Bevel => D2D1_LINE_JOIN_BEVEL,
+            StrokeJoin::Round => D2D1_LINE_JOIN_ROUND,
+        }
+    }
+}
+
+/// 描边和填充的绘制顺序
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PaintOrder {
+    /// 描边画在填充之上，描边的一半会盖住文字
+    #[default]
+    StrokeOverFill,
+    /// 描边画在填充之下，文字保持原来的粗细，常用于字幕
+    FillOverStroke,
+}
+
+/// 文字轮廓的描边，文字本身仍然用 TextProperty::color 填充
+#[derive(Debug, Clone, PartialEq, TypedBuilder)]
+pub struct TextStroke {
+    #[builder(setter(into))]
+    pub color: Color,
+    #[builder(default = 1.0, setter(into))]
+    pub width: f32,
+    #[builder(default, setter(into))]
+    pub join: StrokeJoin,
+    #[builder(default, setter(into))]
+    pub paint_order: PaintOrder,
+}
+
+impl TextStroke {
+    pub fn new(color: impl Into<Color>, width: f32) -> Self {
+        Self::builder().color(color).width(width).build()
+    }
+}
@@ -0,0 +1,145 @@
+use std::ops::Range;
+use std::sync::Arc;
+use windows::core::Result;
+use windows::Win32::Foundation::BOOL;
+use windows::Win32::Graphics::DirectWrite::{DWRITE_HIT_TEST_METRICS, DWRITE_TEXT_METRICS, IDWriteTextLayout};
+use crate::{byte_to_utf16, get_line_metrics, hit_test_metrics, to_text_range, utf16_to_byte, Direct2DPoint, Direct2DRect, InlineBoxMetrics, InlineBoxRange, LineMetrics, link_at_index, text_outline, Path, TextLink, Rect, TextMetrics, WritingMode};
+
+pub struct TextLayoutInfo {
+    pub layout: IDWriteTextLayout,
+    /// 创建布局时使用的文本，下面方法中的索引都是这段文本中的字节偏移
+    pub text: Arc<str>,
+    pub metrics: TextMetrics,
+    pub line_metrics: Vec<LineMetrics>,
+    /// 实际生效的段落间距，计入了每段最后一行的高度
+    paragraph_spacing: f32,
+}
+
+impl TextLayoutInfo {
+    pub(crate) unsafe fn from_layout(layout: IDWriteTextLayout, text: Arc<str>, paragraph_spacing: f32) -> Result<Self> {
+        let mut metrics = DWRITE_TEXT_METRICS::default();
+        layout.GetMetrics(&mut metrics)?;
+        let raw_line_metrics = get_line_metrics(&layout)?;
+        let writing_mode = WritingMode::from_directions(layout.GetReadingDirection(), layout.GetFlowDirection());
+        Ok(Self {
+            metrics: TextMetrics::from_raw(&metrics, writing_mode),
+            line_metrics: LineMetrics::from_raw(&text, &raw_line_metrics),
+            layout,
+            text,
+            paragraph_spacing,
+        })
+    }
+
+    /// 去掉 index 所在行中段落间距占用的部分，间距在行叠加方向的末尾
+    fn exclude_paragraph_spacing(&self, index: usize, mut rect: Direct2DRect) -> Rect {
+        let paragraph_end = self.line_metrics.get(self.line_for_index(index))
+            .is_some_and(|line_metrics| self.text[line_metrics.range.clone()].ends_with('\n'));
+        if paragraph_end {
+            let spacing = self.paragraph_spacing;
+            match self.metrics.writing_mode {
+                WritingMode::HorizontalTb => rect.bottom = (rect.bottom - spacing).max(rect.top),
+                WritingMode::VerticalRl => rect.left = (rect.left + spacing).min(rect.right),
+                WritingMode::VerticalLr => rect.right = (rect.right - spacing).max(rect.left),
+            }
+        }
+        Rect::new((rect.left, rect.top), rect.right - rect.left, rect.bottom - rect.top)
+    }
+
+    /// 根据布局内的坐标找到对应的字符
+    /// 返回 (字符起始的字节偏移, 是否落在字符的后半部分, 坐标是否在文本内)
+    /// 光标应该放在 trailing 为 true 时的下一个字符处
+    pub fn hit_test_point(&self, x: f32, y: f32) -> (usize, bool, bool) {
+        let mut trailing = BOOL::default();
+        let mut inside = BOOL::default();
+        let mut metrics = DWRITE_HIT_TEST_METRICS::default();
+        unsafe {
+            self.layout.HitTestPoint(x, y, &mut trailing, &mut inside, &mut metrics).unwrap();
+        }
+        let index = utf16_to_byte(&self.text, metrics.textPosition as usize);
+        (index, trailing.as_bool(), inside.as_bool())
+    }
+
+    /// 光标放在 index 处时的位置，宽度为 0，高度为所在行的高度，不包括段落间距
+    /// 竖排时为横向的光标，高度为 0，宽度为所在列的宽度
+    pub fn caret_rect(&self, index: usize) -> Rect {
+        let index = index.min(self.text.len());
+        let position = byte_to_utf16(&self.text, index);
+        let (mut x, mut y) = (0.0, 0.0);
+        let mut metrics = DWRITE_HIT_TEST_METRICS::default();
+        unsafe {
+            self.layout.HitTestTextPosition(position as u32, false, &mut x, &mut y, &mut metrics).unwrap();
+        }
+        let rect = match self.metrics.writing_mode.is_vertical() {
+            true => Direct2DRect { left: metrics.left, top: y, right: metrics.left + metrics.width, bottom: y },
+            false => Direct2DRect { left: x, top: y, right: x, bottom: y + metrics.height },
+        };
+        self.exclude_paragraph_spacing(index, rect)
+    }
+
+    /// 选中一段字节范围时需要绘制的矩形，每行至少一个，不包括段落间距
+    pub fn selection_rects(&self, range: Range<usize>) -> Vec<Rect> {
+        let range = range.start.min(self.text.len())..range.end.min(self.text.len());
+        if range.is_empty() {
+            return vec![];
+        }
+        let text_range = to_text_range(&self.text, range);
+        let origin = Direct2DPoint { x: 0.0, y: 0.0 };
+        unsafe {
+            hit_test_metrics(&self.layout, text_range, origin).unwrap()
+        }
+            .into_iter()
+            .map(|metrics| {
+                let index = utf16_to_byte(&self.text, metrics.textPosition as usize);
+                let rect = Direct2DRect {
+                    left: metrics.left,
+                    top: metrics.top,
+                    right: metrics.left + metrics.width,
+                    bottom: metrics.top + metrics.height,
+                };
+                self.exclude_paragraph_spacing(index, rect)
+            })
+            .collect()
+    }
+
+    /// index 所在的行号，从 0 开始，超出文本长度时返回最后一行
+    pub fn line_for_index(&self, index: usize) -> usize {
+        self.line_metrics.iter()
+            .position(|line_metrics| index < line_metrics.range.end)
+            .unwrap_or(self.line_metrics.len().saturating_sub(1))
+    }
+
+    /// 内联框在布局中的区域，需要传入创建布局时使用的内联框
+    /// 用于自行绘制 InlineBoxContent::Placeholder 的内容
+    pub fn inline_box_rect(&self, inline_box: &InlineBoxRange) -> Rect {
+        let start = inline_box.range.start.min(self.text.len());
+        let (mut x, mut y) = (0.0, 0.0);
+        let mut metrics = DWRITE_HIT_TEST_METRICS::default();
+        unsafe {
+            self.layout.HitTestTextPosition(byte_to_utf16(&self.text, start) as u32, false, &mut x, &mut y, &mut metrics).unwrap();
+        }
+        let line_baseline = self.line_metrics.get(self.line_for_index(start)).map(|line_metrics| line_metrics.baseline).unwrap_or_default();
+        let InlineBoxMetrics { width, height, .. } = inline_box.inline_box.metrics;
+        Rect::new((metrics.left, metrics.top + inline_box.inline_box.metrics.top_in_line(line_baseline)), width, height)
+    }
+
+    /// 布局内坐标下的链接在 links 中的下标，需要传入创建布局时使用的链接
+    /// 坐标不在文字上时返回 None，因此行尾之后的空白处不会命中链接
+    pub fn link_at(&self, links: &[TextLink], x: f32, y: f32) -> Option<usize> {
+        match self.hit_test_point(x, y) {
+            (index, _, true) => link_at_index(&self.text, links, index),
+            _ => None,
+        }
+    }
+
+    /// 链接覆盖的区域，跨行时每行一个矩形，可用于绘制焦点框
+    pub fn link_rects(&self, link: &TextLink) -> Vec<Rect> {
+        self.selection_rects(link.char_range(&self.text))
+    }
+
+    /// 所有文字、下划线和删除线的轮廓，原点为布局左上角，不包括内联框
+    pub fn outline(&self) -> Path {
+        unsafe {
+            text_outline(&self.layout, Direct2DPoint { x: 0.0, y: 0.0 }).unwrap()
+        }
+    }
+}
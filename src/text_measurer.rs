@@ -0,0 +1,611 @@
+use windows::core::Result;
+use crate::{create_write_factory, is_word_separator, ContextHolder, FontAdvances, FontCollection, FontFileError, LineHeight, LineMetrics, ReadingDirection, TextAlign, TextDefaults, TextLayoutInfo, TextMetrics, TextProperty, TextTrimming, TextResources, TextVerticalAlign, TextWrap, WritingMode};
+
+/// 文本测量的结果
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct TextMeasurement {
+    pub metrics: TextMetrics,
+    pub line_metrics: Vec<LineMetrics>,
+}
+
+impl From<TextLayoutInfo> for TextMeasurement {
+    fn from(info: TextLayoutInfo) -> Self {
+        Self {
+            metrics: info.metrics,
+            line_metrics: info.line_metrics,
+        }
+    }
+}
+
+/// 测量文本的尺寸和换行，布局代码依赖这个 trait 时可以在没有 DirectWrite 的环境中使用 FallbackMeasurer
+pub trait MeasureText {
+    fn measure(&self, text_property: &TextProperty) -> Result<TextMeasurement>;
+}
+
+/// 只依赖 DirectWrite 工厂的文本测量，不需要窗口和 D3D 设备
+/// 与 ContextHolder 不同，DirectWrite 的错误会返回给调用方而不是 panic
+pub struct TextMeasurer {
+    text_resources: TextResources,
+}
+
+impl TextMeasurer {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            text_resources: TextResources::new(create_write_factory()?)?,
+        })
+    }
+
+    pub fn register_font_collection(&mut self, font_collection: FontCollection) -> Result<()> {
+        self.text_resources.register_font_collection(font_collection)
+    }
+
+    pub fn text_defaults(&self) -> &TextDefaults {
+        self.text_resources.defaults()
+    }
+
+    pub fn set_text_defaults(&mut self, text_defaults: TextDefaults) -> Result<()> {
+        self.text_resources.set_defaults(text_defaults)
+    }
+
+    pub fn create_text_layout(&self, text_property: TextProperty) -> Result<TextLayoutInfo> {
+        unsafe {
+            let text = text_property.text.clone();
+            let paragraph_spacing = text_property.resolved_paragraph_spacing();
+            let layout = self.text_resources.create_text_layout(text_property)?;
+            TextLayoutInfo::from_layout(layout, text, paragraph_spacing)
+        }
+    }
+}
+
+impl MeasureText for TextMeasurer {
+    fn measure(&self, text_property: &TextProperty) -> Result<TextMeasurement> {
+        self.create_text_layout(text_property.clone()).map(TextMeasurement::from)
+    }
+}
+
+impl MeasureText for ContextHolder {
+    fn measure(&self, text_property: &TextProperty) -> Result<TextMeasurement> {
+        self.try_create_text_layout(text_property.clone()).map(TextMeasurement::from)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum FallbackAdvances {
+    /// 以字号为单位的固定宽度、上升和下降高度
+    Fixed {
+        advance: f32,
+        ascent: f32,
+        descent: f32,
+    },
+    Font(FontAdvances),
+}
+
+/// 不依赖系统的确定性文本测量，用于无界面的测试
+/// 不进行字形整形和字体回退，忽略 styles 中的样式，所有字符都使用 TextProperty 的字号
+/// 竖排时所有字符都按横排的宽度沿列排列，相当于把整个布局旋转
+#[derive(Debug, Clone, PartialEq)]
+pub struct FallbackMeasurer {
+    advances: FallbackAdvances,
+    /// TextProperty::font_size 为 None 时使用
+    default_font_size: f32,
+}
+
+impl FallbackMeasurer {
+    /// 每个字符宽度都为 advance * font_size，上升高度为 0.8 倍字号，下降高度为 0.2 倍字号
+    pub fn fixed(advance: f32) -> Self {
+        Self {
+            advances: FallbackAdvances::Fixed {
+                advance,
+                ascent: 0.8,
+                descent: 0.2,
+            },
+            default_font_size: TextDefaults::default().font_size,
+        }
+    }
+
+    /// 使用字体文件中的字形宽度和行高
+    pub fn from_font(font: FontAdvances) -> Self {
+        Self {
+            advances: FallbackAdvances::Font(font),
+            default_font_size: TextDefaults::default().font_size,
+        }
+    }
+
+    pub fn with_default_font_size(mut self, font_size: f32) -> Self {
+        self.default_font_size = font_size;
+        self
+    }
+
+    pub fn from_font_data(data: &[u8]) -> std::result::Result<Self, FontFileError> {
+        FontAdvances::parse(data).map(Self::from_font)
+    }
+
+    fn advance(&self, c: char, font_size: f32) -> f32 {
+        match c {
+            c if c.is_control() => 0.0,
+            c => match &self.advances {
+                FallbackAdvances::Fixed { advance, .. } => advance * font_size,
+                FallbackAdvances::Font(font) => font.advance(c, font_size),
+            },
+        }
+    }
+
+    /// 返回 (基线位置, 行高)
+    fn line_box(&self, font_size: f32) -> (f32, f32) {
+        match &self.advances {
+            FallbackAdvances::Fixed { ascent, descent, .. } => (ascent * font_size, (ascent + descent) * font_size),
+            FallbackAdvances::Font(font) => {
+                let ascent = font.ascent(font_size);
+                (ascent, ascent + font.descent(font_size) + font.line_gap(font_size))
+            }
+        }
+    }
+}
+
+impl MeasureText for FallbackMeasurer {
+    fn measure(&self, text_property: &TextProperty) -> Result<TextMeasurement> {
+        Ok(FallbackMeasurer::measure(self, text_property))
+    }
+}
+
+impl FallbackMeasurer {
+    /// 不会失败，与 MeasureText::measure 相同但不需要处理错误
+    pub fn measure(&self, text_property: &TextProperty) -> TextMeasurement {
+        let text = text_property.text.as_ref();
+        let font_size = text_property.font_size.unwrap_or(self.default_font_size);
+        //内联框的宽度计入被替换文本的第一个字符
+        let inline_box_at = |index: usize| text_property.inline_boxes.iter().find(|inline_box| inline_box.range.contains(&index));
+        //与 DirectWrite 相同，默认的制表位间隔为字号的 4 倍
+        let tab_stop = text_property.tab_stop.unwrap_or(font_size * 4.0);
+        //x 为字符在行内的起始位置，包括缩进
+        let advance = |index: usize, c: char, x: f32| {
+            if let Some(inline_box) = inline_box_at(index) {
+                return match inline_box.range.start == index {
+                    true => inline_box.inline_box.metrics.width + text_property.letter_spacing,
+                    false => 0.0,
+                };
+            }
+            match c {
+                '\n' | '\r' => 0.0,
+                //制表符前进到 x 之后的下一个制表位
+                '\t' if tab_stop > 0.0 => ((x / tab_stop).floor() + 1.0) * tab_stop - x + text_property.letter_spacing,
+                '\t' => text_property.letter_spacing,
+                c if is_word_separator(c) => self.advance(c, font_size) + text_property.letter_spacing + text_property.word_spacing,
+                c => self.advance(c, font_size) + text_property.letter_spacing,
+            }
+        };
+        let (natural_baseline, natural_height) = self.line_box(font_size);
+        let height = match text_property.line_height {
+            LineHeight::Proportional(multiplier) => natural_height * multiplier,
+            line_height => line_height.uniform_height(font_size).unwrap_or(natural_height),
+        };
+        let baseline = text_property.baseline_policy.baseline(height, natural_baseline, natural_height);
+        let paragraph_spacing = text_property.resolved_paragraph_spacing();
+        let indents = (text_property.first_line_indent, text_property.hanging_indent);
+
+        //在 (行方向, 行叠加方向) 上计算，最后再转换为水平和竖直方向
+        let writing_mode = text_property.resolved_writing_mode();
+        let (inline_limit, block_limit) = writing_mode.to_logical(text_property.width, text_property.height);
+        let trimmed = text_property.trimming != TextTrimming::None;
+        let mut lines = break_lines(text, inline_limit, text_property.wrap, indents, advance);
+        if let Some(max_lines) = text_property.max_lines {
+            if lines.len() > max_lines as usize {
+                lines.truncate(max_lines as usize);
+                if let Some(last) = lines.last_mut() {
+                    last.is_trimmed = trimmed;
+                }
+            }
+        }
+
+        let width_of = |range: std::ops::Range<usize>| {
+            let indent = line_indent(text, range.start, indents);
+            let start = range.start;
+            text[range].char_indices().fold(indent, |x, (index, c)| x + advance(start + index, c, x))
+        };
+        let content_width = lines.iter().map(|line| width_of(line.range.start..line.range.end - line.trailing_whitespace_length)).fold(0.0, f32::max);
+        let full_width = lines.iter().map(|line| width_of(line.range.clone())).fold(0.0, f32::max);
+        //使用字体自身的行高时，内联框会撑开所在的行
+        let line_box_of = |line: &LineMetrics| {
+            let (baseline, height) = match text_property.line_height {
+                LineHeight::Default => {
+                    let (ascent, descent) = text_property.inline_boxes.iter()
+                        .filter(|inline_box| line.range.contains(&inline_box.range.start))
+                        .fold((natural_baseline, natural_height - natural_baseline), |(ascent, descent), inline_box| inline_box.inline_box.metrics.extend_line(ascent, descent));
+                    (ascent, ascent + descent)
+                }
+                _ => (baseline, height),
+            };
+            //段落间距加在每段最后一行的下方
+            match text[line.range.clone()].ends_with('\n') {
+                true => (baseline, height + paragraph_spacing),
+                false => (baseline, height),
+            }
+        };
+        let content_height = lines.iter().map(|line| line_box_of(line).1).sum::<f32>();
+
+        let layout_inline = match inline_limit {
+            Some(size) if trimmed => size,
+            Some(size) => size.max(content_width),
+            None => content_width,
+        };
+        let layout_block = match block_limit {
+            Some(size) if trimmed => size,
+            Some(size) => size.max(content_height),
+            None => content_height,
+        };
+        //竖排时阅读方向总是从上到下
+        let right_to_left = !writing_mode.is_vertical() && text_property.reading_direction.resolve(text) == ReadingDirection::RightToLeft;
+        let inline_offset = match (&text_property.align, right_to_left) {
+            (TextAlign::Center, _) => (layout_inline - content_width) / 2.0,
+            (TextAlign::Left, _) | (TextAlign::Start, false) | (TextAlign::End, true) => 0.0,
+            (TextAlign::Right, _) | (TextAlign::Start, true) | (TextAlign::End, false) => layout_inline - content_width,
+        };
+        let block_offset = match text_property.vertical_align {
+            TextVerticalAlign::Top => 0.0,
+            TextVerticalAlign::Center => (layout_block - content_height) / 2.0,
+            TextVerticalAlign::Bottom => layout_block - content_height,
+        };
+        //从右到左排列的列从布局框的右边开始
+        let block_offset = match writing_mode {
+            WritingMode::VerticalRl => layout_block - content_height - block_offset,
+            _ => block_offset,
+        };
+        let (left, top) = writing_mode.to_physical(inline_offset, block_offset);
+        let (width, height) = writing_mode.to_physical(content_width, content_height);
+        let (layout_width, layout_height) = writing_mode.to_physical(layout_inline, layout_block);
+
+        TextMeasurement {
+            metrics: TextMetrics {
+                left,
+                top,
+                width,
+                width_including_trailing_whitespace: full_width,
+                height,
+                layout_width,
+                layout_height,
+                line_count: lines.len(),
+                writing_mode,
+            },
+            line_metrics: lines.into_iter().map(|line| {
+                let (baseline, height) = line_box_of(&line);
+                LineMetrics { baseline, height, ..line }
+            }).collect(),
+        }
+    }
+}
+
+/// 从 start 开始的行的缩进，indents 为 (首行缩进, 悬挂缩进)
+fn line_indent(text: &str, start: usize, (first_line_indent, hanging_indent): (f32, f32)) -> f32 {
+    match start == 0 || text[..start].ends_with('\n') {
+        true => first_line_indent,
+        false => hanging_indent,
+    }
+}
+
+/// 按换行方式把文本分成多行，换行符和行尾空白计入所在行
+/// advance 的参数为 (字节偏移, 字符, 字符在行内的起始位置)
+fn break_lines(text: &str, max_width: Option<f32>, wrap: TextWrap, indents: (f32, f32), advance: impl Fn(usize, char, f32) -> f32) -> Vec<LineMetrics> {
+    let mut lines = vec![];
+    let mut push_line = |start: usize, end: usize| {
+        let content = text[start..end].trim_end_matches(char::is_whitespace);
+        lines.push(LineMetrics {
+            range: start..end,
+            trailing_whitespace_length: end - start - content.len(),
+            ..LineMetrics::default()
+        });
+    };
+
+    let mut paragraph_start = 0;
+    loop {
+        let newline = text[paragraph_start..].find('\n').map(|index| paragraph_start + index);
+        let paragraph_end = newline.unwrap_or(text.len());
+
+        let mut line_start = paragraph_start;
+        let mut width = indents.0;
+        //上一个可以换行的位置，即空白之后
+        let mut break_at = None;
+        for (index, c) in text[paragraph_start..paragraph_end].char_indices() {
+            let index = paragraph_start + index;
+            let c_advance = advance(index, c, width);
+            //行尾的空白不会导致换行
+            if c.is_whitespace() {
+                width += c_advance;
+                break_at = Some(index + c.len_utf8());
+                continue;
+            }
+            let overflow = max_width.is_some_and(|max_width| width + c_advance > max_width) && index > line_start;
+            if overflow {
+                match (wrap, break_at.filter(|break_at| *break_at > line_start)) {
+                    (TextWrap::None, _) => {}
+                    (TextWrap::Character, _) | (TextWrap::EmergencyBreak, None) => {
+                        push_line(line_start, index);
+                        line_start = index;
+                        width = indents.1;
+                    }
+                    (TextWrap::Word | TextWrap::EmergencyBreak, Some(break_index)) => {
+                        push_line(line_start, break_index);
+                        line_start = break_index;
+                        width = text[break_index..index].char_indices().fold(indents.1, |x, (offset, c)| x + advance(break_index + offset, c, x));
+                    }
+                    (TextWrap::Word, None) => {}
+                }
+                break_at = None;
+            }
+            width += c_advance;
+        }
+
+        match newline {
+            Some(newline) => {
+                push_line(line_start, newline + 1);
+                paragraph_start = newline + 1;
+            }
+            None => {
+                push_line(line_start, paragraph_end);
+                break;
+            }
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FlowDirection, TextPropertyError, InlineBox, InlineBoxMetrics, InlineBoxRange, OBJECT_REPLACEMENT_CHARACTER};
+
+    /// 每个字符宽 5，行高 10，基线在 8 处
+    fn measurer() -> FallbackMeasurer {
+        FallbackMeasurer::fixed(0.5).with_default_font_size(10.0)
+    }
+
+    fn ranges(measurement: &TextMeasurement) -> Vec<std::ops::Range<usize>> {
+        measurement.line_metrics.iter().map(|line| line.range.clone()).collect()
+    }
+
+    #[test]
+    fn fixed_advance_wrapping() {
+        let text_property = TextProperty::builder().text("aaaa bbbb cccc").width(50.0).build().unwrap();
+        let measurement = measurer().measure(&text_property);
+        //行尾的空白计入上一行，不会导致换行
+        assert_eq!(ranges(&measurement), vec![0..10, 10..14]);
+        assert_eq!(measurement.line_metrics[0].trailing_whitespace_length, 1);
+        assert_eq!(measurement.line_metrics[1].trailing_whitespace_length, 0);
+        assert_eq!(measurement.metrics.width, 45.0);
+        assert_eq!(measurement.metrics.width_including_trailing_whitespace, 50.0);
+        assert_eq!(measurement.metrics.height, 20.0);
+        assert_eq!(measurement.metrics.line_count, 2);
+        assert_eq!((measurement.line_metrics[0].baseline, measurement.line_metrics[0].height), (8.0, 10.0));
+    }
+
+    #[test]
+    fn without_width_no_wrapping() {
+        let text_property = TextProperty::builder().text("aaaa bbbb\ncc").build().unwrap();
+        let measurement = measurer().measure(&text_property);
+        assert_eq!(ranges(&measurement), vec![0..10, 10..12]);
+        assert_eq!(measurement.metrics.width, 45.0);
+        assert_eq!(measurement.metrics.layout_width, 45.0);
+    }
+
+    #[test]
+    fn emergency_break_splits_long_word() {
+        let text_property = TextProperty::builder().text("aaaaaaaaaaaa").width(25.0).wrap(TextWrap::EmergencyBreak).build().unwrap();
+        assert_eq!(ranges(&measurer().measure(&text_property)), vec![0..5, 5..10, 10..12]);
+        //能在单词之间换行时优先在单词之间换行
+        let text_property = TextProperty::builder().text("aa aaaaaaa").width(25.0).wrap(TextWrap::EmergencyBreak).build().unwrap();
+        assert_eq!(ranges(&measurer().measure(&text_property)), vec![0..3, 3..8, 8..10]);
+    }
+
+    #[test]
+    fn word_wrap_keeps_long_word() {
+        let text_property = TextProperty::builder().text("aaaaaaaaaaaa bb").width(25.0).wrap(TextWrap::Word).build().unwrap();
+        let measurement = measurer().measure(&text_property);
+        assert_eq!(ranges(&measurement), vec![0..13, 13..15]);
+        //单词溢出时布局宽度随内容增大
+        assert_eq!(measurement.metrics.width, 60.0);
+        assert_eq!(measurement.metrics.layout_width, 60.0);
+    }
+
+    #[test]
+    fn no_wrap_keeps_single_line() {
+        let text_property = TextProperty::builder().text("aa aa aa aa").width(25.0).wrap(TextWrap::None).build().unwrap();
+        let measurement = measurer().measure(&text_property);
+        assert_eq!(ranges(&measurement), vec![0..11]);
+        assert_eq!(measurement.metrics.width, 55.0);
+        //换行符仍然会换行
+        let text_property = TextProperty::builder().text("aa aa\naa").width(10.0).wrap(TextWrap::None).build().unwrap();
+        assert_eq!(ranges(&measurer().measure(&text_property)), vec![0..6, 6..8]);
+    }
+
+    #[test]
+    fn character_wrap_ignores_word_boundaries() {
+        let text_property = TextProperty::builder().text("aa aaaaaaa").width(25.0).wrap(TextWrap::Character).build().unwrap();
+        assert_eq!(ranges(&measurer().measure(&text_property)), vec![0..5, 5..10]);
+    }
+
+    #[test]
+    fn first_line_and_hanging_indent() {
+        let text_property = TextProperty::builder()
+            .text("aaaaaaaaaa\naaaa")
+            .width(30.0)
+            .first_line_indent(10.0)
+            .hanging_indent(5.0)
+            .build().unwrap();
+        let measurement = measurer().measure(&text_property);
+        //首行缩进 10 只能放 4 个字符，之后的行缩进 5 能放 5 个字符，新的段落重新使用首行缩进
+        assert_eq!(ranges(&measurement), vec![0..4, 4..9, 9..11, 11..15]);
+        assert_eq!(measurement.metrics.width, 30.0);
+    }
+
+    #[test]
+    fn break_lines_with_indents() {
+        let lines = break_lines("aaaaaaaa", Some(20.0), TextWrap::Character, (0.0, 10.0), |_, _, _| 5.0);
+        let ranges = lines.iter().map(|line| line.range.clone()).collect::<Vec<_>>();
+        assert_eq!(ranges, vec![0..4, 4..6, 6..8]);
+    }
+
+    #[test]
+    fn tab_advances_to_next_tab_stop() {
+        //默认制表位间隔为字号的 4 倍，即 40
+        let text_property = TextProperty::builder().text("\tb").build().unwrap();
+        assert_eq!(measurer().measure(&text_property).metrics.width, 45.0);
+        //制表符前有文字时只前进到下一个制表位
+        let text_property = TextProperty::builder().text("aaa\tb").tab_stop(20.0).build().unwrap();
+        assert_eq!(measurer().measure(&text_property).metrics.width, 25.0);
+        //正好在制表位上时前进到下一个制表位
+        let text_property = TextProperty::builder().text("aaaa\tb\tc").tab_stop(20.0).build().unwrap();
+        assert_eq!(measurer().measure(&text_property).metrics.width, 65.0);
+        //缩进计入制表符前的位置
+        let text_property = TextProperty::builder().text("a\tb").tab_stop(20.0).first_line_indent(18.0).build().unwrap();
+        assert_eq!(measurer().measure(&text_property).metrics.width, 45.0);
+    }
+
+    #[test]
+    fn max_lines_trims_last_line() {
+        let text_property = TextProperty::builder()
+            .text("aaaaaaaaaaaaaaa")
+            .width(25.0)
+            .max_lines(2)
+            .trimming(TextTrimming::CharacterEllipsis)
+            .build().unwrap();
+        let measurement = measurer().measure(&text_property);
+        assert_eq!(ranges(&measurement), vec![0..5, 5..10]);
+        assert!(!measurement.line_metrics[0].is_trimmed);
+        assert!(measurement.line_metrics[1].is_trimmed);
+        assert_eq!(measurement.metrics.height, 20.0);
+    }
+
+    #[test]
+    fn paragraph_spacing_between_paragraphs() {
+        let text_property = TextProperty::builder()
+            .text("aa\nbb\ncc")
+            .paragraph_spacing(5.0)
+            .build().unwrap();
+        let measurement = measurer().measure(&text_property);
+        let heights = measurement.line_metrics.iter().map(|line| line.height).collect::<Vec<f32>>();
+        //间距加在前两段的最后一行下方，最后一段之后没有间距，基线不变
+        assert_eq!(heights, vec![15.0, 15.0, 10.0]);
+        assert!(measurement.line_metrics.iter().all(|line| line.baseline == 8.0));
+        assert_eq!(measurement.metrics.height, 40.0);
+    }
+
+    #[test]
+    fn paragraph_spacing_rejected_with_fixed_line_height() {
+        let text_property = TextProperty::builder()
+            .text("aa\nbb")
+            .line_height(LineHeight::Absolute(12.0))
+            .paragraph_spacing(5.0)
+            .build();
+        assert_eq!(text_property, Err(TextPropertyError::ParagraphSpacingWithFixedLineHeight));
+        let text_property = TextProperty::builder()
+            .text("aa\nbb")
+            .line_height(LineHeight::Proportional(1.5))
+            .paragraph_spacing(5.0)
+            .build();
+        assert_eq!(text_property, Err(TextPropertyError::ParagraphSpacingWithFixedLineHeight));
+        //直接修改字段时不会报错，测量时忽略间距
+        let mut text_property = TextProperty::builder().text("aa\nbb").line_height(LineHeight::Absolute(12.0)).build().unwrap();
+        text_property.paragraph_spacing = 5.0;
+        assert!(text_property.validate().is_err());
+        assert_eq!(measurer().measure(&text_property).metrics.height, 24.0);
+    }
+
+    #[test]
+    fn vertical_text_wraps_columns_by_height() {
+        let text_property = TextProperty::builder().text("aaaaaaaaaaaa").height(25.0).width(50.0).wrap(TextWrap::Character).writing_mode(WritingMode::VerticalRl).build().unwrap();
+        let measurement = measurer().measure(&text_property);
+        //每列高 25 能放 5 个字符，width 不影响换行
+        assert_eq!(ranges(&measurement), vec![0..5, 5..10, 10..12]);
+        let metrics = measurement.metrics;
+        assert_eq!((metrics.width, metrics.height), (30.0, 25.0));
+        assert_eq!((metrics.inline_size(), metrics.block_size()), (25.0, 30.0));
+        assert_eq!((metrics.layout_width, metrics.layout_height), (50.0, 25.0));
+        //从右到左排列的列靠在布局框右边
+        assert_eq!((metrics.left, metrics.top), (20.0, 0.0));
+
+        let text_property = TextProperty { writing_mode: WritingMode::VerticalLr, ..text_property };
+        let measurement = measurer().measure(&text_property);
+        assert_eq!(ranges(&measurement), vec![0..5, 5..10, 10..12]);
+        assert_eq!((measurement.metrics.left, measurement.metrics.top), (0.0, 0.0));
+    }
+
+    #[test]
+    fn vertical_text_without_height_is_single_column() {
+        let text_property = TextProperty::builder().text("aaaa aaaa").width(10.0).writing_mode(WritingMode::VerticalRl).build().unwrap();
+        let measurement = measurer().measure(&text_property);
+        assert_eq!(ranges(&measurement), vec![0..9]);
+        assert_eq!((measurement.metrics.width, measurement.metrics.height), (10.0, 45.0));
+    }
+
+    #[test]
+    fn vertical_reading_direction_with_horizontal_flow() {
+        //不设置 writing_mode 时由两个方向组成竖排，与 VerticalLr 相同
+        let text_property = TextProperty::builder()
+            .text("aaaaaaaaaa")
+            .height(25.0)
+            .reading_direction(ReadingDirection::TopToBottom)
+            .flow_direction(FlowDirection::LeftToRight)
+            .build().unwrap();
+        let measurement = measurer().measure(&text_property);
+        assert_eq!(measurement.metrics.writing_mode, WritingMode::VerticalLr);
+        assert_eq!(ranges(&measurement), vec![0..5, 5..10]);
+    }
+
+    #[test]
+    fn conflicting_directions_are_rejected() {
+        let text_property = TextProperty::builder().text("aaaa").reading_direction(ReadingDirection::TopToBottom).build();
+        assert_eq!(text_property, Err(TextPropertyError::ConflictingDirections(ReadingDirection::TopToBottom, FlowDirection::TopToBottom)));
+        let text_property = TextProperty::builder().text("aaaa").flow_direction(FlowDirection::RightToLeft).build();
+        assert_eq!(text_property, Err(TextPropertyError::ConflictingDirections(ReadingDirection::Auto, FlowDirection::RightToLeft)));
+        //竖排的 writing_mode 覆盖两个方向，不会冲突
+        let text_property = TextProperty::builder().text("aaaa").reading_direction(ReadingDirection::TopToBottom).writing_mode(WritingMode::VerticalRl).build();
+        assert!(text_property.is_ok());
+        //直接修改字段时不会报错，测量时使用与阅读方向匹配的排列方向
+        let mut text_property = TextProperty::builder().text("aaaaaaaaaa").height(25.0).build().unwrap();
+        text_property.reading_direction = ReadingDirection::TopToBottom;
+        assert!(text_property.validate().is_err());
+        let measurement = measurer().measure(&text_property);
+        assert_eq!(measurement.metrics.writing_mode, WritingMode::VerticalRl);
+        assert_eq!(ranges(&measurement), vec![0..5, 5..10]);
+    }
+
+    fn inline_box_text(metrics: InlineBoxMetrics) -> TextProperty {
+        let text = format!("a{OBJECT_REPLACEMENT_CHARACTER}b\ncc");
+        let inline_box = InlineBoxRange::new(1..1 + OBJECT_REPLACEMENT_CHARACTER.len_utf8(), InlineBox::placeholder(metrics));
+        TextProperty::builder().text(text).inline_boxes(vec![inline_box]).build().unwrap()
+    }
+
+    #[test]
+    fn inline_box_on_baseline_extends_line() {
+        let measurement = measurer().measure(&inline_box_text(InlineBoxMetrics::bottom_on_baseline(12.0, 20.0)));
+        //框底部在基线上，所在行的基线下移，下方的高度不变，其他行不受影响
+        assert_eq!(measurement.line_metrics[0].baseline, 20.0);
+        assert_eq!(measurement.line_metrics[0].height, 22.0);
+        assert_eq!(measurement.line_metrics[1].baseline, 8.0);
+        assert_eq!(measurement.line_metrics[1].height, 10.0);
+        assert_eq!(measurement.metrics.width, 22.0);
+        assert_eq!(measurement.metrics.height, 32.0);
+    }
+
+    #[test]
+    fn centered_inline_box_extends_both_sides() {
+        let measurement = measurer().measure(&inline_box_text(InlineBoxMetrics::centered(12.0, 20.0, 8.0, 2.0)));
+        assert_eq!(measurement.line_metrics[0].baseline, 13.0);
+        assert_eq!(measurement.line_metrics[0].height, 20.0);
+    }
+
+    #[test]
+    fn small_inline_box_keeps_line_height() {
+        let measurement = measurer().measure(&inline_box_text(InlineBoxMetrics::bottom_on_baseline(4.0, 4.0)));
+        assert_eq!(measurement.line_metrics[0].baseline, 8.0);
+        assert_eq!(measurement.line_metrics[0].height, 10.0);
+        assert_eq!(measurement.metrics.width, 14.0);
+    }
+
+    #[test]
+    fn inline_box_ignored_with_fixed_line_height() {
+        let mut text_property = inline_box_text(InlineBoxMetrics::bottom_on_baseline(12.0, 20.0));
+        text_property.line_height = LineHeight::Absolute(12.0);
+        let measurement = measurer().measure(&text_property);
+        assert!(measurement.line_metrics.iter().all(|line| line.height == 12.0));
+    }
+}
@@ -0,0 +1,126 @@
+use std::ops::Range;
+use windows::Win32::Graphics::DirectWrite::{DWRITE_LINE_METRICS, DWRITE_TEXT_METRICS};
+use crate::{utf16_to_byte, WritingMode};
+
+/// 整段文本布局后的尺寸，left、top、width、height 都是水平和竖直方向的值
+/// 与书写方向无关的尺寸使用 inline_size 和 block_size
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TextMetrics {
+    /// 文本相对布局框左上角的偏移，居中或右对齐时不为 0
+    pub left: f32,
+    pub top: f32,
+    /// 不包含行尾空白的宽度
+    pub width: f32,
+    pub width_including_trailing_whitespace: f32,
+    pub height: f32,
+    pub layout_width: f32,
+    pub layout_height: f32,
+    pub line_count: usize,
+    pub writing_mode: WritingMode,
+}
+
+impl TextMetrics {
+    pub(crate) fn from_raw(metrics: &DWRITE_TEXT_METRICS, writing_mode: WritingMode) -> Self {
+        Self {
+            left: metrics.left,
+            top: metrics.top,
+            width: metrics.width,
+            width_including_trailing_whitespace: metrics.widthIncludingTrailingWhitespace,
+            height: metrics.height,
+            layout_width: metrics.layoutWidth,
+            layout_height: metrics.layoutHeight,
+            line_count: metrics.lineCount as usize,
+            writing_mode,
+        }
+    }
+
+    /// 沿行方向的尺寸，竖排时为高度
+    pub fn inline_size(&self) -> f32 {
+        self.writing_mode.to_logical(self.width, self.height).0
+    }
+
+    /// 所有行（竖排时为所有列）叠加方向的尺寸，竖排时为宽度
+    pub fn block_size(&self) -> f32 {
+        self.writing_mode.to_logical(self.width, self.height).1
+    }
+
+    /// 布局框沿行方向的尺寸
+    pub fn layout_inline_size(&self) -> f32 {
+        self.writing_mode.to_logical(self.layout_width, self.layout_height).0
+    }
+
+    /// 布局框沿行叠加方向的尺寸
+    pub fn layout_block_size(&self) -> f32 {
+        self.writing_mode.to_logical(self.layout_width, self.layout_height).1
+    }
+}
+
+/// 单行的尺寸，竖排时描述的是一列
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct LineMetrics {
+    /// 该行在源文本中的字节范围，包含行尾的空白和换行符
+    pub range: Range<usize>,
+    /// 行尾空白（包括换行符）的字节长度
+    pub trailing_whitespace_length: usize,
+    /// 行顶部到基线的距离，竖排时为列的起始边到基线的距离
+    pub baseline: f32,
+    /// 行叠加方向的尺寸，竖排时为列宽
+    pub height: f32,
+    /// 该行是否被省略
+    pub is_trimmed: bool,
+}
+
+impl LineMetrics {
+    /// 将 DirectWrite 以 UTF-16 计算的行信息转换为以字节计算的行信息
+    pub(crate) fn from_raw(text: &str, raw_line_metrics: &[DWRITE_LINE_METRICS]) -> Vec<Self> {
+        let mut utf16_start = 0;
+        raw_line_metrics.iter().map(|metrics| {
+            let utf16_end = utf16_start + metrics.length as usize;
+            let utf16_content_end = utf16_end - metrics.trailingWhitespaceLength as usize;
+            let start = utf16_to_byte(text, utf16_start);
+            let end = utf16_to_byte(text, utf16_end);
+            let content_end = utf16_to_byte(text, utf16_content_end);
+            utf16_start = utf16_end;
+            Self {
+                range: start..end,
+                trailing_whitespace_length: end - content_end,
+                baseline: metrics.baseline,
+                height: metrics.height,
+                is_trimmed: metrics.isTrimmed.as_bool(),
+            }
+        }).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn metrics(writing_mode: WritingMode) -> TextMetrics {
+        TextMetrics {
+            width: 30.0,
+            height: 50.0,
+            layout_width: 40.0,
+            layout_height: 60.0,
+            writing_mode,
+            ..TextMetrics::default()
+        }
+    }
+
+    #[test]
+    fn horizontal_sizes() {
+        let metrics = metrics(WritingMode::HorizontalTb);
+        assert_eq!((metrics.inline_size(), metrics.block_size()), (30.0, 50.0));
+        assert_eq!((metrics.layout_inline_size(), metrics.layout_block_size()), (40.0, 60.0));
+    }
+
+    #[test]
+    fn vertical_sizes_swap_axes() {
+        for writing_mode in [WritingMode::VerticalRl, WritingMode::VerticalLr] {
+            let metrics = metrics(writing_mode);
+            //列沿竖直方向，列的排列沿水平方向
+            assert_eq!((metrics.inline_size(), metrics.block_size()), (50.0, 30.0));
+            assert_eq!((metrics.layout_inline_size(), metrics.layout_block_size()), (60.0, 40.0));
+        }
+    }
+}
@@ -0,0 +1,304 @@
+use std::fmt::{Display, Formatter};
+use crate::{GradientParseError, Hsl, SolidColor};
+use super::named_color::lookup_named_color;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColorParseError {
+    Empty,
+    InvalidHex(String),
+    InvalidSyntax(String),
+    UnknownFunction(String),
+    ArgumentCount {
+        function: String,
+        count: usize,
+    },
+    InvalidComponent(String),
+    UnknownName(String),
+    Gradient(Box<GradientParseError>),
+}
+
+impl Display for ColorParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ColorParseError::Empty => write!(f, "color string is empty"),
+            ColorParseError::InvalidHex(hex) => write!(f, "invalid hex color `{}`", hex),
+            ColorParseError::InvalidSyntax(input) => write!(f, "invalid color syntax `{}`", input),
+            ColorParseError::UnknownFunction(name) => write!(f, "unknown color function `{}`", name),
+            ColorParseError::ArgumentCount { function, count } => {
+                write!(f, "color function `{}` does not accept {} arguments", function, count)
+            }
+            ColorParseError::InvalidComponent(component) => write!(f, "invalid color component `{}`", component),
+            ColorParseError::UnknownName(name) => write!(f, "unknown color name `{}`", name),
+            ColorParseError::Gradient(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+impl std::error::Error for ColorParseError {}
+
+/// 解析 CSS 颜色字符串
+/// 支持 #rgb、#rgba、#rrggbb、#rrggbbaa、rgb()/rgba()、hsl()/hsla() 以及 CSS 命名颜色
+pub(crate) fn parse_solid_color(input: &str) -> Result<SolidColor, ColorParseError> {
+    let input = input.trim();
+    if input.is_empty() {
+        return Err(ColorParseError::Empty);
+    }
+    let lower = input.to_ascii_lowercase();
+
+    if let Some(hex) = lower.strip_prefix('#') {
+        return parse_hex(hex).ok_or_else(|| ColorParseError::InvalidHex(input.to_string()));
+    }
+
+    if let Some(open) = lower.find('(') {
+        let name = lower[..open].trim();
+        let args = lower[open + 1..]
+            .strip_suffix(')')
+            .ok_or_else(|| ColorParseError::InvalidSyntax(input.to_string()))?;
+        return match name {
+            "rgb" | "rgba" => parse_rgb_function(name, args),
+            "hsl" | "hsla" => parse_hsl_function(name, args),
+            _ => Err(ColorParseError::UnknownFunction(name.to_string())),
+        };
+    }
+
+    if lower == "transparent" {
+        return Ok(SolidColor::from_rgba(0.0, 0.0, 0.0, 0.0));
+    }
+
+    lookup_named_color(&lower)
+        .map(|rgb| SolidColor::from(rgb << 8 | 0xFF))
+        .ok_or_else(|| ColorParseError::UnknownName(input.to_string()))
+}
+
+fn parse_hex(hex: &str) -> Option<SolidColor> {
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let digit = |index: usize| u8::from_str_radix(&hex[index..index + 1], 16).ok().map(|d| d * 17);
+    let byte = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16).ok();
+    let (r, g, b, a) = match hex.len() {
+        3 => (digit(0)?, digit(1)?, digit(2)?, 0xFF),
+        4 => (digit(0)?, digit(1)?, digit(2)?, digit(3)?),
+        6 => (byte(0)?, byte(2)?, byte(4)?, 0xFF),
+        8 => (byte(0)?, byte(2)?, byte(4)?, byte(6)?),
+        _ => return None,
+    };
+    Some(SolidColor::from_rgba8(r, g, b, a))
+}
+
+/// 拆分函数参数，支持 `rgb(1, 2, 3, 0.5)` 和 `rgb(1 2 3 / 50%)` 两种写法，两种写法不能混用
+fn split_arguments<'a>(function: &str, args: &'a str) -> Result<(Vec<&'a str>, Option<&'a str>), ColorParseError> {
+    let (main, alpha) = match args.split_once('/') {
+        Some((main, alpha)) => (main, Some(alpha.trim())),
+        None => (args, None),
+    };
+
+    //逗号分隔的旧写法中透明度是第四个参数，不能使用 /
+    let legacy = main.contains(',');
+    if legacy && alpha.is_some() {
+        return Err(ColorParseError::InvalidSyntax(args.to_string()));
+    }
+    let mut components: Vec<&str> = match legacy {
+        true => main.split(',').map(str::trim).collect(),
+        false => main.split_whitespace().collect(),
+    };
+
+    if components.iter().any(|component| component.is_empty()) || alpha == Some("") {
+        return Err(ColorParseError::InvalidSyntax(args.to_string()));
+    }
+
+    let alpha = match (components.len(), alpha) {
+        (3, alpha) => alpha,
+        (4, None) if legacy => components.pop(),
+        (count, _) => {
+            return Err(ColorParseError::ArgumentCount {
+                function: function.to_string(),
+                count: count + alpha.map_or(0, |_| 1),
+            });
+        }
+    };
+
+    Ok((components, alpha))
+}
+
+fn parse_rgb_function(function: &str, args: &str) -> Result<SolidColor, ColorParseError> {
+    let (components, alpha) = split_arguments(function, args)?;
+    let r = parse_channel(components[0])?;
+    let g = parse_channel(components[1])?;
+    let b = parse_channel(components[2])?;
+    let a = alpha.map(parse_alpha).transpose()?.unwrap_or(1.0);
+    Ok(SolidColor::from_rgba(r, g, b, a))
+}
+
+fn parse_hsl_function(function: &str, args: &str) -> Result<SolidColor, ColorParseError> {
+    let (components, alpha) = split_arguments(function, args)?;
+    let h = parse_hue(components[0])?;
+    let s = parse_percentage(components[1])?;
+    let l = parse_percentage(components[2])?;
+    let a = alpha.map(parse_alpha).transpose()?.unwrap_or(1.0);
+    Ok(SolidColor::from_hsl(Hsl { h, s, l }, a))
+}
+
+fn parse_number(value: &str) -> Result<f32, ColorParseError> {
+    value
+        .parse::<f32>()
+        .ok()
+        .filter(|number| number.is_finite())
+        .ok_or_else(|| ColorParseError::InvalidComponent(value.to_string()))
+}
+
+/// rgb 通道，支持 0~255 或百分比
+fn parse_channel(value: &str) -> Result<f32, ColorParseError> {
+    let channel = match value.strip_suffix('%') {
+        Some(percent) => parse_number(percent)? / 100.0,
+        None => parse_number(value)? / 255.0,
+    };
+    Ok(channel.clamp(0.0, 1.0))
+}
+
+/// 透明度，支持 0~1 或百分比
+fn parse_alpha(value: &str) -> Result<f32, ColorParseError> {
+    let alpha = match value.strip_suffix('%') {
+        Some(percent) => parse_number(percent)? / 100.0,
+        None => parse_number(value)?,
+    };
+    Ok(alpha.clamp(0.0, 1.0))
+}
+
+/// 饱和度和亮度，必须是百分比
+fn parse_percentage(value: &str) -> Result<f32, ColorParseError> {
+    let percent = value.strip_suffix('%').ok_or_else(|| ColorParseError::InvalidComponent(value.to_string()))?;
+    let percent = parse_number(percent)?;
+    Ok((percent / 100.0).clamp(0.0, 1.0))
+}
+
+/// 色相，返回角度，支持 deg、rad、grad、turn 单位，无单位时视为角度
+pub(crate) fn parse_hue(value: &str) -> Result<f32, ColorParseError> {
+    let degrees = if let Some(number) = value.strip_suffix("deg") {
+        parse_number(number)?
+    } else if let Some(number) = value.strip_suffix("grad") {
+        parse_number(number)? * 0.9
+    } else if let Some(number) = value.strip_suffix("rad") {
+        parse_number(number)?.to_degrees()
+    } else if let Some(number) = value.strip_suffix("turn") {
+        parse_number(number)? * 360.0
+    } else {
+        parse_number(value)?
+    };
+    Ok(degrees.rem_euclid(360.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Color;
+
+    fn parse(input: &str) -> SolidColor {
+        parse_solid_color(input).unwrap_or_else(|error| panic!("{input}: {error}"))
+    }
+
+    fn assert_rgba(input: &str, (r, g, b, a): (f32, f32, f32, f32)) {
+        let color = parse(input);
+        let close = |x: f32, y: f32| (x - y).abs() < 1e-3;
+        assert!(close(color.r, r) && close(color.g, g) && close(color.b, b) && close(color.a, a), "{input}: {color:?}");
+    }
+
+    #[test]
+    fn hex_lengths() {
+        assert_eq!(parse("#f80"), SolidColor::from_rgba8(0xFF, 0x88, 0x00, 0xFF));
+        assert_eq!(parse("#f808"), SolidColor::from_rgba8(0xFF, 0x88, 0x00, 0x88));
+        assert_eq!(parse("#12ab9C"), SolidColor::from_rgba8(0x12, 0xAB, 0x9C, 0xFF));
+        assert_eq!(parse("#12ab9c80"), SolidColor::from_rgba8(0x12, 0xAB, 0x9C, 0x80));
+        for input in ["#", "#12", "#12345", "#1234567", "#123456789", "#ggg", "#+12"] {
+            assert_eq!(parse_solid_color(input), Err(ColorParseError::InvalidHex(input.to_string())));
+        }
+    }
+
+    #[test]
+    fn rgb_functions() {
+        assert_rgba("rgb(255, 128, 0)", (1.0, 128.0 / 255.0, 0.0, 1.0));
+        assert_rgba("rgba(255, 128, 0, 0.5)", (1.0, 128.0 / 255.0, 0.0, 0.5));
+        assert_rgba("rgb(255 128 0)", (1.0, 128.0 / 255.0, 0.0, 1.0));
+        assert_rgba("rgb(255 128 0 / 25%)", (1.0, 128.0 / 255.0, 0.0, 0.25));
+        assert_rgba("rgba(255 128 0 / 0.5)", (1.0, 128.0 / 255.0, 0.0, 0.5));
+        //rgba 也可以不带透明度，rgb 也可以带透明度
+        assert_rgba("rgba(0, 0, 255)", (0.0, 0.0, 1.0, 1.0));
+        assert_rgba("rgb(0, 0, 255, 0.5)", (0.0, 0.0, 1.0, 0.5));
+    }
+
+    #[test]
+    fn rgb_percentages_and_clamping() {
+        assert_rgba("rgb(100%, 50%, 0%)", (1.0, 0.5, 0.0, 1.0));
+        assert_rgba("rgb(300 -20 128 / 150%)", (1.0, 0.0, 128.0 / 255.0, 1.0));
+        assert_rgba("rgb(0 0 0 / -1)", (0.0, 0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn hsl_functions() {
+        assert_rgba("hsl(0, 100%, 50%)", (1.0, 0.0, 0.0, 1.0));
+        assert_rgba("hsla(120, 100%, 50%, 0.5)", (0.0, 1.0, 0.0, 0.5));
+        assert_rgba("hsl(240 100% 50%)", (0.0, 0.0, 1.0, 1.0));
+        assert_rgba("hsla(240 100% 50% / 20%)", (0.0, 0.0, 1.0, 0.2));
+        assert_rgba("hsl(0 0% 100%)", (1.0, 1.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn hue_units() {
+        for input in ["hsl(120deg 100% 50%)", "hsl(0.3333turn 100% 50%)", "hsl(133.333grad 100% 50%)", "hsl(2.0944rad 100% 50%)", "hsl(480 100% 50%)", "hsl(-240 100% 50%)"] {
+            assert_rgba(input, (0.0, 1.0, 0.0, 1.0));
+        }
+        assert_eq!(parse_hue("90deg"), Ok(90.0));
+        assert_eq!(parse_hue("-0.25turn"), Ok(270.0));
+        assert!(parse_hue("90px").is_err());
+    }
+
+    #[test]
+    fn names() {
+        assert_rgba("transparent", (0.0, 0.0, 0.0, 0.0));
+        assert_rgba("TRANSPARENT", (0.0, 0.0, 0.0, 0.0));
+        assert_eq!(parse("red"), SolidColor::from_rgba8(0xFF, 0x00, 0x00, 0xFF));
+        assert_eq!(parse("  RebeccaPurple "), SolidColor::from_rgba8(0x66, 0x33, 0x99, 0xFF));
+        assert_eq!(parse("YellowGreen"), SolidColor::from_rgba8(0x9A, 0xCD, 0x32, 0xFF));
+        assert_eq!(lookup_named_color("aliceblue"), Some(0xF0F8FF));
+        assert_eq!(lookup_named_color("AliceBlue"), None);
+        assert_eq!(lookup_named_color("notacolor"), None);
+    }
+
+    #[test]
+    fn mixed_syntax_is_rejected() {
+        assert_eq!(parse_solid_color("rgb(1, 2, 3 / 50%)"), Err(ColorParseError::InvalidSyntax("1, 2, 3 / 50%".to_string())));
+        assert_eq!(parse_solid_color("hsl(1, 2%, 3% / 0.5)"), Err(ColorParseError::InvalidSyntax("1, 2%, 3% / 0.5".to_string())));
+        //空格分隔时透明度必须写在 / 之后
+        assert_eq!(
+            parse_solid_color("rgb(1 2 3 0.5)"),
+            Err(ColorParseError::ArgumentCount { function: "rgb".to_string(), count: 4 })
+        );
+    }
+
+    #[test]
+    fn hsl_requires_percentages() {
+        assert_eq!(parse_solid_color("hsl(120, 50, 50%)"), Err(ColorParseError::InvalidComponent("50".to_string())));
+        assert_eq!(parse_solid_color("hsl(120 50% 50)"), Err(ColorParseError::InvalidComponent("50".to_string())));
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(parse_solid_color("   "), Err(ColorParseError::Empty));
+        assert_eq!(parse_solid_color("rgb(1, 2, 3"), Err(ColorParseError::InvalidSyntax("rgb(1, 2, 3".to_string())));
+        assert_eq!(parse_solid_color("rgb(1, , 3)"), Err(ColorParseError::InvalidSyntax("1, , 3".to_string())));
+        assert_eq!(parse_solid_color("rgb(1 2 3 /)"), Err(ColorParseError::InvalidSyntax("1 2 3 /".to_string())));
+        assert_eq!(parse_solid_color("lab(50 0 0)"), Err(ColorParseError::UnknownFunction("lab".to_string())));
+        assert_eq!(
+            parse_solid_color("rgb(1, 2)"),
+            Err(ColorParseError::ArgumentCount { function: "rgb".to_string(), count: 2 })
+        );
+        assert_eq!(
+            parse_solid_color("hsla(1 2% 3% 4 / 5)"),
+            Err(ColorParseError::ArgumentCount { function: "hsla".to_string(), count: 5 })
+        );
+        assert_eq!(parse_solid_color("rgb(red, 0, 0)"), Err(ColorParseError::InvalidComponent("red".to_string())));
+        assert_eq!(parse_solid_color("rgb(inf, 0, 0)"), Err(ColorParseError::InvalidComponent("inf".to_string())));
+        assert_eq!(parse_solid_color("Reddish"), Err(ColorParseError::UnknownName("Reddish".to_string())));
+        assert!(matches!(Color::parse("linear-gradient(red)"), Err(ColorParseError::Gradient(_))));
+    }
+}
@@ -0,0 +1,498 @@
+use crate::SolidColor;
+
+/// 色相为角度 0~360，饱和度和亮度为 0~1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsl {
+    pub h: f32,
+    pub s: f32,
+    pub l: f32,
+}
+
+/// 色相为角度 0~360，饱和度和明度为 0~1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsv {
+    pub h: f32,
+    pub s: f32,
+    pub v: f32,
+}
+
+/// 线性 sRGB，各分量为 0~1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinearRgb {
+    pub r: f32,
+    pub g: f32,
+    pub b: f32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Oklab {
+    pub l: f32,
+    pub a: f32,
+    pub b: f32,
+}
+
+/// 色相为角度 0~360
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Oklch {
+    pub l: f32,
+    pub c: f32,
+    pub h: f32,
+}
+
+/// 颜色混合时使用的色彩空间
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ColorSpace {
+    #[default]
+    Srgb,
+    LinearSrgb,
+    Hsl,
+    Hsv,
+    Oklab,
+    Oklch,
+}
+
+pub(crate) fn srgb_to_linear(value: f32) -> f32 {
+    if value <= 0.04045 {
+        value / 12.92
+    } else {
+        ((value + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+pub(crate) fn linear_to_srgb(value: f32) -> f32 {
+    if value <= 0.0031308 {
+        value * 12.92
+    } else {
+        1.055 * value.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// 计算 sRGB 的色相（角度），无彩色时返回 0
+fn hue(r: f32, g: f32, b: f32, max: f32, delta: f32) -> f32 {
+    if delta == 0.0 {
+        return 0.0;
+    }
+    let hue = if max == r {
+        (g - b) / delta % 6.0
+    } else if max == g {
+        (b - r) / delta + 2.0
+    } else {
+        (r - g) / delta + 4.0
+    };
+    (hue * 60.0).rem_euclid(360.0)
+}
+
+/// 按最短弧线插值色相
+fn lerp_hue(from: f32, to: f32, t: f32) -> f32 {
+    let mut delta = (to - from).rem_euclid(360.0);
+    if delta > 180.0 {
+        delta -= 360.0;
+    }
+    (from + delta * t).rem_euclid(360.0)
+}
+
+/// 插值两端可能缺失的色相，与 CSS 相同，无彩色一端的色相视为缺失，使用另一端的色相
+/// 两端都缺失时色相为 0
+fn mix_hue(from: f32, from_missing: bool, to: f32, to_missing: bool, t: f32) -> f32 {
+    match (from_missing, to_missing) {
+        (true, true) => 0.0,
+        (true, false) => to,
+        (false, true) => from,
+        (false, false) => lerp_hue(from, to, t),
+    }
+}
+
+/// 色度低于该值时色相视为缺失，与 to_oklch 相同
+const ACHROMATIC_CHROMA: f32 = 1e-4;
+
+fn lerp(from: f32, to: f32, t: f32) -> f32 {
+    from + (to - from) * t
+}
+
+impl SolidColor {
+    pub fn with_alpha(&self, alpha: f32) -> Self {
+        Self::from_rgba(self.r, self.g, self.b, alpha)
+    }
+
+    pub fn from_hsl(hsl: Hsl, alpha: f32) -> Self {
+        let Hsl { h, s, l } = hsl;
+        let h = h.rem_euclid(360.0);
+        let f = |n: f32| {
+            let k = (n + h / 30.0) % 12.0;
+            let a = s * l.min(1.0 - l);
+            l - a * (k - 3.0).min(9.0 - k).clamp(-1.0, 1.0)
+        };
+        Self::from_rgba(f(0.0), f(8.0), f(4.0), alpha)
+    }
+
+    pub fn to_hsl(&self) -> Hsl {
+        let max = self.r.max(self.g).max(self.b);
+        let min = self.r.min(self.g).min(self.b);
+        let delta = max - min;
+        let l = (max + min) / 2.0;
+        let s = if delta == 0.0 {
+            0.0
+        } else {
+            delta / (1.0 - (2.0 * l - 1.0).abs())
+        };
+        Hsl {
+            h: hue(self.r, self.g, self.b, max, delta),
+            s: s.clamp(0.0, 1.0),
+            l,
+        }
+    }
+
+    pub fn from_hsv(hsv: Hsv, alpha: f32) -> Self {
+        let Hsv { h, s, v } = hsv;
+        let h = h.rem_euclid(360.0);
+        let f = |n: f32| {
+            let k = (n + h / 60.0) % 6.0;
+            v - v * s * k.min(4.0 - k).clamp(0.0, 1.0)
+        };
+        Self::from_rgba(f(5.0), f(3.0), f(1.0), alpha)
+    }
+
+    pub fn to_hsv(&self) -> Hsv {
+        let max = self.r.max(self.g).max(self.b);
+        let min = self.r.min(self.g).min(self.b);
+        let delta = max - min;
+        Hsv {
+            h: hue(self.r, self.g, self.b, max, delta),
+            s: if max == 0.0 { 0.0 } else { delta / max },
+            v: max,
+        }
+    }
+
+    pub fn from_linear_rgb(rgb: LinearRgb, alpha: f32) -> Self {
+        Self::from_rgba(
+            linear_to_srgb(rgb.r),
+            linear_to_srgb(rgb.g),
+            linear_to_srgb(rgb.b),
+            alpha,
+        )
+    }
+
+    pub fn to_linear_rgb(&self) -> LinearRgb {
+        LinearRgb {
+            r: srgb_to_linear(self.r),
+            g: srgb_to_linear(self.g),
+            b: srgb_to_linear(self.b),
+        }
+    }
+
+    pub fn from_oklab(lab: Oklab, alpha: f32) -> Self {
+        let l = lab.l + 0.396_337_78 * lab.a + 0.215_803_76 * lab.b;
+        let m = lab.l - 0.105_561_346 * lab.a - 0.063_854_17 * lab.b;
+        let s = lab.l - 0.089_484_18 * lab.a - 1.291_485_5 * lab.b;
+        let (l, m, s) = (l * l * l, m * m * m, s * s * s);
+        Self::from_linear_rgb(
+            LinearRgb {
+                r: 4.076_741_7 * l - 3.307_711_6 * m + 0.230_969_94 * s,
+                g: -1.268_438 * l + 2.609_757_4 * m - 0.341_319_38 * s,
+                b: -0.004_196_086_3 * l - 0.703_418_6 * m + 1.707_614_7 * s,
+            },
+            alpha,
+        )
+    }
+
+    pub fn to_oklab(&self) -> Oklab {
+        let LinearRgb { r, g, b } = self.to_linear_rgb();
+        let l = (0.412_221_46 * r + 0.536_332_55 * g + 0.051_445_995 * b).cbrt();
+        let m = (0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b).cbrt();
+        let s = (0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b).cbrt();
+        Oklab {
+            l: 0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+            a: 1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+            b: 0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+        }
+    }
+
+    pub fn from_oklch(lch: Oklch, alpha: f32) -> Self {
+        let h = lch.h.to_radians();
+        Self::from_oklab(
+            Oklab {
+                l: lch.l,
+                a: lch.c * h.cos(),
+                b: lch.c * h.sin(),
+            },
+            alpha,
+        )
+    }
+
+    pub fn to_oklch(&self) -> Oklch {
+        let Oklab { l, a, b } = self.to_oklab();
+        let c = (a * a + b * b).sqrt();
+        // 色度极小时色相没有意义
+        let h = if c < ACHROMATIC_CHROMA { 0.0 } else { b.atan2(a).to_degrees().rem_euclid(360.0) };
+        Oklch { l, c, h }
+    }
+
+    /// 在 HSL 空间中增加亮度，amount 为 0~1
+    pub fn lighten(&self, amount: f32) -> Self {
+        let hsl = self.to_hsl();
+        Self::from_hsl(Hsl { l: (hsl.l + amount).clamp(0.0, 1.0), ..hsl }, self.a)
+    }
+
+    /// 在 HSL 空间中降低亮度，amount 为 0~1
+    pub fn darken(&self, amount: f32) -> Self {
+        self.lighten(-amount)
+    }
+
+    /// 在 HSL 空间中增加饱和度，amount 为 0~1
+    pub fn saturate(&self, amount: f32) -> Self {
+        let hsl = self.to_hsl();
+        Self::from_hsl(Hsl { s: (hsl.s + amount).clamp(0.0, 1.0), ..hsl }, self.a)
+    }
+
+    /// 在 HSL 空间中降低饱和度，amount 为 0~1
+    pub fn desaturate(&self, amount: f32) -> Self {
+        self.saturate(-amount)
+    }
+
+    /// 在指定色彩空间中混合两个颜色，t 为 0 时返回 self，为 1 时返回 other
+    /// 色相按最短弧线插值，饱和度或色度为 0 的一端没有色相，使用另一端的色相
+    pub fn mix(&self, other: &SolidColor, t: f32, space: ColorSpace) -> Self {
+        let alpha = lerp(self.a, other.a, t);
+        match space {
+            ColorSpace::Srgb => Self::from_rgba(
+                lerp(self.r, other.r, t),
+                lerp(self.g, other.g, t),
+                lerp(self.b, other.b, t),
+                alpha,
+            ),
+            ColorSpace::LinearSrgb => {
+                let (from, to) = (self.to_linear_rgb(), other.to_linear_rgb());
+                Self::from_linear_rgb(
+                    LinearRgb {
+                        r: lerp(from.r, to.r, t),
+                        g: lerp(from.g, to.g, t),
+                        b: lerp(from.b, to.b, t),
+                    },
+                    alpha,
+                )
+            }
+            ColorSpace::Hsl => {
+                let (from, to) = (self.to_hsl(), other.to_hsl());
+                Self::from_hsl(
+                    Hsl {
+                        h: mix_hue(from.h, from.s == 0.0, to.h, to.s == 0.0, t),
+                        s: lerp(from.s, to.s, t),
+                        l: lerp(from.l, to.l, t),
+                    },
+                    alpha,
+                )
+            }
+            ColorSpace::Hsv => {
+                let (from, to) = (self.to_hsv(), other.to_hsv());
+                Self::from_hsv(
+                    Hsv {
+                        h: mix_hue(from.h, from.s == 0.0, to.h, to.s == 0.0, t),
+                        s: lerp(from.s, to.s, t),
+                        v: lerp(from.v, to.v, t),
+                    },
+                    alpha,
+                )
+            }
+            ColorSpace::Oklab => {
+                let (from, to) = (self.to_oklab(), other.to_oklab());
+                Self::from_oklab(
+                    Oklab {
+                        l: lerp(from.l, to.l, t),
+                        a: lerp(from.a, to.a, t),
+                        b: lerp(from.b, to.b, t),
+                    },
+                    alpha,
+                )
+            }
+            ColorSpace::Oklch => {
+                let (from, to) = (self.to_oklch(), other.to_oklch());
+                Self::from_oklch(
+                    Oklch {
+                        l: lerp(from.l, to.l, t),
+                        c: lerp(from.c, to.c, t),
+                        h: mix_hue(from.h, from.c < ACHROMATIC_CHROMA, to.h, to.c < ACHROMATIC_CHROMA, t),
+                    },
+                    alpha,
+                )
+            }
+        }
+        .clamped()
+    }
+
+    /// 将各分量限制在 0~1 之间
+    pub fn clamped(&self) -> Self {
+        Self::from_rgba(
+            self.r.clamp(0.0, 1.0),
+            self.g.clamp(0.0, 1.0),
+            self.b.clamp(0.0, 1.0),
+            self.a.clamp(0.0, 1.0),
+        )
+    }
+
+    pub fn premultiply(&self) -> Self {
+        Self::from_rgba(self.r * self.a, self.g * self.a, self.b * self.a, self.a)
+    }
+
+    pub fn unpremultiply(&self) -> Self {
+        if self.a == 0.0 {
+            return Self::from_rgba(0.0, 0.0, 0.0, 0.0);
+        }
+        Self::from_rgba(self.r / self.a, self.g / self.a, self.b / self.a, self.a)
+    }
+
+    /// WCAG 2.x 相对亮度，忽略透明度
+    pub fn relative_luminance(&self) -> f32 {
+        let LinearRgb { r, g, b } = self.to_linear_rgb();
+        0.2126 * r + 0.7152 * g + 0.0722 * b
+    }
+
+    /// WCAG 2.x 对比度，范围 1~21，与参数顺序无关
+    pub fn contrast_ratio(&self, other: &SolidColor) -> f32 {
+        let a = self.relative_luminance();
+        let b = other.relative_luminance();
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: &SolidColor, expected: &SolidColor) {
+        let close = |a: f32, b: f32| (a - b).abs() < 1e-3;
+        assert!(
+            close(actual.r, expected.r) && close(actual.g, expected.g) && close(actual.b, expected.b) && close(actual.a, expected.a),
+            "{actual:?} != {expected:?}"
+        );
+    }
+
+    #[test]
+    fn mix_achromatic_uses_other_hue() {
+        let white = SolidColor::from_rgba(1.0, 1.0, 1.0, 1.0);
+        let blue = SolidColor::from_rgba(0.0, 0.0, 1.0, 1.0);
+        for space in [ColorSpace::Hsl, ColorSpace::Hsv, ColorSpace::Oklch] {
+            let mixed = white.mix(&blue, 0.5, space);
+            //不经过红色，蓝色分量始终最大
+            assert!(mixed.b >= mixed.r && mixed.b >= mixed.g, "{space:?}: {mixed:?}");
+            assert!(mixed.r <= mixed.g + 0.05, "{space:?}: {mixed:?}");
+        }
+    }
+
+    #[test]
+    fn mix_hsl_white_blue_midpoint() {
+        let white = SolidColor::from_rgba(1.0, 1.0, 1.0, 1.0);
+        let blue = SolidColor::from_rgba(0.0, 0.0, 1.0, 1.0);
+        let expected = SolidColor::from_hsl(Hsl { h: 240.0, s: 0.5, l: 0.75 }, 1.0);
+        assert_close(&white.mix(&blue, 0.5, ColorSpace::Hsl), &expected);
+        assert_close(&blue.mix(&white, 0.5, ColorSpace::Hsl), &expected);
+    }
+
+    #[test]
+    fn mix_both_achromatic() {
+        let black = SolidColor::from_rgba(0.0, 0.0, 0.0, 1.0);
+        let white = SolidColor::from_rgba(1.0, 1.0, 1.0, 1.0);
+        let gray = SolidColor::from_rgba(0.5, 0.5, 0.5, 1.0);
+        assert_close(&black.mix(&white, 0.5, ColorSpace::Hsl), &gray);
+    }
+
+    #[test]
+    fn mix_chromatic_shortest_arc() {
+        let red = SolidColor::from_rgba(1.0, 0.0, 0.0, 1.0);
+        let blue = SolidColor::from_rgba(0.0, 0.0, 1.0, 1.0);
+        //红色 0° 和蓝色 240° 之间的最短弧线经过 300° 的品红
+        let magenta = SolidColor::from_rgba(1.0, 0.0, 1.0, 1.0);
+        assert_close(&red.mix(&blue, 0.5, ColorSpace::Hsl), &magenta);
+    }
+
+    fn close(a: f32, b: f32) -> bool {
+        (a - b).abs() < 1e-3
+    }
+
+    const SAMPLES: [(f32, f32, f32); 6] = [(1.0, 0.0, 0.0), (0.2, 0.4, 0.6), (0.9, 0.8, 0.1), (0.0, 0.0, 0.0), (1.0, 1.0, 1.0), (0.3, 0.3, 0.3)];
+
+    #[test]
+    fn hsl_known_values() {
+        let color = SolidColor::from_hsl(Hsl { h: 210.0, s: 0.5, l: 0.4 }, 1.0);
+        assert_close(&color, &SolidColor::from_rgba(0.2, 0.4, 0.6, 1.0));
+        let Hsl { h, s, l } = SolidColor::from_rgba(0.2, 0.4, 0.6, 1.0).to_hsl();
+        assert!(close(h, 210.0) && close(s, 0.5) && close(l, 0.4), "{h} {s} {l}");
+        //色相超出 0~360 时取模
+        assert_close(&SolidColor::from_hsl(Hsl { h: -240.0, s: 1.0, l: 0.5 }, 1.0), &SolidColor::from_rgba(0.0, 1.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn hsv_known_values() {
+        let Hsv { h, s, v } = SolidColor::from_rgba(0.2, 0.4, 0.6, 1.0).to_hsv();
+        assert!(close(h, 210.0) && close(s, 2.0 / 3.0) && close(v, 0.6), "{h} {s} {v}");
+        assert_close(&SolidColor::from_hsv(Hsv { h: 60.0, s: 1.0, v: 1.0 }, 0.5), &SolidColor::from_rgba(1.0, 1.0, 0.0, 0.5));
+        assert_eq!(SolidColor::from_rgba(0.0, 0.0, 0.0, 1.0).to_hsv().s, 0.0);
+    }
+
+    #[test]
+    fn linear_known_values() {
+        assert!(close(srgb_to_linear(0.5), 0.214_04));
+        assert!(close(linear_to_srgb(0.5), 0.735_36));
+        //分段函数在分界点附近连续
+        assert!(close(srgb_to_linear(0.040_45), 0.003_130_8));
+        assert!(close(linear_to_srgb(0.003_130_8), 0.040_45));
+    }
+
+    #[test]
+    fn oklab_known_values() {
+        let Oklab { l, a, b } = SolidColor::from_rgba(1.0, 1.0, 1.0, 1.0).to_oklab();
+        assert!(close(l, 1.0) && close(a, 0.0) && close(b, 0.0), "{l} {a} {b}");
+        let Oklab { l, a, b } = SolidColor::from_rgba(1.0, 0.0, 0.0, 1.0).to_oklab();
+        assert!(close(l, 0.627_96) && close(a, 0.224_86) && close(b, 0.125_85), "{l} {a} {b}");
+        let Oklab { l, a, b } = SolidColor::from_rgba(0.0, 0.0, 1.0, 1.0).to_oklab();
+        assert!(close(l, 0.452_01) && close(a, -0.032_46) && close(b, -0.311_53), "{l} {a} {b}");
+        let Oklch { l, c, h } = SolidColor::from_rgba(1.0, 0.0, 0.0, 1.0).to_oklch();
+        assert!(close(l, 0.627_96) && close(c, 0.257_68) && (h - 29.234).abs() < 0.05, "{l} {c} {h}");
+        //灰色没有色相
+        assert_eq!(SolidColor::from_rgba(0.5, 0.5, 0.5, 1.0).to_oklch().h, 0.0);
+    }
+
+    #[test]
+    fn round_trips() {
+        for (r, g, b) in SAMPLES {
+            let color = SolidColor::from_rgba(r, g, b, 0.7);
+            assert_close(&SolidColor::from_hsl(color.to_hsl(), 0.7), &color);
+            assert_close(&SolidColor::from_hsv(color.to_hsv(), 0.7), &color);
+            assert_close(&SolidColor::from_linear_rgb(color.to_linear_rgb(), 0.7), &color);
+            assert_close(&SolidColor::from_oklab(color.to_oklab(), 0.7), &color);
+            assert_close(&SolidColor::from_oklch(color.to_oklch(), 0.7), &color);
+        }
+    }
+
+    #[test]
+    fn premultiply() {
+        let color = SolidColor::from_rgba(0.5, 1.0, 0.2, 0.5);
+        let premultiplied = color.premultiply();
+        assert_close(&premultiplied, &SolidColor::from_rgba(0.25, 0.5, 0.1, 0.5));
+        assert_close(&premultiplied.unpremultiply(), &color);
+        assert_eq!(SolidColor::from_rgba(0.5, 0.5, 0.5, 0.0).unpremultiply(), SolidColor::from_rgba(0.0, 0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn relative_luminance() {
+        assert!(close(SolidColor::from_rgba(1.0, 1.0, 1.0, 1.0).relative_luminance(), 1.0));
+        assert!(close(SolidColor::from_rgba(0.0, 0.0, 0.0, 1.0).relative_luminance(), 0.0));
+        assert!(close(SolidColor::from_rgba(1.0, 0.0, 0.0, 1.0).relative_luminance(), 0.2126));
+        assert!(close(SolidColor::from_rgba(0.0, 1.0, 0.0, 1.0).relative_luminance(), 0.7152));
+        //透明度不影响亮度
+        assert!(close(SolidColor::from_rgba(0.5, 0.5, 0.5, 0.1).relative_luminance(), 0.214_04));
+    }
+
+    #[test]
+    fn contrast_ratio() {
+        let black = SolidColor::from_rgba(0.0, 0.0, 0.0, 1.0);
+        let white = SolidColor::from_rgba(1.0, 1.0, 1.0, 1.0);
+        assert!(close(black.contrast_ratio(&white), 21.0));
+        assert!(close(white.contrast_ratio(&black), 21.0));
+        assert!(close(white.contrast_ratio(&white), 1.0));
+        //#767676 是白色背景上满足 WCAG AA 4.5:1 的最浅灰色
+        let gray = SolidColor::from_rgba8(0x76, 0x76, 0x76, 0xFF);
+        assert!((gray.contrast_ratio(&white) - 4.54).abs() < 0.01);
+        let lighter = SolidColor::from_rgba8(0x77, 0x77, 0x77, 0xFF);
+        assert!(lighter.contrast_ratio(&white) < 4.5);
+    }
+}
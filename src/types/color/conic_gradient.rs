@@ -0,0 +1,139 @@
+use crate::{GradientColor, Point, SolidColor};
+
+/// 锥形（扫描）渐变，与 CSS `conic-gradient()` 一致
+/// center 相对于图形外接矩形，(0.5, 0.5) 为中心
+/// start_angle 为起始角度，0 度指向上方，顺时针旋转
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ConicGradient {
+    pub center: Point,
+    pub start_angle: f32,
+    /// 为 true 时在整圈内重复第一个到最后一个色标之间的部分
+    pub repeating: bool,
+}
+
+impl ConicGradient {
+    pub fn new(start_angle: f32) -> Self {
+        Self {
+            center: Point::new(0.5, 0.5),
+            start_angle,
+            repeating: false,
+        }
+    }
+
+    pub fn at(mut self, center: impl Into<Point>) -> Self {
+        self.center = center.into();
+        self
+    }
+
+    pub fn with_repeating(mut self, repeating: bool) -> Self {
+        self.repeating = repeating;
+        self
+    }
+
+    /// 计算外接矩形内 (x, y) 处在渐变上的位置 0~1，x、y 相对于外接矩形左上角
+    pub fn position_at(&self, width: f32, height: f32, x: f32, y: f32) -> f32 {
+        let dx = x - self.center.x * width;
+        let dy = y - self.center.y * height;
+        //0度指向上方，y轴向下
+        let angle = dx.atan2(-dy).to_degrees();
+        ((angle - self.start_angle).rem_euclid(360.0) / 360.0).min(1.0)
+    }
+
+    /// 计算外接矩形内 (x, y) 处的颜色，x、y 相对于外接矩形左上角
+    pub fn sample(&self, stops: &GradientColor, width: f32, height: f32, x: f32, y: f32) -> SolidColor {
+        let t = self.position_at(width, height, x, y);
+        let t = match (self.repeating, stops.stops.first(), stops.stops.last()) {
+            (true, Some(first), Some(last)) if last.position > first.position => {
+                let span = last.position - first.position;
+                first.position + (t - first.position).rem_euclid(span)
+            }
+            _ => t,
+        };
+        stops.sample(t)
+    }
+}
+
+impl Default for ConicGradient {
+    fn default() -> Self {
+        Self::new(0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GradientStop;
+
+    fn red() -> SolidColor {
+        SolidColor::from_rgba(1.0, 0.0, 0.0, 1.0)
+    }
+
+    fn blue() -> SolidColor {
+        SolidColor::from_rgba(0.0, 0.0, 1.0, 1.0)
+    }
+
+    fn red_to_blue() -> GradientColor {
+        GradientColor::new(vec![GradientStop::new(red(), 0.0), GradientStop::new(blue(), 1.0)])
+    }
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 1e-4, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn position_starts_at_top_clockwise() {
+        let conic = ConicGradient::default();
+        assert_close(conic.position_at(100.0, 100.0, 50.0, 0.0), 0.0);
+        assert_close(conic.position_at(100.0, 100.0, 100.0, 50.0), 0.25);
+        assert_close(conic.position_at(100.0, 100.0, 50.0, 100.0), 0.5);
+        assert_close(conic.position_at(100.0, 100.0, 0.0, 50.0), 0.75);
+    }
+
+    #[test]
+    fn sample_from_angle() {
+        let conic = ConicGradient::new(90.0);
+        //起始角度指向右方，右方为第一个颜色，上方已经转过 270 度
+        assert_eq!(conic.sample(&red_to_blue(), 100.0, 100.0, 100.0, 50.0), red());
+        assert_close(conic.position_at(100.0, 100.0, 50.0, 0.0), 0.75);
+        assert_close(conic.position_at(100.0, 100.0, 50.0, 100.0), 0.25);
+    }
+
+    #[test]
+    fn sample_center_offset() {
+        let conic = ConicGradient::default().at((0.0, 0.0));
+        //中心在左上角，矩形内的点都在 90 度到 180 度之间
+        assert_close(conic.position_at(100.0, 100.0, 100.0, 0.0), 0.25);
+        assert_close(conic.position_at(100.0, 100.0, 100.0, 100.0), 0.375);
+        assert_close(conic.position_at(100.0, 100.0, 0.0, 100.0), 0.5);
+        let conic = ConicGradient::default().at((0.25, 0.5));
+        assert_close(conic.position_at(200.0, 100.0, 50.0, 0.0), 0.0);
+        assert_close(conic.position_at(200.0, 100.0, 100.0, 50.0), 0.25);
+    }
+
+    #[test]
+    fn sample_wraps_at_full_turn() {
+        let conic = ConicGradient::default();
+        //刚转过一整圈之前接近最后一个颜色，回到起点时为第一个颜色
+        let before = conic.position_at(100.0, 100.0, 49.999, 0.0);
+        assert!(before > 0.999 && before <= 1.0, "{before}");
+        assert_eq!(conic.sample(&red_to_blue(), 100.0, 100.0, 50.0, 0.0), red());
+        //起始角度超出 360 度时与取余后相同
+        let wrapped = ConicGradient::new(450.0);
+        for (x, y) in [(100.0, 50.0), (50.0, 100.0), (10.0, 20.0)] {
+            assert_close(wrapped.position_at(100.0, 100.0, x, y), ConicGradient::new(90.0).position_at(100.0, 100.0, x, y));
+        }
+        let negative = ConicGradient::new(-90.0);
+        assert_close(negative.position_at(100.0, 100.0, 0.0, 50.0), 0.0);
+    }
+
+    #[test]
+    fn sample_repeating() {
+        let stops = GradientColor::new(vec![GradientStop::new(red(), 0.0), GradientStop::new(blue(), 0.25)]);
+        let conic = ConicGradient::default().with_repeating(true);
+        //每 90 度重复一次
+        assert_eq!(conic.sample(&stops, 100.0, 100.0, 100.0, 50.0), red());
+        assert_eq!(conic.sample(&stops, 100.0, 100.0, 50.0, 100.0), red());
+        let quarter = conic.sample(&stops, 100.0, 100.0, 100.0, 0.0);
+        assert_eq!(quarter, stops.sample(0.125));
+    }
+}
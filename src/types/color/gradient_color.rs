@@ -1,28 +1,69 @@
+use std::fmt::{Display, Formatter};
 use std::ops::Deref;
-use crate::SolidColor;
+use std::str::FromStr;
+use windows::Win32::Graphics::Direct2D::{D2D1_EXTEND_MODE, D2D1_EXTEND_MODE_CLAMP, D2D1_EXTEND_MODE_MIRROR, D2D1_EXTEND_MODE_WRAP, D2D1_GAMMA, D2D1_GAMMA_1_0, D2D1_GAMMA_2_2};
+use crate::{ColorSpace, GradientParseError, SolidColor};
+use super::gradient_parse::parse_gradient_stops;
 
 pub type Direct2DGradientStop = windows::Win32::Graphics::Direct2D::D2D1_GRADIENT_STOP;
 
+/// OKLab 插值时每两个色标之间插入的分段数
+const OKLAB_SEGMENTS: usize = 8;
+
+/// 由 (颜色, 位置) 列表创建渐变色，不做校验，需要校验时使用 try_gradient_color! 或 GradientColor::validate
 #[macro_export]
 macro_rules! gradient_color {
-    ($(($color:expr, $position: expr),)*) => {
+    ($(($color:expr, $position: expr)),* $(,)?) => {
+        $crate::GradientColor::new(vec![
+            $(
+                $crate::GradientStop::new(
+                    $color,
+                    $position,
+                ),
+            )*
+        ])
+    }
+}
+
+/// 与 gradient_color! 相同，但会校验色标，返回 Result<GradientColor, GradientError>
+#[macro_export]
+macro_rules! try_gradient_color {
+    ($(($color:expr, $position: expr)),* $(,)?) => {
         {
-            let color = $crate::GradientColor {
-                stops: vec![
-                    $(
-                        $crate::GradientStop::new(
-                            $color,
-                            $position,
-                        ),
-                    )*
-                ],
-            };
-            color.check();
-            color
+            let color = $crate::gradient_color!($(($color, $position)),*);
+            color.validate().map(|_| color)
         }
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum GradientError {
+    Empty,
+    PositionOutOfRange {
+        index: usize,
+        position: f32,
+    },
+    Unordered {
+        index: usize,
+    },
+}
+
+impl Display for GradientError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GradientError::Empty => write!(f, "gradient color must have at least one stop"),
+            GradientError::PositionOutOfRange { index, position } => {
+                write!(f, "gradient stop {} has position {}, expected 0.0..=1.0", index, position)
+            }
+            GradientError::Unordered { index } => {
+                write!(f, "gradient stop {} is positioned before the previous stop", index)
+            }
+        }
+    }
+}
+
+impl std::error::Error for GradientError {}
+
 #[repr(transparent)]
 #[derive(Clone, Debug, PartialEq)]
 pub struct GradientStop(Direct2DGradientStop);
@@ -36,6 +77,10 @@ impl GradientStop {
             }
         )
     }
+
+    pub fn color(&self) -> SolidColor {
+        SolidColor(self.0.color)
+    }
 }
 
 impl Deref for GradientStop {
@@ -46,40 +91,212 @@ impl Deref for GradientStop {
     }
 }
 
+/// 渐变在 0~1 范围之外的延展方式
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum GradientSpread {
+    #[default]
+    Pad,
+    Repeat,
+    Reflect,
+}
+
+impl GradientSpread {
+    /// 将任意 t 映射到 0~1 之间
+    pub fn apply(&self, t: f32) -> f32 {
+        match self {
+            GradientSpread::Pad => t.clamp(0.0, 1.0),
+            GradientSpread::Repeat => t.rem_euclid(1.0),
+            GradientSpread::Reflect => {
+                let t = t.rem_euclid(2.0);
+                if t > 1.0 { 2.0 - t } else { t }
+            }
+        }
+    }
+}
+
+impl From<GradientSpread> for D2D1_EXTEND_MODE {
+    fn from(spread: GradientSpread) -> Self {
+        match spread {
+            GradientSpread::Pad => D2D1_EXTEND_MODE_CLAMP,
+            GradientSpread::Repeat => D2D1_EXTEND_MODE_WRAP,
+            GradientSpread::Reflect => D2D1_EXTEND_MODE_MIRROR,
+        }
+    }
+}
+
+/// 色标之间的插值空间
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum GradientInterpolation {
+    /// sRGB gamma 空间插值，与 CSS 默认行为一致
+    #[default]
+    Srgb,
+    LinearSrgb,
+    /// Direct2D 不支持 OKLab，通过插入额外的色标近似
+    Oklab,
+}
+
+impl GradientInterpolation {
+    fn color_space(&self) -> ColorSpace {
+        match self {
+            GradientInterpolation::Srgb => ColorSpace::Srgb,
+            GradientInterpolation::LinearSrgb => ColorSpace::LinearSrgb,
+            GradientInterpolation::Oklab => ColorSpace::Oklab,
+        }
+    }
+}
+
+impl From<GradientInterpolation> for D2D1_GAMMA {
+    fn from(interpolation: GradientInterpolation) -> Self {
+        match interpolation {
+            GradientInterpolation::Srgb | GradientInterpolation::Oklab => D2D1_GAMMA_2_2,
+            GradientInterpolation::LinearSrgb => D2D1_GAMMA_1_0,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct GradientColor {
     pub stops: Vec<GradientStop>,
+    pub spread: GradientSpread,
+    pub interpolation: GradientInterpolation,
 }
 
 impl GradientColor {
 
     pub fn new(stops: Vec<GradientStop>) -> Self {
-        Self { stops }
+        Self {
+            stops,
+            spread: GradientSpread::default(),
+            interpolation: GradientInterpolation::default(),
+        }
+    }
+
+    pub fn with_spread(mut self, spread: GradientSpread) -> Self {
+        self.spread = spread;
+        self
+    }
+
+    pub fn with_interpolation(mut self, interpolation: GradientInterpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    /// 校验渐变色：至少有一个色标，位置在 0~1 之间且不递减
+    /// 相同位置的色标是允许的，用于实现硬边过渡
+    pub fn validate(&self) -> Result<(), GradientError> {
+        if self.stops.is_empty() {
+            return Err(GradientError::Empty);
+        }
+        let mut last_position = 0.0;
+        for (index, stop) in self.stops.iter().enumerate() {
+            if !(0.0..=1.0).contains(&stop.position) {
+                return Err(GradientError::PositionOutOfRange { index, position: stop.position });
+            }
+            if stop.position < last_position {
+                return Err(GradientError::Unordered { index });
+            }
+            last_position = stop.position;
+        }
+        Ok(())
     }
 
+    /// 校验失败时 panic
+    #[deprecated(note = "use `validate`, which returns the error instead of panicking")]
     pub fn check(&self) {
-        self.check_sum();
-        self.check_positions();
+        if let Err(error) = self.validate() {
+            panic!("{}", error);
+        }
     }
 
+    /// 色标位置不需要相加为 1，现在与 check 相同，按 validate 的规则校验
+    #[deprecated(note = "stop positions do not need to sum to 1.0, use `validate`")]
     pub fn check_sum(&self) {
-        let sum = self.stops.iter().fold(0.0, |acc, stop| acc + stop.position);
-        if sum != 1.0 {
-            panic!(
-                "Gradient color stops must add up to 1.0, got {}",
-                sum
-            );
-        }
+        #[allow(deprecated)]
+        self.check();
     }
 
+    /// 现在与 check 相同，按 validate 的规则校验，允许第一个色标在 0 处和位置相同的色标
+    #[deprecated(note = "use `validate`, which also accepts a first stop at 0.0 and equal positions")]
     pub fn check_positions(&self) {
-        let mut last_position = 0.0;
-        for stop in self.stops.iter() {
-            if stop.position <= last_position {
-                panic!("Gradient color stops must be in ascending order");
+        #[allow(deprecated)]
+        self.check();
+    }
+
+    /// 将色标位置均匀分布在 0~1 之间，颜色顺序不变
+    pub fn distribute_evenly(&mut self) {
+        let count = self.stops.len();
+        for (index, stop) in self.stops.iter_mut().enumerate() {
+            stop.0.position = if count > 1 { index as f32 / (count - 1) as f32 } else { 0.0 };
+        }
+    }
+
+    /// 反转渐变方向
+    pub fn reverse(&mut self) {
+        self.stops.reverse();
+        for stop in self.stops.iter_mut() {
+            stop.0.position = 1.0 - stop.0.position;
+        }
+    }
+
+    /// 按位置插入色标，位置相同时插入到已有色标之后，返回插入的下标
+    pub fn insert_stop(&mut self, stop: GradientStop) -> usize {
+        let index = self.stops.partition_point(|s| s.position <= stop.position);
+        self.stops.insert(index, stop);
+        index
+    }
+
+    pub fn remove_stop(&mut self, index: usize) -> Option<GradientStop> {
+        if index < self.stops.len() {
+            Some(self.stops.remove(index))
+        } else {
+            None
+        }
+    }
+
+    /// 计算 t 处的颜色，先按 spread 将 t 映射到 0~1，再在 interpolation 对应的空间中插值
+    /// 第一个色标之前取第一个颜色，最后一个色标之后取最后一个颜色，t 为 NaN 时取第一个颜色
+    pub fn sample(&self, t: f32) -> SolidColor {
+        let (first, last) = match (self.stops.first(), self.stops.last()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return SolidColor::from_rgba(0.0, 0.0, 0.0, 0.0),
+        };
+        let t = self.spread.apply(t);
+        if t.is_nan() || t <= first.position {
+            return first.color();
+        }
+        if t >= last.position {
+            return last.color();
+        }
+        // 第一个位置大于 t 的色标，前一个色标的位置一定不大于 t
+        let index = self.stops.partition_point(|stop| stop.position <= t);
+        let (from, to) = (&self.stops[index - 1], &self.stops[index]);
+        let span = to.position - from.position;
+        let local = if span > 0.0 { (t - from.position) / span } else { 1.0 };
+        from.color().mix(&to.color(), local, self.interpolation.color_space())
+    }
+
+    /// 生成交给 Direct2D 的色标
+    /// Direct2D 只支持 sRGB 和线性插值，OKLab 插值时在每两个色标之间插入额外的色标
+    pub fn expanded_stops(&self) -> Vec<GradientStop> {
+        if self.interpolation != GradientInterpolation::Oklab {
+            return self.stops.clone();
+        }
+        let mut stops = Vec::with_capacity(self.stops.len() * OKLAB_SEGMENTS);
+        for pair in self.stops.windows(2) {
+            let (from, to) = (&pair[0], &pair[1]);
+            stops.push(from.clone());
+            if to.position <= from.position {
+                continue;
+            }
+            let (from_color, to_color) = (from.color(), to.color());
+            for segment in 1..OKLAB_SEGMENTS {
+                let local = segment as f32 / OKLAB_SEGMENTS as f32;
+                let position = from.position + (to.position - from.position) * local;
+                stops.push(GradientStop::new(from_color.mix(&to_color, local, ColorSpace::Oklab), position));
             }
-            last_position = stop.position;
         }
+        stops.extend(self.stops.last().cloned());
+        stops
     }
 
     pub fn as_slice(&self) -> &[Direct2DGradientStop] {
@@ -91,6 +308,9 @@ impl GradientColor {
 
 impl PartialEq for GradientColor {
     fn eq(&self, other: &Self) -> bool {
+        if self.spread != other.spread || self.interpolation != other.interpolation {
+            return false;
+        }
         if self.stops.len() != other.stops.len() {
             return false;
         }
@@ -101,4 +321,251 @@ impl PartialEq for GradientColor {
         }
         true
     }
-}
\ No newline at end of file
+}
+
+/// 解析逗号分隔的 CSS 色标列表，如 "#f00, blue 30%, rgba(0,0,0,.5)"，省略的位置按 CSS 规则补全
+impl FromStr for GradientColor {
+    type Err = GradientParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_gradient_stops(s)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn red() -> SolidColor {
+        SolidColor::from_rgba(1.0, 0.0, 0.0, 1.0)
+    }
+
+    fn blue() -> SolidColor {
+        SolidColor::from_rgba(0.0, 0.0, 1.0, 1.0)
+    }
+
+    fn red_to_blue() -> GradientColor {
+        GradientColor::new(vec![GradientStop::new(red(), 0.0), GradientStop::new(blue(), 1.0)])
+    }
+
+    #[test]
+    fn sample_nan_returns_first_color() {
+        for spread in [GradientSpread::Pad, GradientSpread::Repeat, GradientSpread::Reflect] {
+            assert_eq!(red_to_blue().with_spread(spread).sample(f32::NAN), red());
+        }
+    }
+
+    fn assert_color_close(actual: SolidColor, expected: SolidColor) {
+        let close = |a: f32, b: f32| (a - b).abs() < 1e-4;
+        assert!(
+            close(actual.r, expected.r) && close(actual.g, expected.g) && close(actual.b, expected.b) && close(actual.a, expected.a),
+            "{actual:?} != {expected:?}"
+        );
+    }
+
+    #[test]
+    fn spread_pad() {
+        assert_eq!(GradientSpread::Pad.apply(-0.5), 0.0);
+        assert_eq!(GradientSpread::Pad.apply(0.25), 0.25);
+        assert_eq!(GradientSpread::Pad.apply(1.5), 1.0);
+    }
+
+    #[test]
+    fn spread_repeat() {
+        assert_eq!(GradientSpread::Repeat.apply(0.25), 0.25);
+        assert_eq!(GradientSpread::Repeat.apply(1.25), 0.25);
+        assert_eq!(GradientSpread::Repeat.apply(-0.25), 0.75);
+        assert_eq!(GradientSpread::Repeat.apply(1.0), 0.0);
+    }
+
+    #[test]
+    fn spread_reflect() {
+        assert_eq!(GradientSpread::Reflect.apply(0.25), 0.25);
+        assert_eq!(GradientSpread::Reflect.apply(1.0), 1.0);
+        assert_eq!(GradientSpread::Reflect.apply(1.25), 0.75);
+        assert_eq!(GradientSpread::Reflect.apply(-0.25), 0.25);
+        assert_eq!(GradientSpread::Reflect.apply(2.25), 0.25);
+    }
+
+    #[test]
+    fn expanded_stops_keeps_stops_without_oklab() {
+        let gradient = red_to_blue().with_interpolation(GradientInterpolation::LinearSrgb);
+        assert_eq!(gradient.expanded_stops(), gradient.stops);
+    }
+
+    #[test]
+    fn expanded_stops_oklab() {
+        let green = SolidColor::from_rgba(0.0, 1.0, 0.0, 1.0);
+        let gradient = GradientColor::new(vec![
+            GradientStop::new(red(), 0.0),
+            GradientStop::new(green, 0.4),
+            GradientStop::new(blue(), 1.0),
+        ]).with_interpolation(GradientInterpolation::Oklab);
+        let stops = gradient.expanded_stops();
+        assert_eq!(stops.len(), (gradient.stops.len() - 1) * OKLAB_SEGMENTS + 1);
+        //原有的色标保持不变
+        assert_eq!(stops[0], gradient.stops[0]);
+        assert_eq!(stops[OKLAB_SEGMENTS], gradient.stops[1]);
+        assert_eq!(stops.last(), gradient.stops.last());
+        //位置递增，插入的颜色与 OKLab 采样一致
+        assert!(stops.windows(2).all(|pair| pair[0].position < pair[1].position));
+        for stop in &stops {
+            assert_color_close(stop.color(), gradient.sample(stop.position));
+        }
+    }
+
+    #[test]
+    fn expanded_stops_oklab_hard_edge() {
+        let gradient = GradientColor::new(vec![
+            GradientStop::new(red(), 0.0),
+            GradientStop::new(red(), 0.5),
+            GradientStop::new(blue(), 0.5),
+            GradientStop::new(blue(), 1.0),
+        ]).with_interpolation(GradientInterpolation::Oklab);
+        //位置相同的两个色标之间不插入色标
+        assert_eq!(gradient.expanded_stops().len(), 2 * OKLAB_SEGMENTS + 2);
+    }
+
+    #[test]
+    fn sample_at_stops() {
+        let green = SolidColor::from_rgba(0.0, 1.0, 0.0, 1.0);
+        let gradient = GradientColor::new(vec![
+            GradientStop::new(red(), 0.2),
+            GradientStop::new(green, 0.5),
+            GradientStop::new(blue(), 0.8),
+        ]);
+        assert_eq!(gradient.sample(0.0), red());
+        assert_eq!(gradient.sample(0.2), red());
+        assert_eq!(gradient.sample(0.5), green);
+        assert_eq!(gradient.sample(0.8), blue());
+        assert_eq!(gradient.sample(1.0), blue());
+    }
+
+    #[test]
+    fn sample_between_stops() {
+        let gradient = red_to_blue();
+        assert_color_close(gradient.sample(0.25), SolidColor::from_rgba(0.75, 0.0, 0.25, 1.0));
+        let linear = red_to_blue().with_interpolation(GradientInterpolation::LinearSrgb);
+        assert_color_close(linear.sample(0.5), red().mix(&blue(), 0.5, ColorSpace::LinearSrgb));
+    }
+
+    #[test]
+    fn sample_hard_edge_takes_later_stop() {
+        let gradient = GradientColor::new(vec![
+            GradientStop::new(red(), 0.0),
+            GradientStop::new(red(), 0.5),
+            GradientStop::new(blue(), 0.5),
+            GradientStop::new(blue(), 1.0),
+        ]);
+        assert_eq!(gradient.sample(0.49), red());
+        assert_eq!(gradient.sample(0.5), blue());
+    }
+
+    #[test]
+    fn sample_with_spread() {
+        let gradient = red_to_blue().with_spread(GradientSpread::Reflect);
+        assert_color_close(gradient.sample(1.25), gradient.sample(0.75));
+        let gradient = red_to_blue().with_spread(GradientSpread::Repeat);
+        assert_color_close(gradient.sample(1.25), gradient.sample(0.25));
+    }
+
+    #[test]
+    fn sample_empty_is_transparent() {
+        assert_eq!(GradientColor::new(vec![]).sample(0.5), SolidColor::from_rgba(0.0, 0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn validate_rules() {
+        assert_eq!(red_to_blue().validate(), Ok(()));
+        assert_eq!(GradientColor::new(vec![]).validate(), Err(GradientError::Empty));
+        //只有一个色标、第一个色标在 0 处和位置相同的色标都是允许的
+        assert_eq!(GradientColor::new(vec![GradientStop::new(red(), 0.3)]).validate(), Ok(()));
+        let hard_edge = GradientColor::new(vec![GradientStop::new(red(), 0.5), GradientStop::new(blue(), 0.5)]);
+        assert_eq!(hard_edge.validate(), Ok(()));
+        let out_of_range = GradientColor::new(vec![GradientStop::new(red(), 0.0), GradientStop::new(blue(), 1.5)]);
+        assert_eq!(out_of_range.validate(), Err(GradientError::PositionOutOfRange { index: 1, position: 1.5 }));
+        let negative = GradientColor::new(vec![GradientStop::new(red(), -0.1)]);
+        assert_eq!(negative.validate(), Err(GradientError::PositionOutOfRange { index: 0, position: -0.1 }));
+        let nan = GradientColor::new(vec![GradientStop::new(red(), f32::NAN)]);
+        assert!(matches!(nan.validate(), Err(GradientError::PositionOutOfRange { index: 0, .. })));
+        let unordered = GradientColor::new(vec![GradientStop::new(red(), 0.6), GradientStop::new(blue(), 0.4)]);
+        assert_eq!(unordered.validate(), Err(GradientError::Unordered { index: 1 }));
+    }
+
+    #[test]
+    fn macros() {
+        let gradient = gradient_color!((red(), 0.0), (blue(), 1.0));
+        assert_eq!(gradient, red_to_blue());
+        assert_eq!(gradient_color!((red(), 0.0), (blue(), 1.0),), red_to_blue());
+        //gradient_color! 不校验，try_gradient_color! 返回校验结果
+        let unordered = gradient_color!((red(), 0.6), (blue(), 0.4));
+        assert_eq!(unordered.stops.len(), 2);
+        assert_eq!(try_gradient_color!((red(), 0.0), (blue(), 1.0)), Ok(red_to_blue()));
+        assert_eq!(try_gradient_color!((red(), 0.6), (blue(), 0.4)), Err(GradientError::Unordered { index: 1 }));
+        assert_eq!(try_gradient_color!(), Err(GradientError::Empty));
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn deprecated_checks_use_validate() {
+        //原来的 check_sum 会拒绝这个渐变
+        let gradient = GradientColor::new(vec![GradientStop::new(red(), 0.0), GradientStop::new(red(), 0.5), GradientStop::new(blue(), 1.0)]);
+        gradient.check();
+        gradient.check_sum();
+        gradient.check_positions();
+        let result = std::panic::catch_unwind(|| GradientColor::new(vec![]).check());
+        assert!(result.is_err());
+    }
+
+    fn positions(gradient: &GradientColor) -> Vec<f32> {
+        gradient.stops.iter().map(|stop| stop.position).collect()
+    }
+
+    #[test]
+    fn distribute_evenly() {
+        let mut gradient = GradientColor::new(vec![
+            GradientStop::new(red(), 0.1),
+            GradientStop::new(blue(), 0.2),
+            GradientStop::new(red(), 0.3),
+        ]);
+        gradient.distribute_evenly();
+        assert_eq!(positions(&gradient), vec![0.0, 0.5, 1.0]);
+        assert_eq!(gradient.stops[1].color(), blue());
+        let mut single = GradientColor::new(vec![GradientStop::new(red(), 0.7)]);
+        single.distribute_evenly();
+        assert_eq!(positions(&single), vec![0.0]);
+    }
+
+    #[test]
+    fn reverse() {
+        let mut gradient = GradientColor::new(vec![
+            GradientStop::new(red(), 0.0),
+            GradientStop::new(blue(), 0.25),
+            GradientStop::new(red(), 0.75),
+        ]);
+        gradient.reverse();
+        assert_eq!(positions(&gradient), vec![0.25, 0.75, 1.0]);
+        assert_eq!(gradient.stops[1].color(), blue());
+        assert_eq!(gradient.validate(), Ok(()));
+        gradient.reverse();
+        assert_eq!(positions(&gradient), vec![0.0, 0.25, 0.75]);
+    }
+
+    #[test]
+    fn insert_and_remove_stop() {
+        let green = SolidColor::from_rgba(0.0, 1.0, 0.0, 1.0);
+        let mut gradient = red_to_blue();
+        assert_eq!(gradient.insert_stop(GradientStop::new(green, 0.5)), 1);
+        //位置相同时插入到已有色标之后
+        assert_eq!(gradient.insert_stop(GradientStop::new(red(), 0.5)), 2);
+        assert_eq!(gradient.insert_stop(GradientStop::new(red(), 1.0)), 4);
+        assert_eq!(gradient.insert_stop(GradientStop::new(blue(), 0.0)), 1);
+        assert_eq!(positions(&gradient), vec![0.0, 0.0, 0.5, 0.5, 1.0, 1.0]);
+        assert_eq!(gradient.stops[2].color(), green);
+        assert_eq!(gradient.validate(), Ok(()));
+
+        assert_eq!(gradient.remove_stop(2), Some(GradientStop::new(green, 0.5)));
+        assert_eq!(gradient.remove_stop(5), None);
+        assert_eq!(gradient.stops.len(), 5);
+    }
+}
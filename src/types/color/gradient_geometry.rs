@@ -0,0 +1,182 @@
+use crate::Point;
+
+/// 渐变几何参数所在的坐标系
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum GradientUnits {
+    /// 相对于图形外接矩形，(0, 0) 为左上角，(1, 1) 为右下角
+    #[default]
+    ObjectBoundingBox,
+    /// 绘制坐标系，多个图形可以共用同一段渐变
+    UserSpace,
+}
+
+/// 由起点和终点确定的线性渐变
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LinearGradientGeometry {
+    pub start: Point,
+    pub end: Point,
+    pub units: GradientUnits,
+}
+
+impl LinearGradientGeometry {
+    pub fn object_bounding_box(start: impl Into<Point>, end: impl Into<Point>) -> Self {
+        Self {
+            start: start.into(),
+            end: end.into(),
+            units: GradientUnits::ObjectBoundingBox,
+        }
+    }
+
+    pub fn user_space(start: impl Into<Point>, end: impl Into<Point>) -> Self {
+        Self {
+            start: start.into(),
+            end: end.into(),
+            units: GradientUnits::UserSpace,
+        }
+    }
+}
+
+/// 由中心、原点偏移和半径确定的径向渐变
+/// offset 为渐变原点相对于中心的偏移
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RadialGradientGeometry {
+    pub center: Point,
+    pub offset: Point,
+    pub radius_x: f32,
+    pub radius_y: f32,
+    pub units: GradientUnits,
+}
+
+impl RadialGradientGeometry {
+    pub fn object_bounding_box(center: impl Into<Point>, radius_x: f32, radius_y: f32) -> Self {
+        Self {
+            center: center.into(),
+            offset: Point::default(),
+            radius_x,
+            radius_y,
+            units: GradientUnits::ObjectBoundingBox,
+        }
+    }
+
+    pub fn user_space(center: impl Into<Point>, radius_x: f32, radius_y: f32) -> Self {
+        Self {
+            center: center.into(),
+            offset: Point::default(),
+            radius_x,
+            radius_y,
+            units: GradientUnits::UserSpace,
+        }
+    }
+
+    pub fn with_offset(mut self, offset: impl Into<Point>) -> Self {
+        self.offset = offset.into();
+        self
+    }
+}
+
+/// CSS `linear-gradient(to <corner>)` 指向的角
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GradientCorner {
+    TopLeft,
+    TopRight,
+    BottomRight,
+    BottomLeft,
+}
+
+impl GradientCorner {
+    /// 指向角时渐变线垂直于另外两个角的连线，角度取决于外接矩形的长宽比，正方形时为 45 度的奇数倍
+    pub fn angle(&self, width: f32, height: f32) -> f32 {
+        let angle = height.atan2(width).to_degrees();
+        match self {
+            GradientCorner::TopRight => angle,
+            GradientCorner::BottomRight => 180.0 - angle,
+            GradientCorner::BottomLeft => 180.0 + angle,
+            GradientCorner::TopLeft => 360.0 - angle,
+        }
+    }
+}
+
+/// CSS 线性渐变的方向
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CssLinearDirection {
+    /// 0 度从下到上，顺时针旋转
+    Angle(f32),
+    Corner(GradientCorner),
+}
+
+impl CssLinearDirection {
+    /// 在给定尺寸的外接矩形中的角度
+    pub fn angle(&self, width: f32, height: f32) -> f32 {
+        match self {
+            CssLinearDirection::Angle(angle) => *angle,
+            CssLinearDirection::Corner(corner) => corner.angle(width, height),
+        }
+    }
+}
+
+/// 省略方向时从上到下
+impl Default for CssLinearDirection {
+    fn default() -> Self {
+        CssLinearDirection::Angle(180.0)
+    }
+}
+
+impl From<f32> for CssLinearDirection {
+    fn from(angle: f32) -> Self {
+        CssLinearDirection::Angle(angle)
+    }
+}
+
+impl From<GradientCorner> for CssLinearDirection {
+    fn from(corner: GradientCorner) -> Self {
+        CssLinearDirection::Corner(corner)
+    }
+}
+
+/// CSS 径向渐变的形状
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum RadialShape {
+    Circle,
+    #[default]
+    Ellipse,
+}
+
+/// CSS 径向渐变的尺寸关键字
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum RadialExtent {
+    ClosestSide,
+    FarthestSide,
+    ClosestCorner,
+    #[default]
+    FarthestCorner,
+}
+
+/// 与 CSS `radial-gradient()` 语义一致的径向渐变
+/// position 相对于图形外接矩形，(0.5, 0.5) 为中心
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CssRadialGradient {
+    pub shape: RadialShape,
+    pub extent: RadialExtent,
+    pub position: Point,
+}
+
+impl CssRadialGradient {
+    pub fn new(shape: RadialShape, extent: RadialExtent) -> Self {
+        Self {
+            shape,
+            extent,
+            position: Point::new(0.5, 0.5),
+        }
+    }
+
+    pub fn at(mut self, position: impl Into<Point>) -> Self {
+        self.position = position.into();
+        self
+    }
+}
+
+impl Default for CssRadialGradient {
+    fn default() -> Self {
+        Self::new(RadialShape::default(), RadialExtent::default())
+    }
+}
@@ -0,0 +1,444 @@
+use std::fmt::{Display, Formatter};
+use crate::{Color, ColorParseError, ColorSpace, CssLinearDirection, CssRadialGradient, GradientColor, GradientCorner, GradientError, GradientStop, Point, RadialExtent, RadialShape, SolidColor};
+use super::color_parse::{parse_hue, parse_solid_color};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum GradientParseErrorKind {
+    UnknownFunction(String),
+    MissingParenthesis,
+    EmptyArgument,
+    InvalidDirection(String),
+    InvalidPosition(String),
+    InvalidStop(String),
+    InvalidColor(ColorParseError),
+    TooFewStops,
+    InvalidGradient(GradientError),
+}
+
+/// 渐变字符串解析错误，position 为出错位置在输入字符串中的字节偏移
+#[derive(Debug, Clone, PartialEq)]
+pub struct GradientParseError {
+    pub position: usize,
+    pub kind: GradientParseErrorKind,
+}
+
+impl GradientParseError {
+    fn new(position: usize, kind: GradientParseErrorKind) -> Self {
+        Self { position, kind }
+    }
+}
+
+impl Display for GradientParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            GradientParseErrorKind::UnknownFunction(name) => write!(f, "unknown gradient function `{}`", name)?,
+            GradientParseErrorKind::MissingParenthesis => write!(f, "missing parenthesis")?,
+            GradientParseErrorKind::EmptyArgument => write!(f, "empty argument")?,
+            GradientParseErrorKind::InvalidDirection(direction) => write!(f, "invalid gradient direction `{}`", direction)?,
+            GradientParseErrorKind::InvalidPosition(position) => write!(f, "invalid position `{}`", position)?,
+            GradientParseErrorKind::InvalidStop(stop) => write!(f, "invalid color stop `{}`", stop)?,
+            GradientParseErrorKind::InvalidColor(error) => write!(f, "{}", error)?,
+            GradientParseErrorKind::TooFewStops => write!(f, "gradient needs at least two color stops")?,
+            GradientParseErrorKind::InvalidGradient(error) => write!(f, "{}", error)?,
+        }
+        write!(f, " at {}", self.position)
+    }
+}
+
+impl std::error::Error for GradientParseError {}
+
+/// 带有在输入字符串中偏移量的片段
+#[derive(Debug, Clone, Copy)]
+struct Token<'a> {
+    text: &'a str,
+    offset: usize,
+}
+
+impl<'a> Token<'a> {
+    fn trimmed(text: &'a str, offset: usize) -> Self {
+        let start = text.len() - text.trim_start().len();
+        Self {
+            text: text.trim(),
+            offset: offset + start,
+        }
+    }
+
+    fn error(&self, kind: GradientParseErrorKind) -> GradientParseError {
+        GradientParseError::new(self.offset, kind)
+    }
+}
+
+/// 在括号外按分隔符拆分，保留每一段的偏移量
+fn split_top_level(token: Token, separator: impl Fn(char) -> bool) -> Vec<Token> {
+    let mut parts = vec![];
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (index, c) in token.text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            c if depth == 0 && separator(c) => {
+                parts.push(Token::trimmed(&token.text[start..index], token.offset + start));
+                start = index + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(Token::trimmed(&token.text[start..], token.offset + start));
+    parts
+}
+
+fn split_arguments(token: Token) -> Result<Vec<Token>, GradientParseError> {
+    let arguments = split_top_level(token, |c| c == ',');
+    match arguments.iter().find(|argument| argument.text.is_empty()) {
+        Some(empty) => Err(empty.error(GradientParseErrorKind::EmptyArgument)),
+        None => Ok(arguments),
+    }
+}
+
+fn split_words(token: Token) -> Vec<Token> {
+    split_top_level(token, char::is_whitespace)
+        .into_iter()
+        .filter(|word| !word.text.is_empty())
+        .collect()
+}
+
+/// 解析 CSS 渐变函数，支持 `linear-gradient()` 和 `radial-gradient()`
+pub(crate) fn parse_css_gradient(input: &str) -> Result<Color, GradientParseError> {
+    let token = Token::trimmed(input, 0);
+    let open = token
+        .text
+        .find('(')
+        .ok_or_else(|| token.error(GradientParseErrorKind::MissingParenthesis))?;
+    let name = &token.text[..open];
+    let body = token.text[open + 1..]
+        .strip_suffix(')')
+        .ok_or_else(|| GradientParseError::new(token.offset + token.text.len(), GradientParseErrorKind::MissingParenthesis))?;
+    let arguments = split_arguments(Token {
+        text: body,
+        offset: token.offset + open + 1,
+    })?;
+
+    if name.eq_ignore_ascii_case("linear-gradient") {
+        let (direction, stops) = match parse_linear_direction(arguments[0])? {
+            Some(direction) => (direction, &arguments[1..]),
+            // 省略方向时默认从上到下
+            None => (CssLinearDirection::default(), &arguments[..]),
+        };
+        Ok(Color::CssLinearGradient(parse_stops(stops, token)?, direction))
+    } else if name.eq_ignore_ascii_case("radial-gradient") {
+        let (radial, stops) = match parse_radial_prelude(arguments[0])? {
+            Some(radial) => (radial, &arguments[1..]),
+            None => (CssRadialGradient::default(), &arguments[..]),
+        };
+        Ok(Color::CssRadialGradient(parse_stops(stops, token)?, radial))
+    } else {
+        Err(token.error(GradientParseErrorKind::UnknownFunction(name.trim().to_string())))
+    }
+}
+
+/// 解析逗号分隔的色标列表，如 `#f00, blue 30%, rgba(0,0,0,.5)`
+pub(crate) fn parse_gradient_stops(input: &str) -> Result<GradientColor, GradientParseError> {
+    let token = Token::trimmed(input, 0);
+    parse_stops(&split_arguments(token)?, token)
+}
+
+/// 返回 None 表示第一个参数不是方向，而是色标
+fn parse_linear_direction(token: Token) -> Result<Option<CssLinearDirection>, GradientParseError> {
+    let words = split_words(token);
+    if !words[0].text.eq_ignore_ascii_case("to") {
+        return Ok(parse_angle(token.text).map(CssLinearDirection::Angle));
+    }
+
+    let invalid = || token.error(GradientParseErrorKind::InvalidDirection(token.text.to_string()));
+    let mut vertical = None;
+    let mut horizontal = None;
+    for word in &words[1..] {
+        match word.text.to_ascii_lowercase().as_str() {
+            "top" if vertical.is_none() => vertical = Some(0.0),
+            "bottom" if vertical.is_none() => vertical = Some(180.0),
+            "left" if horizontal.is_none() => horizontal = Some(270.0),
+            "right" if horizontal.is_none() => horizontal = Some(90.0),
+            _ => return Err(invalid()),
+        }
+    }
+
+    // 指向角时角度取决于外接矩形的长宽比，绘制时再计算
+    let direction = match (vertical, horizontal) {
+        (Some(vertical), None) => CssLinearDirection::Angle(vertical),
+        (None, Some(horizontal)) => CssLinearDirection::Angle(horizontal),
+        (Some(0.0), Some(90.0)) => GradientCorner::TopRight.into(),
+        (Some(180.0), Some(90.0)) => GradientCorner::BottomRight.into(),
+        (Some(180.0), Some(270.0)) => GradientCorner::BottomLeft.into(),
+        (Some(0.0), Some(270.0)) => GradientCorner::TopLeft.into(),
+        _ => return Err(invalid()),
+    };
+    Ok(Some(direction))
+}
+
+/// 角度必须带单位，只有 0 可以省略单位
+fn parse_angle(value: &str) -> Option<f32> {
+    let lower = value.to_ascii_lowercase();
+    let has_unit = ["deg", "grad", "rad", "turn"].iter().any(|unit| lower.ends_with(unit));
+    if !has_unit && lower.parse::<f32>().ok() != Some(0.0) {
+        return None;
+    }
+    parse_hue(&lower).ok()
+}
+
+/// 解析 `[<shape> || <extent>] [at <position>]`，返回 None 表示第一个参数是色标
+fn parse_radial_prelude(token: Token) -> Result<Option<CssRadialGradient>, GradientParseError> {
+    let words = split_words(token);
+    let mut shape = None;
+    let mut extent = None;
+    let mut position = None;
+
+    let mut index = 0;
+    while index < words.len() {
+        let word = words[index];
+        let invalid = || word.error(GradientParseErrorKind::InvalidDirection(word.text.to_string()));
+        match word.text.to_ascii_lowercase().as_str() {
+            "circle" if shape.is_none() => shape = Some(RadialShape::Circle),
+            "ellipse" if shape.is_none() => shape = Some(RadialShape::Ellipse),
+            "closest-side" if extent.is_none() => extent = Some(RadialExtent::ClosestSide),
+            "farthest-side" if extent.is_none() => extent = Some(RadialExtent::FarthestSide),
+            "closest-corner" if extent.is_none() => extent = Some(RadialExtent::ClosestCorner),
+            "farthest-corner" if extent.is_none() => extent = Some(RadialExtent::FarthestCorner),
+            "at" if position.is_none() => {
+                position = Some(parse_radial_position(token, &words[index + 1..])?);
+                break;
+            }
+            // 第一个词不是关键字时，整个参数是色标
+            _ if index == 0 => return Ok(None),
+            _ => return Err(invalid()),
+        }
+        index += 1;
+    }
+
+    let mut radial = CssRadialGradient::new(shape.unwrap_or_default(), extent.unwrap_or_default());
+    if let Some(position) = position {
+        radial = radial.at(position);
+    }
+    Ok(Some(radial))
+}
+
+fn parse_radial_position(token: Token, words: &[Token]) -> Result<Point, GradientParseError> {
+    let keyword = |word: &Token| match word.text.to_ascii_lowercase().as_str() {
+        "left" | "top" => Some(0.0),
+        "center" => Some(0.5),
+        "right" | "bottom" => Some(1.0),
+        _ => None,
+    };
+    let is_vertical = |word: &Token| ["top", "bottom"].iter().any(|k| word.text.eq_ignore_ascii_case(k));
+    let is_horizontal = |word: &Token| ["left", "right"].iter().any(|k| word.text.eq_ignore_ascii_case(k));
+    let value = |word: &Token| {
+        keyword(word)
+            .or_else(|| parse_percentage(word.text))
+            .ok_or_else(|| word.error(GradientParseErrorKind::InvalidPosition(word.text.to_string())))
+    };
+
+    match words {
+        [word] if is_vertical(word) => Ok(Point::new(0.5, value(word)?)),
+        [word] => Ok(Point::new(value(word)?, 0.5)),
+        // 关键字可以交换顺序，如 `top left`
+        [first, second] if is_vertical(first) || is_horizontal(second) => Ok(Point::new(value(second)?, value(first)?)),
+        [first, second] => Ok(Point::new(value(first)?, value(second)?)),
+        _ => Err(token.error(GradientParseErrorKind::InvalidPosition(token.text.to_string()))),
+    }
+}
+
+/// 只支持百分比和 0，其他长度需要知道图形尺寸
+fn parse_percentage(value: &str) -> Option<f32> {
+    match value.strip_suffix('%') {
+        Some(percent) => percent.parse::<f32>().ok().filter(|p| p.is_finite()).map(|p| p / 100.0),
+        None => value.parse::<f32>().ok().filter(|p| *p == 0.0),
+    }
+}
+
+/// 把位置递增的色标截断到 0~1 之间，超出范围时在 0 和 1 处插入按 sRGB 插值的色标
+fn clip_stops(stops: Vec<GradientStop>) -> Vec<GradientStop> {
+    let in_range = |stop: &GradientStop| (0.0..=1.0).contains(&stop.position);
+    if stops.iter().all(in_range) {
+        return stops;
+    }
+    // 位置相同时取后一个色标，与 GradientColor::sample 相同
+    let color_at = |position: f32| {
+        let index = stops.partition_point(|stop| stop.position <= position);
+        match (index.checked_sub(1).map(|index| &stops[index]), stops.get(index)) {
+            (Some(from), Some(to)) => {
+                let local = (position - from.position) / (to.position - from.position);
+                from.color().mix(&to.color(), local, ColorSpace::Srgb)
+            }
+            (Some(stop), None) | (None, Some(stop)) => stop.color(),
+            (None, None) => unreachable!(),
+        }
+    };
+    let mut clipped = vec![GradientStop::new(color_at(0.0), 0.0)];
+    clipped.extend(stops.iter().filter(|stop| in_range(stop)).cloned());
+    clipped.push(GradientStop::new(color_at(1.0), 1.0));
+    clipped
+}
+
+/// 解析色标并按 CSS 规则补全省略的位置：
+/// 首尾省略时取 0 和 1，位置小于之前的最大值时取之前的最大值，中间省略的色标均匀分布
+/// 位置可以超出 0~1，超出的部分在 0 和 1 处截断，截断处的颜色按两侧色标插值
+fn parse_stops(arguments: &[Token], whole: Token) -> Result<GradientColor, GradientParseError> {
+    let mut stops: Vec<(SolidColor, Option<f32>, Token)> = vec![];
+    for argument in arguments {
+        let words = split_words(*argument);
+        let color = parse_solid_color(words[0].text).map_err(|error| {
+            // 单独的位置是颜色提示，暂不支持
+            let kind = if parse_percentage(words[0].text).is_some() {
+                GradientParseErrorKind::InvalidStop(argument.text.to_string())
+            } else {
+                GradientParseErrorKind::InvalidColor(error)
+            };
+            words[0].error(kind)
+        })?;
+        let positions = words[1..]
+            .iter()
+            .map(|word| {
+                parse_percentage(word.text)
+                    .map(|position| (position, *word))
+                    .ok_or_else(|| word.error(GradientParseErrorKind::InvalidPosition(word.text.to_string())))
+            })
+            .collect::<Result<Vec<(f32, Token)>, GradientParseError>>()?;
+        match positions.as_slice() {
+            [] => stops.push((color, None, *argument)),
+            [(position, word)] => stops.push((color, Some(*position), *word)),
+            // 双位置色标等价于两个相同颜色的色标
+            [(start, start_word), (end, end_word)] => {
+                stops.push((color, Some(*start), *start_word));
+                stops.push((color, Some(*end), *end_word));
+            }
+            _ => return Err(argument.error(GradientParseErrorKind::InvalidStop(argument.text.to_string()))),
+        }
+    }
+
+    if stops.len() < 2 {
+        return Err(whole.error(GradientParseErrorKind::TooFewStops));
+    }
+
+    let last = stops.len() - 1;
+    stops[0].1.get_or_insert(0.0);
+    stops[last].1.get_or_insert(1.0);
+
+    let mut max = f32::NEG_INFINITY;
+    for stop in stops.iter_mut() {
+        if let Some(position) = stop.1.as_mut() {
+            *position = position.max(max);
+            max = *position;
+        }
+    }
+
+    let mut index = 1;
+    while index < last {
+        if stops[index].1.is_some() {
+            index += 1;
+            continue;
+        }
+        let start = index - 1;
+        let end = (index..=last).find(|i| stops[*i].1.is_some()).unwrap_or(last);
+        let (from, to) = (stops[start].1.unwrap_or(0.0), stops[end].1.unwrap_or(1.0));
+        for (step, stop) in stops[index..end].iter_mut().enumerate() {
+            stop.1 = Some(from + (to - from) * (step + 1) as f32 / (end - start) as f32);
+        }
+        index = end;
+    }
+
+    let stops = stops
+        .into_iter()
+        .map(|(color, position, _)| GradientStop::new(color, position.unwrap_or(0.0)))
+        .collect();
+    let gradient = GradientColor::new(clip_stops(stops));
+    gradient
+        .validate()
+        .map_err(|error| whole.error(GradientParseErrorKind::InvalidGradient(error)))?;
+    Ok(gradient)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn linear(input: &str) -> (GradientColor, CssLinearDirection) {
+        match parse_css_gradient(input).unwrap() {
+            Color::CssLinearGradient(gradient, direction) => (gradient, direction),
+            color => panic!("unexpected {color:?}"),
+        }
+    }
+
+    fn positions(gradient: &GradientColor) -> Vec<f32> {
+        gradient.stops.iter().map(|stop| stop.position).collect()
+    }
+
+    fn assert_color_close(actual: SolidColor, expected: SolidColor) {
+        let close = |a: f32, b: f32| (a - b).abs() < 1e-4;
+        assert!(
+            close(actual.r, expected.r) && close(actual.g, expected.g) && close(actual.b, expected.b) && close(actual.a, expected.a),
+            "{actual:?} != {expected:?}"
+        );
+    }
+
+    #[test]
+    fn stops_outside_range_are_clipped() {
+        let (gradient, _) = linear("linear-gradient(red -20%, blue 120%)");
+        assert_eq!(positions(&gradient), vec![0.0, 1.0]);
+        //0 和 1 处的颜色按原来的 -20%~120% 插值
+        let red = SolidColor::from_rgba(1.0, 0.0, 0.0, 1.0);
+        let blue = SolidColor::from_rgba(0.0, 0.0, 1.0, 1.0);
+        assert_color_close(gradient.stops[0].color(), red.mix(&blue, 0.2 / 1.4, ColorSpace::Srgb));
+        assert_color_close(gradient.stops[1].color(), red.mix(&blue, 1.2 / 1.4, ColorSpace::Srgb));
+        assert!(gradient.validate().is_ok());
+    }
+
+    #[test]
+    fn stops_inside_range_are_kept() {
+        let (gradient, _) = linear("linear-gradient(red -50%, lime 50%, blue 150%)");
+        assert_eq!(positions(&gradient), vec![0.0, 0.5, 1.0]);
+        assert_eq!(gradient.stops[1].color(), SolidColor::from_rgba(0.0, 1.0, 0.0, 1.0));
+    }
+
+    #[test]
+    fn stops_all_before_range() {
+        let (gradient, _) = linear("linear-gradient(red -50%, blue -10%)");
+        let blue = SolidColor::from_rgba(0.0, 0.0, 1.0, 1.0);
+        assert_eq!(positions(&gradient), vec![0.0, 1.0]);
+        assert!(gradient.stops.iter().all(|stop| stop.color() == blue));
+    }
+
+    #[test]
+    fn negative_position_is_not_raised_to_zero() {
+        //之前的最大值从第一个色标开始计算，负的位置保持不变
+        let (gradient, _) = linear("linear-gradient(red -100%, blue 100%)");
+        let red = SolidColor::from_rgba(1.0, 0.0, 0.0, 1.0);
+        let blue = SolidColor::from_rgba(0.0, 0.0, 1.0, 1.0);
+        assert_color_close(gradient.stops[0].color(), red.mix(&blue, 0.5, ColorSpace::Srgb));
+    }
+
+    #[test]
+    fn corner_direction() {
+        let (_, direction) = linear("linear-gradient(to top right, red, blue)");
+        assert_eq!(direction, CssLinearDirection::Corner(GradientCorner::TopRight));
+        let (_, direction) = linear("linear-gradient(to left bottom, red, blue)");
+        assert_eq!(direction, CssLinearDirection::Corner(GradientCorner::BottomLeft));
+        let (_, direction) = linear("linear-gradient(to right, red, blue)");
+        assert_eq!(direction, CssLinearDirection::Angle(90.0));
+        let (_, direction) = linear("linear-gradient(red, blue)");
+        assert_eq!(direction, CssLinearDirection::Angle(180.0));
+    }
+
+    #[test]
+    fn corner_angle_depends_on_aspect_ratio() {
+        let close = |a: f32, b: f32| (a - b).abs() < 1e-3;
+        assert!(close(GradientCorner::TopRight.angle(100.0, 100.0), 45.0));
+        assert!(close(GradientCorner::BottomRight.angle(100.0, 100.0), 135.0));
+        assert!(close(GradientCorner::BottomLeft.angle(100.0, 100.0), 225.0));
+        assert!(close(GradientCorner::TopLeft.angle(100.0, 100.0), 315.0));
+        //宽的矩形渐变线更接近竖直方向，垂直于左上到右下的对角线
+        let angle = GradientCorner::TopRight.angle(200.0, 100.0);
+        assert!(close(angle, 0.5f32.atan().to_degrees()));
+        let (sin, cos) = angle.to_radians().sin_cos();
+        assert!(close(sin * 200.0 - cos * 100.0, 0.0));
+    }
+}
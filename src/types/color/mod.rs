@@ -1,3 +1,5 @@
+use std::str::FromStr;
+
 pub type Direct2DColor = windows::Win32::Graphics::Direct2D::Common::D2D1_COLOR_F;
 
 mod solid_color;
@@ -8,11 +10,56 @@ mod gradient_color;
 
 pub use gradient_color::*;
 
+mod gradient_geometry;
+
+pub use gradient_geometry::*;
+
+mod conic_gradient;
+
+pub use conic_gradient::*;
+
+mod pattern;
+
+pub use pattern::*;
+
+mod color_space;
+
+pub use color_space::*;
+
+mod named_color;
+
+mod color_parse;
+
+pub use color_parse::ColorParseError;
+
+mod gradient_parse;
+
+pub use gradient_parse::{GradientParseError, GradientParseErrorKind};
+
 #[derive(Clone, Debug)]
 pub enum Color {
     SolidColor(Direct2DColor),
     LinearGradient(GradientColor, f32),
     RadialGradient(GradientColor),
+    LinearGradientGeometry(GradientColor, LinearGradientGeometry),
+    RadialGradientGeometry(GradientColor, RadialGradientGeometry),
+    /// 与 CSS `linear-gradient()` 一致，方向为角度或指向的角
+    CssLinearGradient(GradientColor, CssLinearDirection),
+    CssRadialGradient(GradientColor, CssRadialGradient),
+    ConicGradient(GradientColor, ConicGradient),
+    Pattern(Pattern),
+}
+
+impl Color {
+    /// 解析 CSS 颜色字符串，如 "#ff0000"、"rgb(255, 0, 0)"、"hsl(0, 100%, 50%)"、"red"
+    /// 也支持 "linear-gradient(45deg, #f00, blue 30%)" 和 "radial-gradient(circle at 30% 40%, red, transparent)"
+    pub fn parse(input: &str) -> Result<Self, ColorParseError> {
+        let lower = input.trim_start().to_ascii_lowercase();
+        if lower.starts_with("linear-gradient") || lower.starts_with("radial-gradient") {
+            return gradient_parse::parse_css_gradient(input).map_err(|error| ColorParseError::Gradient(Box::new(error)));
+        }
+        SolidColor::from_str(input).map(Color::from)
+    }
 }
 
 impl Default for Color {
@@ -32,6 +79,12 @@ impl PartialEq for Color {
             (Color::SolidColor(a), Color::SolidColor(b)) => a.eq(b),
             (Color::LinearGradient(a, angle1), Color::LinearGradient(b, angle2)) => a.eq(b) && *angle1 == *angle2,
             (Color::RadialGradient(a), Color::RadialGradient(b)) => a.eq(b),
+            (Color::LinearGradientGeometry(a, geometry1), Color::LinearGradientGeometry(b, geometry2)) => a.eq(b) && geometry1 == geometry2,
+            (Color::RadialGradientGeometry(a, geometry1), Color::RadialGradientGeometry(b, geometry2)) => a.eq(b) && geometry1 == geometry2,
+            (Color::CssLinearGradient(a, direction1), Color::CssLinearGradient(b, direction2)) => a.eq(b) && direction1 == direction2,
+            (Color::CssRadialGradient(a, radial1), Color::CssRadialGradient(b, radial2)) => a.eq(b) && radial1 == radial2,
+            (Color::ConicGradient(a, conic1), Color::ConicGradient(b, conic2)) => a.eq(b) && conic1 == conic2,
+            (Color::Pattern(a), Color::Pattern(b)) => a == b,
             _ => false,
         }
     }
@@ -77,3 +130,26 @@ impl From<(u8, u8, u8, u8)> for Color {
         Color::SolidColor(color)
     }
 }
+
+// from 0xRRGGBBAA
+impl From<u32> for Color {
+    fn from(color: u32) -> Self {
+        SolidColor::from(color).into()
+    }
+}
+
+impl FromStr for Color {
+    type Err = ColorParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Color::parse(s)
+    }
+}
+
+impl TryFrom<&str> for Color {
+    type Error = ColorParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        Color::parse(value)
+    }
+}
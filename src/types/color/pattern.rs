@@ -0,0 +1,233 @@
+use crate::{ColorSpace, SolidColor};
+
+/// 每个像素在两个方向上的采样数，用于抗锯齿
+const PATTERN_SUPERSAMPLE: u32 = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PatternKind {
+    /// 平行线，angle 为 0 时为水平线
+    Stripes,
+    /// 斜线，angle 为 0 时为 45 度
+    DiagonalHatch,
+    /// 交叉斜线，angle 为 0 时为 45 度和 135 度
+    CrossHatch,
+    /// 点阵，点的直径为 line_width
+    Dots,
+    /// 棋盘格，每格边长为 spacing
+    Checkerboard,
+}
+
+/// 平铺图案填充
+/// spacing 为图案重复的间距，line_width 为线宽，angle 为整体旋转角度（顺时针）
+/// 图案以绘制坐标系原点对齐，相邻图形的图案可以无缝衔接
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pattern {
+    pub kind: PatternKind,
+    pub foreground: SolidColor,
+    pub background: SolidColor,
+    pub spacing: f32,
+    pub line_width: f32,
+    pub angle: f32,
+}
+
+/// 图案的一个平铺单元
+#[derive(Clone, Debug, PartialEq)]
+pub struct PatternTile {
+    pub width: u32,
+    pub height: u32,
+    /// 按行排列的像素，未预乘透明度
+    pub pixels: Vec<SolidColor>,
+}
+
+impl Pattern {
+    pub fn new(kind: PatternKind, foreground: SolidColor, background: SolidColor) -> Self {
+        Self {
+            kind,
+            foreground,
+            background,
+            spacing: 8.0,
+            line_width: 1.0,
+            angle: 0.0,
+        }
+    }
+
+    pub fn with_spacing(mut self, spacing: f32) -> Self {
+        self.spacing = spacing;
+        self
+    }
+
+    pub fn with_line_width(mut self, line_width: f32) -> Self {
+        self.line_width = line_width;
+        self
+    }
+
+    pub fn with_angle(mut self, angle: f32) -> Self {
+        self.angle = angle;
+        self
+    }
+
+    /// 平铺单元的尺寸
+    pub fn tile_size(&self) -> f32 {
+        let spacing = self.spacing.max(1.0);
+        match self.kind {
+            PatternKind::Checkerboard => spacing * 2.0,
+            _ => spacing,
+        }
+    }
+
+    /// 平铺单元需要旋转的角度
+    pub fn tile_angle(&self) -> f32 {
+        match self.kind {
+            PatternKind::DiagonalHatch | PatternKind::CrossHatch => self.angle + 45.0,
+            _ => self.angle,
+        }
+    }
+
+    /// 平铺单元内 (x, y) 处是否为前景色，x、y 为未旋转的单元坐标
+    pub fn covers(&self, x: f32, y: f32) -> bool {
+        let spacing = self.spacing.max(1.0);
+        let half_width = self.line_width / 2.0;
+        //到最近的网格线的距离
+        let line_distance = |value: f32| {
+            let value = value.rem_euclid(spacing);
+            value.min(spacing - value)
+        };
+        match self.kind {
+            PatternKind::Stripes | PatternKind::DiagonalHatch => line_distance(y) <= half_width,
+            PatternKind::CrossHatch => line_distance(x) <= half_width || line_distance(y) <= half_width,
+            PatternKind::Dots => {
+                let dx = x.rem_euclid(spacing) - spacing / 2.0;
+                let dy = y.rem_euclid(spacing) - spacing / 2.0;
+                dx.hypot(dy) <= half_width
+            }
+            PatternKind::Checkerboard => {
+                ((x / spacing).floor() + (y / spacing).floor()).rem_euclid(2.0) == 0.0
+            }
+        }
+    }
+
+    /// 生成未旋转的平铺单元，scale 为每个单位对应的像素数
+    pub fn generate_tile(&self, scale: f32) -> PatternTile {
+        let tile_size = self.tile_size();
+        let pixel_size = (tile_size * scale).round().max(1.0) as u32;
+        let step = tile_size / pixel_size as f32;
+        let samples = PATTERN_SUPERSAMPLE * PATTERN_SUPERSAMPLE;
+
+        let mut pixels = Vec::with_capacity((pixel_size * pixel_size) as usize);
+        for py in 0..pixel_size {
+            for px in 0..pixel_size {
+                let mut covered = 0;
+                for sy in 0..PATTERN_SUPERSAMPLE {
+                    for sx in 0..PATTERN_SUPERSAMPLE {
+                        let x = (px as f32 + (sx as f32 + 0.5) / PATTERN_SUPERSAMPLE as f32) * step;
+                        let y = (py as f32 + (sy as f32 + 0.5) / PATTERN_SUPERSAMPLE as f32) * step;
+                        if self.covers(x, y) {
+                            covered += 1;
+                        }
+                    }
+                }
+                let coverage = covered as f32 / samples as f32;
+                pixels.push(self.background.premultiply().mix(&self.foreground.premultiply(), coverage, ColorSpace::Srgb).unpremultiply());
+            }
+        }
+
+        PatternTile {
+            width: pixel_size,
+            height: pixel_size,
+            pixels,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn black() -> SolidColor {
+        SolidColor::from_rgba(0.0, 0.0, 0.0, 1.0)
+    }
+
+    fn white() -> SolidColor {
+        SolidColor::from_rgba(1.0, 1.0, 1.0, 1.0)
+    }
+
+    /// 前景为白色、背景为黑色时像素的红色分量就是覆盖率
+    fn coverage(tile: &PatternTile, x: u32, y: u32) -> f32 {
+        tile.pixels[(y * tile.width + x) as usize].r
+    }
+
+    fn assert_close(actual: f32, expected: f32) {
+        assert!((actual - expected).abs() < 1e-4, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn tile_size_follows_scale() {
+        let stripes = Pattern::new(PatternKind::Stripes, white(), black()).with_spacing(8.0);
+        let tile = stripes.generate_tile(1.0);
+        assert_eq!((tile.width, tile.height), (8, 8));
+        assert_eq!(tile.pixels.len(), 64);
+        let tile = stripes.generate_tile(2.0);
+        assert_eq!((tile.width, tile.height), (16, 16));
+        assert_eq!(tile.pixels.len(), 256);
+
+        let checkerboard = Pattern::new(PatternKind::Checkerboard, white(), black()).with_spacing(8.0);
+        assert_eq!(checkerboard.generate_tile(1.0).width, 16);
+        assert_eq!(checkerboard.generate_tile(2.0).width, 32);
+        //间距太小时至少为 1
+        assert_eq!(stripes.with_spacing(0.0).generate_tile(1.0).width, 1);
+    }
+
+    #[test]
+    fn stripes_coverage() {
+        let stripes = Pattern::new(PatternKind::Stripes, white(), black()).with_spacing(8.0).with_line_width(1.0);
+        let tile = stripes.generate_tile(1.0);
+        //线的中心在 y = 0 处，上下各半个像素分别落在第一行和最后一行，每行 4 个采样中有 2 个被覆盖
+        for x in 0..8 {
+            assert_close(coverage(&tile, x, 0), 0.5);
+            assert_close(coverage(&tile, x, 7), 0.5);
+            for y in 1..7 {
+                assert_close(coverage(&tile, x, y), 0.0);
+            }
+        }
+        //放大 2 倍后线宽为 2 个像素，完整覆盖第一行和最后一行
+        let tile = stripes.generate_tile(2.0);
+        assert_close(coverage(&tile, 0, 0), 1.0);
+        assert_close(coverage(&tile, 0, 15), 1.0);
+        assert_close(coverage(&tile, 0, 1), 0.0);
+    }
+
+    #[test]
+    fn dots_coverage() {
+        let dots = Pattern::new(PatternKind::Dots, white(), black()).with_spacing(8.0).with_line_width(4.0);
+        let tile = dots.generate_tile(1.0);
+        //点在单元中心，中心的四个像素完全覆盖，角落没有覆盖
+        for (x, y) in [(3, 3), (4, 3), (3, 4), (4, 4)] {
+            assert_close(coverage(&tile, x, y), 1.0);
+        }
+        for (x, y) in [(0, 0), (7, 0), (0, 7), (7, 7)] {
+            assert_close(coverage(&tile, x, y), 0.0);
+        }
+        //边缘的像素部分覆盖，覆盖率的和接近圆的面积
+        assert!(coverage(&tile, 5, 3) > 0.0 && coverage(&tile, 5, 3) < 1.0);
+        let total = tile.pixels.iter().map(|pixel| pixel.r).sum::<f32>();
+        assert!((total - std::f32::consts::PI * 4.0).abs() < 1.0, "{total}");
+    }
+
+    #[test]
+    fn coverage_uses_supersampling() {
+        let stripes = Pattern::new(PatternKind::Stripes, white(), black()).with_spacing(8.0).with_line_width(0.5);
+        let tile = stripes.generate_tile(1.0);
+        //线宽 0.5 时第一行只有 y = 0.125 一个采样被覆盖
+        assert_close(coverage(&tile, 0, 0), 1.0 / PATTERN_SUPERSAMPLE as f32);
+    }
+
+    #[test]
+    fn tile_angle() {
+        let pattern = |kind: PatternKind| Pattern::new(kind, white(), black()).with_angle(30.0);
+        assert_eq!(pattern(PatternKind::Stripes).tile_angle(), 30.0);
+        assert_eq!(pattern(PatternKind::Dots).tile_angle(), 30.0);
+        assert_eq!(pattern(PatternKind::Checkerboard).tile_angle(), 30.0);
+        assert_eq!(pattern(PatternKind::DiagonalHatch).tile_angle(), 75.0);
+        assert_eq!(pattern(PatternKind::CrossHatch).tile_angle(), 75.0);
+    }
+}
@@ -1,5 +1,7 @@
 use std::ops::Deref;
-use crate::Direct2DColor;
+use std::str::FromStr;
+use crate::{ColorParseError, Direct2DColor};
+use super::color_parse::parse_solid_color;
 
 #[repr(transparent)]
 #[derive(Clone, Copy, Debug)]
@@ -27,6 +29,15 @@ impl SolidColor {
             }
         )
     }
+
+    pub fn from_rgba8(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self::from_rgba(
+            r as f32 / 255.0,
+            g as f32 / 255.0,
+            b as f32 / 255.0,
+            a as f32 / 255.0,
+        )
+    }
 }
 
 impl PartialEq  for SolidColor {
@@ -47,4 +58,27 @@ impl Deref for SolidColor {
     fn deref(&self) -> &Self::Target {
         &self.0
     }
-}
\ No newline at end of file
+}
+impl FromStr for SolidColor {
+    type Err = ColorParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_solid_color(s)
+    }
+}
+
+impl TryFrom<&str> for SolidColor {
+    type Error = ColorParseError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        parse_solid_color(value)
+    }
+}
+
+// from 0xRRGGBBAA
+impl From<u32> for SolidColor {
+    fn from(color: u32) -> Self {
+        let [r, g, b, a] = color.to_be_bytes();
+        Self::from_rgba8(r, g, b, a)
+    }
+}
@@ -3,4 +3,8 @@ pub use point::*;
 mod color;
 pub use color::*;
 mod rect;
-pub use rect::*;
\ No newline at end of file
+pub use rect::*;
+mod text_range;
+pub use text_range::*;
+mod path;
+pub use path::*;
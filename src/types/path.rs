@@ -0,0 +1,316 @@
+use crate::{Point, Rect};
+
+/// 贝塞尔曲线近似四分之一圆时控制点到端点的距离与半径之比
+const CIRCLE_KAPPA: f32 = 0.552_284_8;
+/// 测量长度时每段曲线拆分成的线段数量
+const CURVE_STEPS: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathSegment {
+    /// 开始一个新的子路径
+    MoveTo(Point),
+    LineTo(Point),
+    /// 二次贝塞尔曲线，依次为控制点和终点
+    QuadTo(Point, Point),
+    /// 三次贝塞尔曲线，依次为两个控制点和终点
+    CubicTo(Point, Point, Point),
+    /// 闭合当前子路径
+    Close,
+}
+
+/// 由直线和贝塞尔曲线组成的路径，坐标与绘制坐标系相同
+/// 填充时使用非零环绕规则
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Path {
+    segments: Vec<PathSegment>,
+}
+
+impl Path {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    pub fn move_to(&mut self, point: impl Into<Point>) {
+        self.segments.push(PathSegment::MoveTo(point.into()));
+    }
+
+    pub fn line_to(&mut self, point: impl Into<Point>) {
+        self.segments.push(PathSegment::LineTo(point.into()));
+    }
+
+    pub fn quad_to(&mut self, control: impl Into<Point>, point: impl Into<Point>) {
+        self.segments.push(PathSegment::QuadTo(control.into(), point.into()));
+    }
+
+    pub fn cubic_to(&mut self, control1: impl Into<Point>, control2: impl Into<Point>, point: impl Into<Point>) {
+        self.segments.push(PathSegment::CubicTo(control1.into(), control2.into(), point.into()));
+    }
+
+    pub fn close(&mut self) {
+        self.segments.push(PathSegment::Close);
+    }
+
+    /// 添加一个闭合的矩形子路径
+    pub fn add_rect(&mut self, left: f32, top: f32, right: f32, bottom: f32) {
+        self.move_to((left, top));
+        self.line_to((right, top));
+        self.line_to((right, bottom));
+        self.line_to((left, bottom));
+        self.close();
+    }
+
+    /// 添加一个闭合的椭圆子路径，由四段三次贝塞尔曲线近似
+    pub fn add_ellipse(&mut self, center: impl Into<Point>, radius_x: f32, radius_y: f32) {
+        let Point(center) = center.into();
+        let (x, y) = (center.x, center.y);
+        let (kx, ky) = (radius_x * CIRCLE_KAPPA, radius_y * CIRCLE_KAPPA);
+        self.move_to((x + radius_x, y));
+        self.cubic_to((x + radius_x, y + ky), (x + kx, y + radius_y), (x, y + radius_y));
+        self.cubic_to((x - kx, y + radius_y), (x - radius_x, y + ky), (x - radius_x, y));
+        self.cubic_to((x - radius_x, y - ky), (x - kx, y - radius_y), (x, y - radius_y));
+        self.cubic_to((x + kx, y - radius_y), (x + radius_x, y - ky), (x + radius_x, y));
+        self.close();
+    }
+
+    /// 追加另一条路径的所有子路径
+    pub fn append(&mut self, other: &Path) {
+        self.segments.extend_from_slice(&other.segments);
+    }
+
+    /// 对所有点（包括控制点）进行变换
+    pub fn map_points(&self, fun: impl Fn(Point) -> Point) -> Path {
+        let segments = self.segments.iter().map(|segment| match *segment {
+            PathSegment::MoveTo(point) => PathSegment::MoveTo(fun(point)),
+            PathSegment::LineTo(point) => PathSegment::LineTo(fun(point)),
+            PathSegment::QuadTo(control, point) => PathSegment::QuadTo(fun(control), fun(point)),
+            PathSegment::CubicTo(control1, control2, point) => PathSegment::CubicTo(fun(control1), fun(control2), fun(point)),
+            PathSegment::Close => PathSegment::Close,
+        }).collect();
+        Path { segments }
+    }
+
+    pub fn translate(&self, dx: f32, dy: f32) -> Path {
+        self.map_points(|Point(point)| Point::new(point.x + dx, point.y + dy))
+    }
+
+    /// 包含所有点（包括控制点）的矩形，空路径返回 None
+    pub fn bounds(&self) -> Option<Rect> {
+        let mut points = self.segments.iter().flat_map(|segment| match *segment {
+            PathSegment::MoveTo(point) | PathSegment::LineTo(point) => vec![point],
+            PathSegment::QuadTo(control, point) => vec![control, point],
+            PathSegment::CubicTo(control1, control2, point) => vec![control1, control2, point],
+            PathSegment::Close => vec![],
+        });
+        let Point(first) = points.next()?;
+        let (left, top, right, bottom) = points.fold((first.x, first.y, first.x, first.y), |(left, top, right, bottom), Point(point)| {
+            (left.min(point.x), top.min(point.y), right.max(point.x), bottom.max(point.y))
+        });
+        Some(Rect::new((left, top), right - left, bottom - top))
+    }
+}
+
+/// 按长度在路径上取点，曲线拆分成线段近似
+/// 多个子路径首尾相接计算长度，子路径之间的跳跃不计入长度
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PathMeasure {
+    /// (起点, 终点, 起点到路径开头的长度)
+    pieces: Vec<(Point, Point, f32)>,
+    length: f32,
+}
+
+impl PathMeasure {
+    pub fn new(path: &Path) -> Self {
+        let mut measure = Self::default();
+        let mut current = Point::default();
+        let mut figure_start = current;
+        for segment in path.segments() {
+            match *segment {
+                PathSegment::MoveTo(point) => {
+                    current = point;
+                    figure_start = point;
+                }
+                PathSegment::LineTo(point) => {
+                    measure.add_piece(current, point);
+                    current = point;
+                }
+                PathSegment::QuadTo(control, point) => {
+                    let start = current;
+                    for step in 1..=CURVE_STEPS {
+                        let t = step as f32 / CURVE_STEPS as f32;
+                        let next = bezier_point(&[start, control, point], t);
+                        measure.add_piece(current, next);
+                        current = next;
+                    }
+                }
+                PathSegment::CubicTo(control1, control2, point) => {
+                    let start = current;
+                    for step in 1..=CURVE_STEPS {
+                        let t = step as f32 / CURVE_STEPS as f32;
+                        let next = bezier_point(&[start, control1, control2, point], t);
+                        measure.add_piece(current, next);
+                        current = next;
+                    }
+                }
+                PathSegment::Close => {
+                    measure.add_piece(current, figure_start);
+                    current = figure_start;
+                }
+            }
+        }
+        measure
+    }
+
+    fn add_piece(&mut self, start: Point, end: Point) {
+        let length = (end.x - start.x).hypot(end.y - start.y);
+        if length > 0.0 {
+            self.pieces.push((start, end, self.length));
+            self.length += length;
+        }
+    }
+
+    pub fn length(&self) -> f32 {
+        self.length
+    }
+
+    /// 距离路径开头 distance 处的点和切线角度，角度单位为度，沿 x 轴正方向为 0，顺时针为正
+    /// distance 超出路径范围时返回 None
+    pub fn point_at(&self, distance: f32) -> Option<(Point, f32)> {
+        if !(0.0..=self.length).contains(&distance) {
+            return None;
+        }
+        let index = self.pieces.partition_point(|(_, _, start)| *start <= distance).checked_sub(1)?;
+        let (Point(start), Point(end), start_distance) = self.pieces[index];
+        let (dx, dy) = (end.x - start.x, end.y - start.y);
+        let t = ((distance - start_distance) / dx.hypot(dy)).min(1.0);
+        Some((Point::new(start.x + dx * t, start.y + dy * t), dy.atan2(dx).to_degrees()))
+    }
+}
+
+/// 用 de Casteljau 算法计算任意阶贝塞尔曲线上的点
+fn bezier_point(points: &[Point], t: f32) -> Point {
+    let mut points = points.to_vec();
+    while points.len() > 1 {
+        points = points.windows(2).map(|pair| {
+            let (Point(a), Point(b)) = (pair[0], pair[1]);
+            Point::new(a.x + (b.x - a.x) * t, a.y + (b.y - a.y) * t)
+        }).collect();
+    }
+    points[0]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_near(actual: f32, expected: f32, tolerance: f32) {
+        assert!((actual - expected).abs() <= tolerance, "{actual} != {expected}");
+    }
+
+    fn edges(rect: &Rect) -> (f32, f32, f32, f32) {
+        (rect.left, rect.top, rect.right, rect.bottom)
+    }
+
+    #[test]
+    fn bounds_of_empty_path() {
+        assert!(Path::new().bounds().is_none());
+        let mut path = Path::new();
+        path.close();
+        assert!(path.bounds().is_none());
+    }
+
+    #[test]
+    fn bounds_include_control_points() {
+        let mut path = Path::new();
+        path.add_rect(1.0, 2.0, 5.0, 4.0);
+        assert_eq!(edges(&path.bounds().unwrap()), (1.0, 2.0, 5.0, 4.0));
+
+        let mut path = Path::new();
+        path.move_to((0.0, 0.0));
+        path.quad_to((5.0, -10.0), (10.0, 0.0));
+        path.cubic_to((12.0, 3.0), (-2.0, 8.0), (10.0, 4.0));
+        assert_eq!(edges(&path.bounds().unwrap()), (-2.0, -10.0, 12.0, 8.0));
+    }
+
+    #[test]
+    fn translate_moves_bounds() {
+        let mut path = Path::new();
+        path.add_ellipse((0.0, 0.0), 3.0, 2.0);
+        assert_eq!(edges(&path.bounds().unwrap()), (-3.0, -2.0, 3.0, 2.0));
+        assert_eq!(edges(&path.translate(10.0, -1.0).bounds().unwrap()), (7.0, -3.0, 13.0, 1.0));
+    }
+
+    #[test]
+    fn measure_lines_and_close() {
+        let mut path = Path::new();
+        path.add_rect(0.0, 0.0, 4.0, 3.0);
+        let measure = PathMeasure::new(&path);
+        assert_eq!(measure.length(), 14.0);
+        let (Point(point), angle) = measure.point_at(2.0).unwrap();
+        assert_eq!((point.x, point.y, angle), (2.0, 0.0, 0.0));
+        //第二条边向下，顺时针为正
+        let (Point(point), angle) = measure.point_at(5.0).unwrap();
+        assert_eq!((point.x, point.y, angle), (4.0, 1.0, 90.0));
+        //闭合的边回到起点
+        let (Point(point), angle) = measure.point_at(13.0).unwrap();
+        assert_eq!((point.x, point.y, angle), (0.0, 1.0, -90.0));
+        assert!(measure.point_at(-0.1).is_none());
+        assert!(measure.point_at(14.1).is_none());
+    }
+
+    #[test]
+    fn jumps_between_figures_are_not_measured() {
+        let mut path = Path::new();
+        path.move_to((0.0, 0.0));
+        path.line_to((2.0, 0.0));
+        path.move_to((10.0, 10.0));
+        path.line_to((10.0, 13.0));
+        let measure = PathMeasure::new(&path);
+        assert_eq!(measure.length(), 5.0);
+        let (Point(point), angle) = measure.point_at(3.0).unwrap();
+        assert_eq!((point.x, point.y, angle), (10.0, 11.0, 90.0));
+    }
+
+    #[test]
+    fn measure_points_on_circle() {
+        let radius = 10.0;
+        let mut path = Path::new();
+        path.add_ellipse((0.0, 0.0), radius, radius);
+        let measure = PathMeasure::new(&path);
+        let circumference = 2.0 * std::f32::consts::PI * radius;
+        assert_near(measure.length(), circumference, 0.05);
+        //从最右边开始顺时针，每四分之一圆切线转 90 度
+        for (quarter, (x, y, angle)) in [(radius, 0.0, 90.0), (0.0, radius, 180.0), (-radius, 0.0, -90.0), (0.0, -radius, 0.0)].into_iter().enumerate() {
+            let distance = measure.length() * quarter as f32 / 4.0 + 0.01;
+            let (Point(point), actual_angle) = measure.point_at(distance).unwrap();
+            assert_near(point.x, x, 0.05);
+            assert_near(point.y, y, 0.05);
+            //角度在 ±180 处可能取到另一侧
+            let difference = (actual_angle - angle).rem_euclid(360.0);
+            assert!(difference.min(360.0 - difference) < 6.0, "quarter {quarter}: {actual_angle} != {angle}");
+        }
+    }
+
+    #[test]
+    fn measure_quadratic_curve() {
+        let mut path = Path::new();
+        path.move_to((0.0, 0.0));
+        path.quad_to((5.0, 10.0), (10.0, 0.0));
+        let measure = PathMeasure::new(&path);
+        //对称的曲线中点在顶点，切线接近水平，角度取自拆分后的线段
+        let (Point(point), angle) = measure.point_at(measure.length() / 2.0).unwrap();
+        assert_near(point.x, 5.0, 0.01);
+        assert_near(point.y, 5.0, 0.05);
+        assert!(angle.abs() < 8.0, "{angle}");
+        //起点沿控制点方向
+        let (_, angle) = measure.point_at(0.0).unwrap();
+        assert_near(angle, 10.0f32.atan2(5.0).to_degrees(), 4.0);
+    }
+}
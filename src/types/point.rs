@@ -10,7 +10,7 @@ pub fn create_point(x: f32, y: f32) -> Direct2DPoint {
 }
 
 #[repr(transparent)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Point(pub(crate) Direct2DPoint);
 
 impl Point {
@@ -0,0 +1,142 @@
+use std::ops::Range;
+use windows::Win32::Graphics::DirectWrite::DWRITE_TEXT_RANGE;
+
+/// 字符串的 UTF-16 长度，DirectWrite 中的位置和长度都以 UTF-16 码元计算
+pub fn utf16_len(text: &str) -> usize {
+    text.chars().map(char::len_utf16).sum()
+}
+
+/// 将字节偏移转换为 UTF-16 偏移，偏移不在字符边界上时按所在字符的起始位置计算
+pub fn byte_to_utf16(text: &str, byte_index: usize) -> usize {
+    let mut utf16_index = 0;
+    for (index, c) in text.char_indices() {
+        if index + c.len_utf8() > byte_index {
+            break;
+        }
+        utf16_index += c.len_utf16();
+    }
+    utf16_index
+}
+
+/// 将 UTF-16 偏移转换为字节偏移，偏移落在代理对中间时按所在字符的起始位置计算
+pub fn utf16_to_byte(text: &str, utf16_index: usize) -> usize {
+    let mut current = 0;
+    for (index, c) in text.char_indices() {
+        if current + c.len_utf16() > utf16_index {
+            return index;
+        }
+        current += c.len_utf16();
+    }
+    text.len()
+}
+
+/// 将字节范围转换为 DirectWrite 的文本范围
+pub(crate) fn to_text_range(text: &str, range: Range<usize>) -> DWRITE_TEXT_RANGE {
+    let start = byte_to_utf16(text, range.start);
+    let end = byte_to_utf16(text, range.end.max(range.start));
+    DWRITE_TEXT_RANGE {
+        startPosition: start as u32,
+        length: (end - start) as u32,
+    }
+}
+
+/// 把字节范围扩展到字符边界上，起始位置向前、结束位置向后取整，并限制在文本长度内
+pub fn expand_to_char_boundaries(text: &str, range: Range<usize>) -> Range<usize> {
+    let mut start = range.start.min(text.len());
+    while !text.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut end = range.end.clamp(start, text.len());
+    while !text.is_char_boundary(end) {
+        end += 1;
+    }
+    start..end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 字节范围：a 0..1，😀 1..5，中 5..8，b 8..9
+    /// UTF-16 范围：a 0..1，😀 1..3，中 3..4，b 4..5
+    const TEXT: &str = "a😀中b";
+
+    #[test]
+    fn lengths() {
+        assert_eq!(utf16_len(TEXT), 5);
+        assert_eq!(utf16_len(""), 0);
+    }
+
+    #[test]
+    fn byte_to_utf16_boundaries() {
+        let expected = [(0, 0), (1, 1), (5, 3), (8, 4), (9, 5)];
+        for (byte_index, utf16_index) in expected {
+            assert_eq!(byte_to_utf16(TEXT, byte_index), utf16_index, "byte {byte_index}");
+        }
+    }
+
+    #[test]
+    fn utf16_to_byte_boundaries() {
+        let expected = [(0, 0), (1, 1), (3, 5), (4, 8), (5, 9)];
+        for (utf16_index, byte_index) in expected {
+            assert_eq!(utf16_to_byte(TEXT, utf16_index), byte_index, "utf16 {utf16_index}");
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        for text in [TEXT, "中文字符", "👨‍👩‍👧 family", "𝄞a𝄞"] {
+            for (index, _) in text.char_indices().chain(Some((text.len(), ' '))) {
+                assert_eq!(utf16_to_byte(text, byte_to_utf16(text, index)), index, "{text} {index}");
+            }
+            for utf16_index in 0..=utf16_len(text) {
+                let byte_index = utf16_to_byte(text, utf16_index);
+                assert!(text.is_char_boundary(byte_index));
+                assert!(byte_to_utf16(text, byte_index) <= utf16_index);
+            }
+        }
+    }
+
+    #[test]
+    fn index_inside_character() {
+        //字节偏移落在多字节字符中间时取该字符的起始位置
+        assert_eq!(byte_to_utf16(TEXT, 2), 1);
+        assert_eq!(byte_to_utf16(TEXT, 4), 1);
+        assert_eq!(byte_to_utf16(TEXT, 6), 3);
+        //UTF-16 偏移落在代理对中间时取该字符的起始位置
+        assert_eq!(utf16_to_byte(TEXT, 2), 1);
+    }
+
+    #[test]
+    fn index_out_of_range() {
+        assert_eq!(byte_to_utf16(TEXT, 100), 5);
+        assert_eq!(utf16_to_byte(TEXT, 100), TEXT.len());
+        assert_eq!(utf16_to_byte("", 1), 0);
+    }
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn text_range() {
+        let range = to_text_range(TEXT, 1..8);
+        assert_eq!((range.startPosition, range.length), (1, 3));
+        let range = to_text_range(TEXT, 5..9);
+        assert_eq!((range.startPosition, range.length), (3, 2));
+        //结束位置在起始位置之前时长度为 0
+        let range = to_text_range(TEXT, 5..1);
+        assert_eq!((range.startPosition, range.length), (3, 0));
+        let range = to_text_range(TEXT, 8..100);
+        assert_eq!((range.startPosition, range.length), (4, 1));
+    }
+
+    #[test]
+    #[allow(clippy::reversed_empty_ranges)]
+    fn expand_boundaries() {
+        assert_eq!(expand_to_char_boundaries(TEXT, 1..5), 1..5);
+        assert_eq!(expand_to_char_boundaries(TEXT, 2..6), 1..8);
+        assert_eq!(expand_to_char_boundaries(TEXT, 3..3), 1..5);
+        assert_eq!(expand_to_char_boundaries(TEXT, 7..100), 5..9);
+        assert_eq!(expand_to_char_boundaries(TEXT, 100..200), 9..9);
+        //结束位置在起始位置之前时得到空范围
+        assert_eq!(expand_to_char_boundaries(TEXT, 8..2), 8..8);
+    }
+}